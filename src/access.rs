@@ -0,0 +1,139 @@
+use crate::config::get_config_dir;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// When the ranks add up to more than this, they all decay so old favorites fade out.
+const MAX_TOTAL_RANK: f64 = 10_000.0;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// How often and how recently a try was entered.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct AccessEntry {
+    pub rank: f64,
+    /// Unix timestamp of the last visit.
+    pub last_access: u64,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct AccessFile {
+    #[serde(default)]
+    tries: BTreeMap<String, AccessEntry>,
+}
+
+/// Visits to tries, keyed by each try's full path, for frecency ranking.
+/// The default log lives only in memory.
+#[derive(Default)]
+pub struct AccessLog {
+    path: Option<PathBuf>,
+    tries: BTreeMap<String, AccessEntry>,
+    warning: Option<String>,
+}
+
+/// Returns the default access log, `access.toml` in the config directory.
+pub fn get_access_path() -> PathBuf {
+    get_config_dir().join("access.toml")
+}
+
+fn key(path: &Path) -> String {
+    std::path::absolute(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl AccessLog {
+    /// Loads the log from `path`. A missing file gives an empty log; one
+    /// that can't be parsed is moved to `access.toml.bak` (or the next free
+    /// `.bak.N`) first, or, if that fails, the log stays in memory only. See
+    /// `warning`.
+    pub fn load(path: PathBuf) -> Self {
+        let (file, warning, writable) = utils::load_data_file::<AccessFile>(&path);
+        Self {
+            path: writable.then_some(path),
+            tries: file.tries,
+            warning,
+        }
+    }
+
+    /// What went wrong reading the file, if anything.
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = AccessFile {
+            tries: self.tries.clone(),
+        };
+        let contents = toml::to_string(&file).map_err(std::io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    /// Records a visit to `path` at `now` and saves the log.
+    pub fn record(&mut self, path: &Path, now: u64) -> std::io::Result<()> {
+        let entry = self.tries.entry(key(path)).or_insert(AccessEntry {
+            rank: 0.0,
+            last_access: now,
+        });
+        entry.rank += 1.0;
+        entry.last_access = now;
+
+        let total: f64 = self.tries.values().map(|e| e.rank).sum();
+        if total > MAX_TOTAL_RANK {
+            for entry in self.tries.values_mut() {
+                entry.rank *= 0.9;
+            }
+            self.tries.retain(|_, e| e.rank >= 1.0);
+        }
+        self.save()
+    }
+
+    /// Frecency of `path`: its rank weighted by how recently it was visited,
+    /// as zoxide does. Tries that were never visited score 0.
+    pub fn frecency(&self, path: &Path, now: u64) -> f64 {
+        let Some(entry) = self.tries.get(&key(path)) else {
+            return 0.0;
+        };
+        let age = now.saturating_sub(entry.last_access);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+        entry.rank * weight
+    }
+
+    /// Carries the history over when a try is renamed or moved.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> std::io::Result<()> {
+        match self.tries.remove(&key(from)) {
+            Some(entry) => {
+                self.tries.insert(key(to), entry);
+                self.save()
+            }
+            None => Ok(()),
+        }
+    }
+}
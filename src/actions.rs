@@ -0,0 +1,187 @@
+use crate::cli::Shell;
+use crate::shell::{cd_command, quote};
+use std::path::{Path, PathBuf};
+
+/// What the shell wrapper should do once the binary exits.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Change into `path`.
+    Cd { path: PathBuf },
+    /// Open `path` with the editor command `cmd`.
+    Edit { path: PathBuf, cmd: String },
+    /// Change into `path`, then run the shell command `cmd` there.
+    Run { path: PathBuf, cmd: String },
+    /// Open `path` in a terminal multiplexer by running `argv`.
+    Mux { path: PathBuf, argv: Vec<String> },
+}
+
+/// How actions are printed on stdout.
+pub enum Output {
+    /// Tab-separated action lines, read by the shell integration.
+    Protocol,
+    /// A command for `shell` to eval, for older wrappers and manual use.
+    Eval(Shell),
+}
+
+/// Terminal multiplexers a try can be opened in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Multiplexer {
+    Tmux,
+    Zellij,
+}
+
+/// Escapes a protocol field so it holds no tabs or newlines. Backslashes
+/// are doubled and control characters become `\t`, `\n`, `\r` or `\uXXXX`,
+/// which `printf '%b'`, `[regex]::Unescape` and JSON strings all decode.
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Action {
+    pub fn path(&self) -> &Path {
+        match self {
+            Action::Cd { path }
+            | Action::Edit { path, .. }
+            | Action::Run { path, .. }
+            | Action::Mux { path, .. } => path,
+        }
+    }
+
+    /// The action as one protocol line: its name followed by its fields,
+    /// separated by tabs.
+    pub fn to_line(&self) -> String {
+        let path = self.path().to_string_lossy();
+        let mut fields = match self {
+            Action::Cd { .. } => vec!["cd", &path],
+            Action::Edit { cmd, .. } => vec!["edit", &path, cmd],
+            Action::Run { cmd, .. } => vec!["run", &path, cmd],
+            Action::Mux { .. } => vec!["mux", &path],
+        };
+        if let Action::Mux { argv, .. } = self {
+            fields.extend(argv.iter().map(String::as_str));
+        }
+        fields
+            .into_iter()
+            .map(escape_field)
+            .collect::<Vec<_>>()
+            .join("\t")
+    }
+
+    /// The action as a command for `shell` to eval.
+    pub fn to_command(&self, shell: &Shell) -> String {
+        match self {
+            Action::Cd { path } => cd_command(shell, path, None),
+            Action::Edit { path, cmd } => cd_command(shell, path, Some(cmd)),
+            Action::Run { path, cmd } => {
+                let separator = match shell {
+                    Shell::Bash | Shell::Zsh | Shell::Sh | Shell::Xonsh => " && ",
+                    Shell::Fish => "; and ",
+                    Shell::PowerShell | Shell::NuShell | Shell::Elvish => "; ",
+                };
+                format!("{}{}{}", cd_command(shell, path, None), separator, cmd)
+            }
+            Action::Mux { argv, .. } => {
+                let words: Vec<String> = argv.iter().map(|arg| quote(shell, arg)).collect();
+                match shell {
+                    Shell::PowerShell => format!("& {}", words.join(" ")),
+                    Shell::NuShell => format!("^{}", words.join(" ")),
+                    // A leading string literal would make xonsh read the line as Python.
+                    Shell::Xonsh => format!("![{}]", words.join(" ")),
+                    _ => words.join(" "),
+                }
+            }
+        }
+    }
+
+    pub fn print(&self, output: &Output) {
+        match output {
+            Output::Protocol => println!("{}", self.to_line()),
+            Output::Eval(shell) => println!("{}", self.to_command(shell)),
+        }
+    }
+}
+
+impl Multiplexer {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "tmux" => Some(Multiplexer::Tmux),
+            "zellij" => Some(Multiplexer::Zellij),
+            _ => None,
+        }
+    }
+
+    /// Whether try-rs itself runs inside this multiplexer.
+    pub fn is_running(&self) -> bool {
+        let var = match self {
+            Multiplexer::Tmux => "TMUX",
+            Multiplexer::Zellij => "ZELLIJ",
+        };
+        std::env::var_os(var).is_some()
+    }
+
+    /// The command opening `path` as a window (or tab) named `name` when
+    /// `inside` the multiplexer, or else attaching to a session of that name.
+    pub fn argv(&self, path: &Path, name: &str, inside: bool) -> Vec<String> {
+        let path = path.to_string_lossy().to_string();
+        // Both multiplexers reject '.' and ':' in session names.
+        let session = name.replace(['.', ':'], "_");
+        let args: Vec<&str> = match (self, inside) {
+            (Multiplexer::Tmux, true) => vec!["tmux", "new-window", "-c", &path, "-n", name],
+            (Multiplexer::Tmux, false) => {
+                vec!["tmux", "new-session", "-A", "-s", &session, "-c", &path]
+            }
+            (Multiplexer::Zellij, true) => {
+                vec![
+                    "zellij", "action", "new-tab", "--cwd", &path, "--name", name,
+                ]
+            }
+            (Multiplexer::Zellij, false) => vec![
+                "zellij",
+                "attach",
+                "--create",
+                &session,
+                "options",
+                "--default-cwd",
+                &path,
+            ],
+        };
+        args.into_iter().map(String::from).collect()
+    }
+}
+
+/// Settings that decide what entering a try prints.
+pub struct EnterOptions {
+    pub output: Output,
+    pub editor_cmd: Option<String>,
+    /// Shell command run after changing into a try, from `enter_command`.
+    pub enter_command: Option<String>,
+}
+
+impl EnterOptions {
+    /// The action entering `path`, with the editor when `open_editor` is set.
+    pub fn action(&self, path: &Path, open_editor: bool) -> Action {
+        let path = path.to_path_buf();
+        match (&self.editor_cmd, &self.enter_command) {
+            (Some(cmd), _) if open_editor => Action::Edit {
+                path,
+                cmd: cmd.clone(),
+            },
+            (_, Some(cmd)) => Action::Run {
+                path,
+                cmd: cmd.clone(),
+            },
+            _ => Action::Cd { path },
+        }
+    }
+}
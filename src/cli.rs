@@ -1,11 +1,17 @@
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "try-rs")]
 #[command(about = format!("🦀 try-rs {} 🦀\nA blazing fast, Rust-based workspace manager for your temporary experiments.", env!("CARGO_PKG_VERSION")), long_about = None)]
 #[command(version = env!("CARGO_PKG_VERSION"))]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Cli {
-    /// Create or jump to an experiment / Clone a git URL. Starts TUI if omitted
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// Create or jump to an experiment / Clone a git URL. Starts TUI if omitted.
+    /// Use `try-rs -- NAME` for a name that is also a subcommand, like `list`
     #[arg(value_name = "NAME_OR_URL")]
     pub name_or_url: Option<String>,
 
@@ -29,14 +35,35 @@ pub struct Cli {
     #[arg(long)]
     pub completions: Option<Shell>,
 
+    /// Print experiments matching QUERY ranked as in the picker, without opening it
+    /// (tab-separated: score, display name, match indices, path)
+    #[arg(long, value_name = "QUERY")]
+    pub filter: Option<String>,
+
+    /// Shell that evaluates the printed command, set by the shell integration
+    #[arg(long, value_name = "SHELL", hide = true)]
+    pub shell: Option<Shell>,
+
+    /// Print tab-separated actions instead of a command, for the shell integration
+    #[arg(long, hide = true)]
+    pub actions: bool,
+
     /// Perform a full clone instead of the default shallow clone (--depth 1)
     #[arg(short = 'f', long)]
     pub full_clone: bool,
 
+    /// Populate a new experiment from a template in the config `templates` directory
+    #[arg(short = 't', long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
     /// Create a git worktree from current repository (must be inside a git repo)
     #[arg(short = 'w', long = "worktree", value_name = "WORKTREE_NAME")]
     pub worktree: Option<String>,
 
+    /// Start the new worktree branch at this ref instead of HEAD
+    #[arg(long, value_name = "REF", requires = "worktree")]
+    pub base: Option<String>,
+
     /// Render the picker inline (non-fullscreen), useful for shell key bindings
     #[arg(long)]
     pub inline_picker: bool,
@@ -98,6 +125,148 @@ pub struct Cli {
     pub hide_right_panel: bool,
 }
 
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Print every experiment across all tries directories (names by default)
+    List {
+        /// Print a JSON array with all fields
+        #[arg(long, conflicts_with_all = ["tsv", "format"])]
+        json: bool,
+        /// Print tab-separated values with a header row
+        #[arg(long, conflicts_with = "format")]
+        tsv: bool,
+        /// Print each experiment with a template, e.g. "{name}\t{path}"
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+    },
+    /// Inspect, restore or empty deleted experiments
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// Remove old, large or empty experiments (into the trash)
+    Prune {
+        /// Only experiments at least DAYS old, by date prefix or modification time
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Only experiments larger than MB megabytes
+        #[arg(long, value_name = "MB")]
+        larger_than: Option<u64>,
+        /// Only experiments that are empty or were never modified after creation
+        #[arg(long)]
+        empty: bool,
+        /// Show what would be pruned without removing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rename existing experiments to the configured naming scheme
+    Migrate {
+        /// Show the planned renames without renaming anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Rename without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// List and manage the git worktrees of the current repository
+    Worktree {
+        /// A folder inside the repository, instead of the current directory
+        #[arg(long, value_name = "PATH")]
+        repo: Option<PathBuf>,
+        #[command(subcommand)]
+        command: WorktreeCommand,
+    },
+    /// Check the shell integration, config file and tries directories
+    Doctor,
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Print completion candidates, one per line, for the shell completion scripts
+    #[command(hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        kind: CompletionKind,
+    },
+}
+
+/// What the `complete` subcommand lists.
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompletionKind {
+    /// Folder names, with and without their date prefix
+    Names,
+    /// Local branches of the current repository, for `-w`
+    Branches,
+    /// Theme names
+    Themes,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Report syntax errors, unknown keys and invalid values, exiting non-zero if any
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// List trashed experiments, most recently deleted first
+    List,
+    /// Move a trashed experiment back to where it was deleted from
+    Restore {
+        /// Trash id, or an experiment name (the most recent match wins)
+        #[arg(value_name = "ID_OR_NAME")]
+        target: String,
+    },
+    /// Permanently delete trashed experiments
+    Empty {
+        /// Only delete entries older than the retention period
+        #[arg(long)]
+        expired: bool,
+        /// Empty the trash without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorktreeCommand {
+    /// Print each worktree with its branch, state and kind (main, try or worktree)
+    List,
+    /// Lock a worktree so it is never pruned or moved
+    Lock {
+        /// Path, folder name or branch of the worktree
+        target: String,
+        /// Why the worktree is locked
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Unlock a locked worktree
+    Unlock {
+        /// Path, folder name or branch of the worktree
+        target: String,
+    },
+    /// Remove a worktree and its folder with `git worktree remove`
+    Remove {
+        /// Path, folder name or branch of the worktree
+        target: String,
+        /// Remove it even with uncommitted changes
+        #[arg(long)]
+        force: bool,
+    },
+    /// Drop the entries of worktrees whose folder no longer exists
+    Prune {
+        /// Show what would be pruned without pruning anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the command entering a worktree, for `eval`
+    Jump {
+        /// Path, folder name or branch of the worktree
+        target: String,
+    },
+}
+
 #[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum Shell {
     Fish,
@@ -107,4 +276,8 @@ pub enum Shell {
     NuShell,
     #[allow(clippy::enum_variant_names)]
     PowerShell,
+    Elvish,
+    Xonsh,
+    /// POSIX sh, such as dash or ash
+    Sh,
 }
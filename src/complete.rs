@@ -0,0 +1,66 @@
+use crate::cli::CompletionKind;
+use crate::naming::NameTemplate;
+use crate::themes::Theme;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Folder names in every tries directory. Dated folders are offered both
+/// with and without their date prefix, since either one opens the folder.
+pub fn folder_candidates(tries_dirs: &[PathBuf], name_template: &NameTemplate) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    for dir in tries_dirs {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            if !entry.metadata().map(|m| m.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some((_, stripped, _)) = name_template.extract(&name) {
+                names.insert(stripped);
+            }
+            names.insert(name);
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// Local branches of the repository containing `dir`, for `-w`.
+pub fn branch_candidates(dir: &Path) -> Vec<String> {
+    Command::new("git")
+        .args(["for-each-ref", "--format=%(refname:short)", "refs/heads"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn theme_candidates() -> Vec<String> {
+    Theme::all().into_iter().map(|theme| theme.name).collect()
+}
+
+/// Handles the hidden `complete` subcommand the shell completion scripts call.
+pub fn run_complete_command(
+    kind: CompletionKind,
+    tries_dirs: &[PathBuf],
+    name_template: &NameTemplate,
+) {
+    let candidates = match kind {
+        CompletionKind::Names => folder_candidates(tries_dirs, name_template),
+        CompletionKind::Branches => branch_candidates(Path::new(".")),
+        CompletionKind::Themes => theme_candidates(),
+    };
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+}
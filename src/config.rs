@@ -1,12 +1,24 @@
-use crate::tui::Theme;
+use crate::actions::Multiplexer;
+use crate::cli::ConfigCommand;
+use crate::detectors::{self, Detector};
+use crate::naming::NameTemplate;
+use crate::trash::{DEFAULT_RETENTION_DAYS, get_default_trash_dir};
+use crate::tui::{SortMode, Theme};
 use crate::utils::expand_path;
+use crate::worktrees;
 use serde::Deserialize;
 use serde::Serialize;
+use std::fmt;
 use std::fs;
-use std::io::Write;
+use std::ops::{Range, RangeInclusive};
 use std::path::{Path, PathBuf};
+use toml::de::{DeTable, DeValue};
+use toml_edit::{DocumentMut, Item};
 
-#[derive(Deserialize, Serialize)]
+/// Widths the right panel can take, in percent of the screen.
+pub const RIGHT_PANEL_WIDTH_RANGE: RangeInclusive<u16> = 10..=90;
+
+#[derive(Deserialize, Serialize, Default)]
 pub struct Config {
     pub tries_paths: Option<String>,
     pub tries_path: Option<String>,
@@ -14,12 +26,42 @@ pub struct Config {
     pub editor: Option<String>,
     pub apply_date_prefix: Option<bool>,
     pub date_prefix_format: Option<String>,
+    pub name_template: Option<String>,
     pub transparent_background: Option<bool>,
     pub show_disk: Option<bool>,
     pub show_preview: Option<bool>,
     pub show_legend: Option<bool>,
     pub show_right_panel: Option<bool>,
     pub right_panel_width: Option<u16>,
+    pub hooks: Option<HooksConfig>,
+    pub trash_dir: Option<String>,
+    pub trash_retention_days: Option<u64>,
+    pub prune: Option<PruneConfig>,
+    pub archive_path: Option<String>,
+    pub detectors: Option<Vec<Detector>>,
+    pub default_sort: Option<String>,
+    pub sort_reverse: Option<bool>,
+    pub enter_command: Option<String>,
+    pub multiplexer: Option<String>,
+    pub worktree_copy: Option<Vec<String>>,
+}
+
+/// Shell commands run at points in a try's lifecycle, from the `[hooks]` table.
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct HooksConfig {
+    pub post_create: Option<String>,
+    pub post_clone: Option<String>,
+    pub post_worktree: Option<String>,
+    pub pre_delete: Option<String>,
+    pub on_enter: Option<String>,
+}
+
+/// Default policy for `try-rs prune` and the TUI prune screen, from the `[prune]` table.
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct PruneConfig {
+    pub older_than_days: Option<u64>,
+    pub larger_than_mb: Option<u64>,
+    pub empty: Option<bool>,
 }
 
 pub fn get_file_config_toml_name() -> String {
@@ -48,7 +90,7 @@ pub fn get_base_config_dir() -> PathBuf {
 }
 
 /// Returns candidate config file paths in priority order.
-fn config_candidates() -> Vec<PathBuf> {
+pub fn config_candidates() -> Vec<PathBuf> {
     let config_name = get_file_config_toml_name();
     let mut candidates = Vec::new();
 
@@ -64,15 +106,265 @@ fn config_candidates() -> Vec<PathBuf> {
     candidates
 }
 
+/// Where the TUI offers to create a config file: the candidates without duplicates.
+pub fn config_save_locations() -> Vec<PathBuf> {
+    let mut locations: Vec<PathBuf> = Vec::new();
+    for candidate in config_candidates() {
+        if !locations.contains(&candidate) {
+            locations.push(candidate);
+        }
+    }
+    locations
+}
+
 /// Finds the first existing config file path.
-fn find_config_path() -> Option<PathBuf> {
+pub fn find_config_path() -> Option<PathBuf> {
     config_candidates().into_iter().find(|p| p.exists())
 }
 
-pub fn load_file_config_toml_if_exists() -> Option<Config> {
-    let path = find_config_path()?;
-    let contents = fs::read_to_string(&path).ok()?;
-    toml::from_str::<Config>(&contents).ok()
+/// A problem in the config file, located as precisely as the parser allows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigIssue {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    /// Dotted key the problem is about, e.g. `hooks.post_create`.
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, ": {}", key)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+fn line_of(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())].matches('\n').count() + 1
+}
+
+/// The key at `path` in `table`, and the byte range of its name.
+fn find_key(table: &DeTable, path: &[String]) -> Option<Range<usize>> {
+    let (first, rest) = path.split_first()?;
+    let (key, value) = table.iter().find(|(key, _)| key.get_ref() == first)?;
+    if rest.is_empty() {
+        return Some(key.span());
+    }
+    match value.get_ref() {
+        DeValue::Table(table) => find_key(table, rest),
+        DeValue::Array(items) => {
+            let (index, rest) = rest.split_first()?;
+            match items.get(index.parse::<usize>().ok()?)?.get_ref() {
+                DeValue::Table(table) if !rest.is_empty() => find_key(table, rest),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Every key in `table` as its dotted name and the byte range of its value.
+fn all_keys(table: &DeTable, prefix: &str, keys: &mut Vec<(String, Range<usize>)>) {
+    for (key, value) in table.iter() {
+        let name = format!("{}{}", prefix, key.get_ref());
+        match value.get_ref() {
+            DeValue::Table(table) => all_keys(table, &format!("{}.", name), keys),
+            DeValue::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if let DeValue::Table(table) = item.get_ref() {
+                        all_keys(table, &format!("{}.{}.", name, i), keys);
+                    }
+                }
+            }
+            _ => {}
+        }
+        keys.push((name, value.span()));
+    }
+}
+
+/// The dotted name of the innermost key whose value contains `offset`.
+fn key_at(table: &DeTable, offset: usize) -> Option<String> {
+    let mut keys = Vec::new();
+    all_keys(table, "", &mut keys);
+    keys.into_iter()
+        .filter(|(_, span)| span.contains(&offset))
+        .min_by_key(|(_, span)| span.len())
+        .map(|(name, _)| name)
+}
+
+/// The keys and indices of an ignored field, leaving out `Option` wrappers.
+fn ignored_path(path: &serde_ignored::Path, segments: &mut Vec<String>) {
+    match path {
+        serde_ignored::Path::Root => {}
+        serde_ignored::Path::Seq { parent, index } => {
+            ignored_path(parent, segments);
+            segments.push(index.to_string());
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            ignored_path(parent, segments);
+            segments.push(key.clone());
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent, segments),
+    }
+}
+
+/// Parses the config file at `path`, reporting syntax errors, unknown keys
+/// and invalid values. The config is `None` when it could not be parsed.
+pub fn parse_config(path: &Path, contents: &str) -> (Option<Config>, Vec<ConfigIssue>) {
+    let issue = |line: Option<usize>, key: Option<String>, message: String| ConfigIssue {
+        path: path.to_path_buf(),
+        line,
+        key,
+        message,
+    };
+    let table = match DeTable::parse(contents) {
+        Ok(table) => table,
+        Err(e) => {
+            let line = e.span().map(|span| line_of(contents, span.start));
+            return (None, vec![issue(line, None, e.message().to_string())]);
+        }
+    };
+
+    let mut unknown = Vec::new();
+    let deserializer = toml::de::Deserializer::from(table.clone());
+    let config: Config = match serde_ignored::deserialize(deserializer, |ignored| {
+        let mut segments = Vec::new();
+        ignored_path(&ignored, &mut segments);
+        unknown.push(segments);
+    }) {
+        Ok(config) => config,
+        Err(e) => {
+            let offset = e.span().map(|span| span.start);
+            let line = offset.map(|offset| line_of(contents, offset));
+            let key = offset.and_then(|offset| key_at(table.get_ref(), offset));
+            return (None, vec![issue(line, key, e.message().to_string())]);
+        }
+    };
+
+    let mut issues: Vec<ConfigIssue> = unknown
+        .into_iter()
+        .map(|segments| {
+            let line =
+                find_key(table.get_ref(), &segments).map(|span| line_of(contents, span.start));
+            issue(line, Some(segments.join(".")), "unknown key".to_string())
+        })
+        .collect();
+    let key_line = |key: &str| {
+        find_key(table.get_ref(), &[key.to_string()]).map(|span| line_of(contents, span.start))
+    };
+    if let Some(width) = config.right_panel_width
+        && !RIGHT_PANEL_WIDTH_RANGE.contains(&width)
+    {
+        issues.push(issue(
+            key_line("right_panel_width"),
+            Some("right_panel_width".to_string()),
+            format!(
+                "{} is out of range, expected {} to {}",
+                width,
+                RIGHT_PANEL_WIDTH_RANGE.start(),
+                RIGHT_PANEL_WIDTH_RANGE.end()
+            ),
+        ));
+    }
+    if let Some(name) = &config.theme
+        && !Theme::all().iter().any(|theme| &theme.name == name)
+    {
+        issues.push(issue(
+            key_line("theme"),
+            Some("theme".to_string()),
+            format!(
+                "unknown theme '{}', expected one of: {}",
+                name,
+                Theme::all()
+                    .into_iter()
+                    .map(|theme| theme.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    }
+    if let Err(message) = NameTemplate::from_config(
+        config.name_template.as_deref(),
+        config.apply_date_prefix,
+        config.date_prefix_format.as_deref(),
+    ) {
+        let key = if config.name_template.is_some() {
+            "name_template"
+        } else {
+            "date_prefix_format"
+        };
+        issues.push(issue(key_line(key), Some(key.to_string()), message));
+    }
+    for pattern in config.worktree_copy.iter().flatten() {
+        let message = match glob::Pattern::new(pattern) {
+            Err(e) => format!("invalid pattern '{}': {}", pattern, e.msg),
+            Ok(_) if worktrees::copy_pattern_escapes(pattern) => format!(
+                "pattern '{}' must stay inside the repository (no '..' or absolute paths)",
+                pattern
+            ),
+            Ok(_) => continue,
+        };
+        issues.push(issue(
+            key_line("worktree_copy"),
+            Some("worktree_copy".to_string()),
+            message,
+        ));
+    }
+    issues.sort_by_key(|issue| issue.line);
+    (Some(config), issues)
+}
+
+/// Reads and validates the config file at `path`.
+pub fn check_config_file(path: &Path) -> Vec<ConfigIssue> {
+    match fs::read_to_string(path) {
+        Ok(contents) => parse_config(path, &contents).1,
+        Err(e) => vec![ConfigIssue {
+            path: path.to_path_buf(),
+            line: None,
+            key: None,
+            message: e.to_string(),
+        }],
+    }
+}
+
+/// Handles the `config` subcommand.
+pub fn run_config_command(command: &ConfigCommand) -> bool {
+    match command {
+        ConfigCommand::Check => {
+            let Some(path) = find_config_path() else {
+                eprintln!("No config file found, using defaults");
+                return true;
+            };
+            let issues = check_config_file(&path);
+            for issue in &issues {
+                println!("{}", issue);
+            }
+            if issues.is_empty() {
+                eprintln!("{}: ok", path.display());
+            }
+            issues.is_empty()
+        }
+    }
+}
+
+/// Loads the config file, if there is one, with the problems found in it.
+/// A file that doesn't parse is reported and otherwise ignored.
+pub fn load_file_config_toml_if_exists() -> (Option<Config>, Vec<ConfigIssue>) {
+    let Some(path) = find_config_path() else {
+        return (None, Vec::new());
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse_config(&path, &contents),
+        Err(_) => (None, check_config_file(&path)),
+    }
 }
 
 pub struct AppConfig {
@@ -83,12 +375,36 @@ pub struct AppConfig {
     pub config_path: Option<PathBuf>,
     pub apply_date_prefix: Option<bool>,
     pub date_prefix_format: Option<String>,
+    /// How try folders are named and read back.
+    pub name_template: NameTemplate,
     pub transparent_background: Option<bool>,
     pub show_disk: Option<bool>,
     pub show_preview: Option<bool>,
     pub show_legend: Option<bool>,
     pub show_right_panel: Option<bool>,
     pub right_panel_width: Option<u16>,
+    pub hooks: HooksConfig,
+    pub trash_dir: PathBuf,
+    pub trash_retention_days: u64,
+    pub prune: PruneConfig,
+    pub archive_dir: PathBuf,
+    pub detectors: Vec<Detector>,
+    pub default_sort: SortMode,
+    pub sort_reverse: bool,
+    pub enter_command: Option<String>,
+    pub multiplexer: Option<Multiplexer>,
+    /// Globs copied from the main working tree into new worktrees.
+    pub worktree_copy: Vec<String>,
+    /// Problems found in the config file, to warn about.
+    pub config_issues: Vec<ConfigIssue>,
+}
+
+/// Returns the default archive location, next to other try-rs data.
+pub fn get_default_archive_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .map(|dir| dir.join("try-rs"))
+        .unwrap_or_else(get_config_dir)
+        .join("archive")
 }
 
 pub fn load_configuration() -> AppConfig {
@@ -110,18 +426,31 @@ pub fn load_configuration() -> AppConfig {
         .or_else(|| std::env::var("EDITOR").ok());
     let mut apply_date_prefix = None;
     let mut date_prefix_format = None;
+    let mut name_template = NameTemplate::default();
     let mut transparent_background = None;
     let mut show_disk = None;
     let mut show_preview = None;
     let mut show_legend = None;
     let mut show_right_panel = None;
     let mut right_panel_width = None;
+    let mut hooks = HooksConfig::default();
+    let mut trash_dir = get_default_trash_dir();
+    let mut trash_retention_days = DEFAULT_RETENTION_DAYS;
+    let mut prune = PruneConfig::default();
+    let mut archive_dir = get_default_archive_dir();
+    let mut custom_detectors = Vec::new();
+    let mut default_sort = SortMode::default();
+    let mut sort_reverse = false;
+    let mut enter_command = None;
+    let mut multiplexer = None;
+    let mut worktree_copy = Vec::new();
 
     let loaded_config_path = find_config_path();
 
-    if let Some(config) = load_file_config_toml_if_exists() {
+    let (config, config_issues) = load_file_config_toml_if_exists();
+    if let Some(config) = config {
         let paths_source = config.tries_paths.or(config.tries_path);
-        
+
         if let Some(paths_str) = paths_source
             && !try_path_specified
         {
@@ -135,11 +464,19 @@ pub fn load_configuration() -> AppConfig {
         if let Some(editor) = config.editor {
             editor_cmd = Some(editor);
         }
-        if let Some(theme_name) = config.theme {
-            if let Some(found_theme) = Theme::all().into_iter().find(|t| t.name == theme_name) {
-                theme = found_theme;
-            }
+        if let Some(theme_name) = config.theme
+            && let Some(found_theme) = Theme::all().into_iter().find(|t| t.name == theme_name)
+        {
+            theme = found_theme;
         }
+        // An invalid template was reported by `parse_config`; fall back to the default
+        name_template = NameTemplate::from_config(
+            config.name_template.as_deref(),
+            config.apply_date_prefix,
+            config.date_prefix_format.as_deref(),
+        )
+        .or_else(|_| NameTemplate::from_config(None, config.apply_date_prefix, None))
+        .expect("the default template is valid");
         apply_date_prefix = config.apply_date_prefix;
         date_prefix_format = config.date_prefix_format;
         transparent_background = config.transparent_background;
@@ -148,6 +485,30 @@ pub fn load_configuration() -> AppConfig {
         show_legend = config.show_legend;
         show_right_panel = config.show_right_panel;
         right_panel_width = config.right_panel_width;
+        hooks = config.hooks.unwrap_or_default();
+        if let Some(dir) = config.trash_dir {
+            trash_dir = expand_path(&dir);
+        }
+        if let Some(days) = config.trash_retention_days {
+            trash_retention_days = days;
+        }
+        prune = config.prune.unwrap_or_default();
+        if let Some(dir) = config.archive_path {
+            archive_dir = expand_path(&dir);
+        }
+        custom_detectors = config.detectors.unwrap_or_default();
+        if let Some(mode) = config.default_sort.as_deref().and_then(SortMode::from_name) {
+            default_sort = mode;
+        }
+        sort_reverse = config.sort_reverse.unwrap_or(false);
+        enter_command = config.enter_command.filter(|cmd| !cmd.trim().is_empty());
+        multiplexer = config
+            .multiplexer
+            .as_deref()
+            .and_then(Multiplexer::from_name);
+        // `config check` reports the escaping ones, the rest still apply
+        worktree_copy = config.worktree_copy.unwrap_or_default();
+        worktree_copy.retain(|pattern| !worktrees::copy_pattern_escapes(pattern));
     }
 
     AppConfig {
@@ -158,19 +519,62 @@ pub fn load_configuration() -> AppConfig {
         config_path: loaded_config_path,
         apply_date_prefix,
         date_prefix_format,
+        name_template,
         transparent_background,
         show_disk,
         show_preview,
         show_legend,
         show_right_panel,
         right_panel_width,
+        hooks,
+        trash_dir,
+        trash_retention_days,
+        prune,
+        archive_dir,
+        detectors: detectors::registry(&custom_detectors),
+        default_sort,
+        sort_reverse,
+        enter_command,
+        multiplexer,
+        worktree_copy,
+        config_issues,
+    }
+}
+
+/// Whether `a` and `b` hold the same value, ignoring formatting and comments.
+fn same_value(a: &toml_edit::Value, b: &toml_edit::Value) -> bool {
+    use toml_edit::Value;
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        _ => false,
+    }
+}
+
+/// Sets `key` to `value` unless it already holds it, keeping the comment
+/// after the old value.
+fn set_key(doc: &mut DocumentMut, key: &str, value: impl Into<toml_edit::Value>) {
+    let value = value.into();
+    match doc.get_mut(key).and_then(Item::as_value_mut) {
+        Some(existing) if same_value(existing, &value) => {}
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        None => doc[key] = toml_edit::value(value),
     }
 }
 
+/// Saves the settings the TUI changes by editing the config file in place.
+/// Comments, formatting and keys that are not passed here are left as they
+/// are, and settings passed as `None` are not touched.
+#[allow(clippy::too_many_arguments)]
 pub fn save_config(
     path: &Path,
     theme: &Theme,
-    tries_paths: &[PathBuf],
+    tries_paths: Option<&[PathBuf]>,
     editor: &Option<String>,
     apply_date_prefix: Option<bool>,
     date_prefix_format: Option<String>,
@@ -181,37 +585,74 @@ pub fn save_config(
     show_right_panel: Option<bool>,
     right_panel_width: Option<u16>,
 ) -> std::io::Result<()> {
-    let paths_string = tries_paths
-        .iter()
-        .map(|p| p.to_string_lossy().to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    let config = Config {
-        tries_paths: Some(paths_string),
-        tries_path: tries_paths.first().map(|p| p.to_string_lossy().to_string()),
-        theme: Some(theme.name.clone()),
-        editor: editor.clone(),
-        apply_date_prefix,
-        date_prefix_format,
-        transparent_background,
-        show_disk,
-        show_preview,
-        show_legend,
-        show_right_panel,
-        right_panel_width,
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
     };
+    // A file that doesn't parse is left alone rather than replaced
+    let mut doc = contents
+        .parse::<DocumentMut>()
+        .map_err(std::io::Error::other)?;
 
-    let toml_string =
-        toml::to_string(&config).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    if let Some(tries_paths) = tries_paths {
+        // Keep whichever of the two keys the file uses; `tries_paths` wins
+        // when loading, so it is the one updated when both are present.
+        let paths_key = if doc.contains_key("tries_paths") {
+            "tries_paths"
+        } else {
+            "tries_path"
+        };
+        let paths_unchanged = doc
+            .get(paths_key)
+            .and_then(Item::as_str)
+            .is_some_and(|existing| {
+                existing
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(expand_path)
+                    .eq(tries_paths.iter().cloned())
+            });
+        if !paths_unchanged {
+            let paths_string = tries_paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            set_key(&mut doc, paths_key, paths_string);
+        }
+    }
 
-    if let Some(parent) = path.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent)?;
+    set_key(&mut doc, "theme", theme.name.as_str());
+    if let Some(editor) = editor {
+        set_key(&mut doc, "editor", editor.as_str());
+    }
+    if let Some(format) = date_prefix_format {
+        set_key(&mut doc, "date_prefix_format", format);
+    }
+    let flags = [
+        ("apply_date_prefix", apply_date_prefix),
+        ("transparent_background", transparent_background),
+        ("show_disk", show_disk),
+        ("show_preview", show_preview),
+        ("show_legend", show_legend),
+        ("show_right_panel", show_right_panel),
+    ];
+    for (key, flag) in flags {
+        if let Some(flag) = flag {
+            set_key(&mut doc, key, flag);
         }
     }
+    if let Some(width) = right_panel_width {
+        set_key(&mut doc, "right_panel_width", i64::from(width));
+    }
+
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
 
-    let mut file = fs::File::create(path)?;
-    file.write_all(toml_string.as_bytes())?;
-    Ok(())
+    fs::write(path, doc.to_string())
 }
@@ -0,0 +1,137 @@
+use crate::themes::Theme;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Recognizes a project type by marker files in the root of a try.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Detector {
+    /// Name shown in the legend, e.g. "Rust".
+    pub label: String,
+    pub icon: String,
+    /// A theme icon color (`rust`, `go`, `git`, ... as in `icon_<name>`) or
+    /// a literal color such as `green` or `#a6e3a1`.
+    #[serde(default = "default_color")]
+    pub color: String,
+    /// File names or globs (`*.csproj`); any one of them present is a match.
+    #[serde(default)]
+    pub markers: Vec<String>,
+}
+
+fn default_color() -> String {
+    "file".to_string()
+}
+
+fn detector(label: &str, icon: &str, color: &str, markers: &[&str]) -> Detector {
+    Detector {
+        label: label.to_string(),
+        icon: icon.to_string(),
+        color: color.to_string(),
+        markers: markers.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+/// The project types recognized without any configuration.
+pub fn builtin_detectors() -> Vec<Detector> {
+    vec![
+        detector("Rust", "", "rust", &["Cargo.toml"]),
+        detector("Maven", "", "maven", &["pom.xml"]),
+        detector("Gradle", "", "go", &["build.gradle", "build.gradle.kts"]),
+        detector("Flutter", "", "flutter", &["pubspec.yaml"]),
+        detector("Go", "", "go", &["go.mod"]),
+        detector(
+            "Python",
+            "",
+            "python",
+            &["pyproject.toml", "requirements.txt"],
+        ),
+        detector("Node", "", "worktree", &["package.json"]),
+        detector("Deno", "", "git", &["deno.json", "deno.jsonc"]),
+        detector("Zig", "", "python", &["build.zig"]),
+        detector(
+            ".NET",
+            "󰪮",
+            "gitmodules",
+            &["*.csproj", "*.fsproj", "*.sln"],
+        ),
+        detector("Elixir", "", "gitmodules", &["mix.exs"]),
+        detector("Nix", "", "go", &["flake.nix"]),
+        detector("Mise", "󰬔", "mise", &["mise.toml"]),
+    ]
+}
+
+/// Builds the registry from the built-in detectors and the `[[detectors]]`
+/// config entries. An entry with the label of a built-in replaces it (with no
+/// markers, it disables it); other entries are added after the built-ins.
+pub fn registry(custom: &[Detector]) -> Vec<Detector> {
+    let mut detectors = builtin_detectors();
+    for entry in custom {
+        match detectors
+            .iter_mut()
+            .find(|d| d.label.eq_ignore_ascii_case(&entry.label))
+        {
+            Some(existing) => *existing = entry.clone(),
+            None => detectors.push(entry.clone()),
+        }
+    }
+    detectors.retain(|d| !d.markers.is_empty());
+    detectors
+}
+
+fn is_glob(marker: &str) -> bool {
+    marker.contains(['*', '?', '['])
+}
+
+/// Returns the labels of the detectors matching `dir`, in registry order.
+pub fn detect(detectors: &[Detector], dir: &Path) -> Vec<String> {
+    // Only list the folder when some marker is a glob.
+    let mut file_names: Option<Vec<String>> = None;
+    detectors
+        .iter()
+        .filter(|d| {
+            d.markers.iter().any(|marker| {
+                if !is_glob(marker) {
+                    return dir.join(marker).exists();
+                }
+                let Ok(pattern) = glob::Pattern::new(marker) else {
+                    return false;
+                };
+                file_names
+                    .get_or_insert_with(|| {
+                        fs::read_dir(dir)
+                            .map(|entries| {
+                                entries
+                                    .flatten()
+                                    .map(|e| e.file_name().to_string_lossy().to_string())
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    })
+                    .iter()
+                    .any(|name| pattern.matches(name))
+            })
+        })
+        .map(|d| d.label.clone())
+        .collect()
+}
+
+/// Resolves a detector color against the theme, falling back to the file icon color.
+pub fn resolve_color(color: &str, theme: &Theme) -> Color {
+    match color {
+        "rust" => theme.icon_rust,
+        "maven" => theme.icon_maven,
+        "flutter" => theme.icon_flutter,
+        "go" => theme.icon_go,
+        "python" => theme.icon_python,
+        "mise" => theme.icon_mise,
+        "worktree" => theme.icon_worktree,
+        "worktree_lock" => theme.icon_worktree_lock,
+        "gitmodules" => theme.icon_gitmodules,
+        "git" => theme.icon_git,
+        "folder" => theme.icon_folder,
+        "file" => theme.icon_file,
+        other => Color::from_str(other).unwrap_or(theme.icon_file),
+    }
+}
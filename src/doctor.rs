@@ -0,0 +1,252 @@
+use crate::cli::Shell;
+use crate::config;
+use crate::shell;
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    /// Not broken, but likely not what the user wants.
+    Warning,
+    Problem,
+}
+
+/// The outcome of one diagnostic, with a way to fix it when it isn't ok.
+#[derive(Debug)]
+pub struct Check {
+    pub status: Status,
+    pub summary: String,
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(summary: String) -> Self {
+        Check {
+            status: Status::Ok,
+            summary,
+            fix: None,
+        }
+    }
+
+    fn warning(summary: String, fix: String) -> Self {
+        Check {
+            status: Status::Warning,
+            summary,
+            fix: Some(fix),
+        }
+    }
+
+    fn problem(summary: String, fix: String) -> Self {
+        Check {
+            status: Status::Problem,
+            summary,
+            fix: Some(fix),
+        }
+    }
+}
+
+/// The name `--setup` takes for `shell`, e.g. `nu-shell`.
+fn setup_name(shell: &Shell) -> String {
+    shell
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_else(|| format!("{:?}", shell).to_lowercase())
+}
+
+/// Whether a shell was detected and its integration file is installed.
+pub fn check_shell(shell: Option<&Shell>) -> Check {
+    let Some(shell) = shell else {
+        return Check::warning(
+            format!(
+                "Could not detect the shell from $SHELL ({})",
+                std::env::var("SHELL").unwrap_or_default()
+            ),
+            "Run `try-rs --setup <shell>` for the shell you use".to_string(),
+        );
+    };
+    let path = shell::get_shell_integration_path(shell);
+    if shell::is_shell_integration_configured(shell) {
+        Check::ok(format!(
+            "Shell {:?}, integration file at {}",
+            shell,
+            path.display()
+        ))
+    } else {
+        Check::problem(
+            format!(
+                "Shell {:?}, but there is no integration file at {}",
+                shell,
+                path.display()
+            ),
+            format!("Run `try-rs --setup {}`", setup_name(shell)),
+        )
+    }
+}
+
+/// Whether one of `rc_files` loads the integration of `shell`. Without it the
+/// `try-rs` function never exists, so selecting a folder can't change directory.
+pub fn check_rc_files(shell: &Shell, rc_files: &[PathBuf]) -> Check {
+    let integration = shell::get_shell_integration_path(shell);
+    if rc_files.is_empty() {
+        return Check::ok(format!(
+            "{:?} loads {} by itself",
+            shell,
+            integration.display()
+        ));
+    }
+
+    let source_cmd = shell::source_command(shell, &integration);
+    let integration = integration.to_string_lossy().to_string();
+    let existing: Vec<&PathBuf> = rc_files.iter().filter(|rc| rc.exists()).collect();
+    for rc in &existing {
+        let content = fs::read_to_string(rc).unwrap_or_default();
+        let loads = content.lines().any(|line| {
+            let line = line.trim();
+            !line.starts_with('#') && (line == source_cmd || line.contains(&integration))
+        });
+        if loads {
+            return Check::ok(format!("{} loads the integration", rc.display()));
+        }
+    }
+
+    let rc = existing.first().copied().unwrap_or(&rc_files[0]);
+    let summary = if existing.is_empty() {
+        format!("{} does not exist", rc.display())
+    } else {
+        format!("{} does not load the integration", rc.display())
+    };
+    Check::problem(
+        summary,
+        format!(
+            "Add `{}` to {} and start a new shell, or run `try-rs --setup {}`",
+            source_cmd,
+            rc.display(),
+            setup_name(shell)
+        ),
+    )
+}
+
+/// Which config file is used, and whether it parses.
+pub fn check_config(path: Option<&Path>) -> Check {
+    let Some(path) = path else {
+        let mut candidates: Vec<String> = config::config_candidates()
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        candidates.dedup();
+        return Check::warning(
+            "No config file found, using defaults".to_string(),
+            format!("Create one of: {}", candidates.join(", ")),
+        );
+    };
+    let (config, issues) = match fs::read_to_string(path) {
+        Ok(contents) => config::parse_config(path, &contents),
+        Err(_) => (None, config::check_config_file(path)),
+    };
+    let Some(first) = issues.first() else {
+        return Check::ok(format!("Config file {}", path.display()));
+    };
+    let summary = match issues.len() {
+        1 => first.to_string(),
+        n => format!("{} (and {} more)", first, n - 1),
+    };
+    if config.is_none() {
+        Check::problem(
+            summary,
+            format!(
+                "Fix the error in {}; until then every setting in it is ignored",
+                path.display()
+            ),
+        )
+    } else {
+        Check::warning(
+            summary,
+            "Run `try-rs config check` to list every problem, then fix them".to_string(),
+        )
+    }
+}
+
+/// Whether a tries directory exists and new experiments can be created in it.
+pub fn check_tries_dir(dir: &Path) -> Check {
+    if !dir.exists() {
+        return Check::warning(
+            format!("Tries directory {} does not exist", dir.display()),
+            format!(
+                "Run `mkdir -p '{}'`, or fix `tries_paths` in the config file",
+                dir.display()
+            ),
+        );
+    }
+    if !dir.is_dir() {
+        return Check::problem(
+            format!("Tries directory {} is not a directory", dir.display()),
+            "Point `tries_paths` in the config file at a directory".to_string(),
+        );
+    }
+    let probe = dir.join(format!(".try-rs-doctor-{}", std::process::id()));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Check::ok(format!("Tries directory {} is writable", dir.display()))
+        }
+        Err(e) => Check::problem(
+            format!("Tries directory {} is not writable: {}", dir.display(), e),
+            format!("Fix the permissions of {}", dir.display()),
+        ),
+    }
+}
+
+/// Whether `git` can be run, which cloning and worktrees need.
+pub fn check_git() -> Check {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            Check::ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        _ => Check::warning(
+            "git was not found on PATH".to_string(),
+            "Install git to clone repositories and create worktrees".to_string(),
+        ),
+    }
+}
+
+/// Runs every check in order.
+pub fn run_checks(tries_dirs: &[PathBuf]) -> Vec<Check> {
+    let shell = shell::detect_shell();
+    let mut checks = vec![check_shell(shell.as_ref())];
+    if let Some(shell) = &shell {
+        checks.push(check_rc_files(shell, &shell::get_rc_files(shell)));
+    }
+    checks.push(check_config(config::find_config_path().as_deref()));
+    checks.extend(tries_dirs.iter().map(|dir| check_tries_dir(dir)));
+    checks.push(check_git());
+    checks
+}
+
+/// Handles the `doctor` subcommand. Returns false when a problem was found.
+pub fn run_doctor_command(tries_dirs: &[PathBuf]) -> bool {
+    let checks = run_checks(tries_dirs);
+    for check in &checks {
+        let label = match check.status {
+            Status::Ok => "ok",
+            Status::Warning => "warn",
+            Status::Problem => "FAIL",
+        };
+        println!("[{label:>4}] {}", check.summary);
+        if let Some(fix) = &check.fix {
+            println!("       fix: {fix}");
+        }
+    }
+    let problems = checks
+        .iter()
+        .filter(|check| check.status == Status::Problem)
+        .count();
+    if problems == 0 {
+        eprintln!("No problems found");
+    } else {
+        eprintln!("{problems} problem(s) found");
+    }
+    problems == 0
+}
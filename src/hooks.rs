@@ -0,0 +1,92 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+/// What a hook is told about the try it runs for.
+pub struct HookContext<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    pub source_url: Option<&'a str>,
+}
+
+impl<'a> HookContext<'a> {
+    pub fn new(path: &'a Path, source_url: Option<&'a str>) -> Self {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        Self {
+            name,
+            path,
+            source_url,
+        }
+    }
+}
+
+fn hook_command(cmd: &str, ctx: &HookContext) -> Command {
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", cmd]);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.args(["-c", cmd]);
+        command
+    };
+    command
+        .current_dir(ctx.path)
+        .env("TRY_NAME", ctx.name)
+        .env("TRY_PATH", ctx.path)
+        .env("TRY_SOURCE_URL", ctx.source_url.unwrap_or_default());
+    command
+}
+
+/// Runs a hook in the try directory. Its stdout is sent to stderr so it never
+/// ends up in the output the shell wrapper evaluates.
+pub fn run_hook(cmd: &str, ctx: &HookContext) -> io::Result<ExitStatus> {
+    hook_command(cmd, ctx)
+        .stdout(io::stderr())
+        .stderr(Stdio::inherit())
+        .status()
+}
+
+/// Runs a hook with its output captured, for use while the TUI owns the terminal.
+pub fn run_hook_captured(cmd: &str, ctx: &HookContext) -> io::Result<Output> {
+    hook_command(cmd, ctx).stdin(Stdio::null()).output()
+}
+
+/// Runs the hook `label` if it is configured, warning on stderr when it fails.
+pub fn run_optional_hook(label: &str, cmd: Option<&str>, ctx: &HookContext) {
+    let Some(cmd) = cmd else {
+        return;
+    };
+    match run_hook(cmd, ctx) {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("Warning: {} hook failed ({})", label, status),
+        Err(e) => eprintln!("Warning: could not run {} hook: {}", label, e),
+    }
+}
+
+/// Runs the `pre_delete` hook, if any, for `path`. Returns why the deletion
+/// was vetoed: the first line the hook wrote to stderr, or its exit status.
+pub fn pre_delete_veto(cmd: Option<&str>, path: &Path) -> Option<String> {
+    let output = match run_hook_captured(cmd?, &HookContext::new(path, None)) {
+        Ok(output) => output,
+        Err(e) => return Some(format!("could not run hook: {}", e)),
+    };
+    if output.status.success() {
+        return None;
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Some(
+        stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| output.status.to_string()),
+    )
+}
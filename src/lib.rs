@@ -1,6 +1,20 @@
+pub mod access;
+pub mod actions;
 pub mod cli;
+pub mod complete;
 pub mod config;
+pub mod detectors;
+pub mod doctor;
+pub mod hooks;
+pub mod list;
+pub mod meta;
+pub mod migrate;
+pub mod naming;
+pub mod prune;
 pub mod shell;
+pub mod templates;
 pub mod themes;
+pub mod trash;
 pub mod tui;
 pub mod utils;
+pub mod worktrees;
@@ -0,0 +1,284 @@
+use crate::access::AccessLog;
+use crate::detectors::Detector;
+use crate::meta::MetaStore;
+use crate::naming::NameTemplate;
+use crate::tui::{self, SortMode, TryEntry};
+use crate::utils;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Local, SecondsFormat};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Fields available in `--format` templates, in TSV column order.
+pub const FIELDS: [&str; 13] = [
+    "name",
+    "display_name",
+    "path",
+    "tab",
+    "created",
+    "modified",
+    "project_types",
+    "is_git",
+    "is_worktree",
+    "is_worktree_locked",
+    "size_mb",
+    "tags",
+    "pinned",
+];
+
+/// One try as printed by `try-rs list`.
+#[derive(Serialize, Debug)]
+pub struct ListEntry {
+    pub name: String,
+    pub display_name: String,
+    pub path: PathBuf,
+    /// Index of the tries directory the try lives in.
+    pub tab: usize,
+    pub created: String,
+    pub modified: String,
+    pub project_types: Vec<String>,
+    pub is_git: bool,
+    pub is_worktree: bool,
+    pub is_worktree_locked: bool,
+    pub size_mb: Option<u64>,
+    pub tags: Vec<String>,
+    pub pinned: bool,
+}
+
+pub enum ListOutput {
+    Json,
+    Tsv,
+    Format(String),
+}
+
+enum Segment {
+    Text(String),
+    Field(String),
+}
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl ListEntry {
+    fn new(entry: TryEntry, tries_dir: &Path, tab: usize) -> Self {
+        Self {
+            path: tries_dir.join(&entry.name),
+            tab,
+            created: timestamp(entry.created),
+            modified: timestamp(entry.modified),
+            name: entry.name,
+            display_name: entry.display_name,
+            project_types: entry.project_types,
+            is_git: entry.is_git,
+            is_worktree: entry.is_worktree,
+            is_worktree_locked: entry.is_worktree_locked,
+            size_mb: entry.size_mb,
+            tags: entry.tags,
+            pinned: entry.pinned,
+        }
+    }
+
+    /// The value of `field` as plain text. Lists are comma separated.
+    pub fn field(&self, field: &str) -> Option<String> {
+        Some(match field {
+            "name" => self.name.clone(),
+            "display_name" => self.display_name.clone(),
+            "path" => self.path.to_string_lossy().to_string(),
+            "tab" => self.tab.to_string(),
+            "created" => self.created.clone(),
+            "modified" => self.modified.clone(),
+            "project_types" => self.project_types.join(","),
+            "is_git" => self.is_git.to_string(),
+            "is_worktree" => self.is_worktree.to_string(),
+            "is_worktree_locked" => self.is_worktree_locked.to_string(),
+            "size_mb" => self.size_mb.map(|s| s.to_string()).unwrap_or_default(),
+            "tags" => self.tags.join(","),
+            "pinned" => self.pinned.to_string(),
+            _ => return None,
+        })
+    }
+}
+
+/// Scans every tries directory, each sorted by `sort`. Folder sizes are
+/// only measured when `with_size` is set, since that walks every file.
+pub fn collect_entries(
+    tries_dirs: &[PathBuf],
+    detectors: &[Detector],
+    name_template: &NameTemplate,
+    meta: &MetaStore,
+    access: &AccessLog,
+    sort: (SortMode, bool),
+    with_size: bool,
+) -> Vec<ListEntry> {
+    let mut list = Vec::new();
+    for (tab, dir) in tries_dirs.iter().enumerate() {
+        let mut entries = tui::scan_entries(dir, detectors, name_template, meta, access);
+        tui::sort_entries(&mut entries, dir, sort.0, sort.1);
+        for mut entry in entries {
+            if with_size && entry.size_mb.is_none() {
+                entry.size_mb = Some(utils::get_folder_size_mb(&dir.join(&entry.name)));
+            }
+            list.push(ListEntry::new(entry, dir, tab));
+        }
+    }
+    list
+}
+
+/// Splits a template like `{name}\t{path}` into text and fields, failing on unknown fields.
+fn parse_template(template: &str) -> Result<Vec<Segment>> {
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    let mut segments = Vec::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let field = &rest[start + 1..start + len];
+        if !FIELDS.contains(&field) {
+            bail!(
+                "Unknown field '{{{}}}' in format. Available fields: {}",
+                field,
+                FIELDS.join(", ")
+            );
+        }
+        segments.push(Segment::Text(rest[..start].to_string()));
+        segments.push(Segment::Field(field.to_string()));
+        rest = &rest[start + len + 1..];
+    }
+    segments.push(Segment::Text(rest.to_string()));
+    Ok(segments)
+}
+
+fn render(segments: &[Segment], entry: &ListEntry) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.clone(),
+            Segment::Field(field) => entry.field(field).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Keeps TSV rows intact when a name contains tabs or newlines.
+fn tsv_cell(value: String) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Handles the `list` subcommand.
+pub fn run_list_command(
+    tries_dirs: &[PathBuf],
+    detectors: &[Detector],
+    name_template: &NameTemplate,
+    sort: (SortMode, bool),
+    output: &ListOutput,
+) -> Result<()> {
+    let meta = MetaStore::load(crate::meta::get_meta_path());
+    let access = AccessLog::load(crate::access::get_access_path());
+    for warning in meta.warning().into_iter().chain(access.warning()) {
+        eprintln!("Warning: {}", warning);
+    }
+    let segments = match output {
+        ListOutput::Format(template) => Some(parse_template(template)?),
+        _ => None,
+    };
+    let with_size = match output {
+        ListOutput::Format(template) => template.contains("{size_mb}"),
+        _ => true,
+    };
+    let entries = collect_entries(
+        tries_dirs,
+        detectors,
+        name_template,
+        &meta,
+        &access,
+        sort,
+        with_size,
+    );
+
+    match output {
+        ListOutput::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+        ListOutput::Tsv => {
+            println!("{}", FIELDS.join("\t"));
+            for entry in &entries {
+                let row: Vec<String> = FIELDS
+                    .iter()
+                    .map(|field| tsv_cell(entry.field(field).unwrap_or_default()))
+                    .collect();
+                println!("{}", row.join("\t"));
+            }
+        }
+        ListOutput::Format(_) => {
+            let segments = segments.unwrap_or_default();
+            for entry in &entries {
+                println!("{}", render(&segments, entry));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ranks the tries of every tries directory for `query`, best match first.
+/// Ties keep the tab order, then the sort mode order within each tab.
+pub fn filter_entries(
+    tries_dirs: &[PathBuf],
+    detectors: &[Detector],
+    name_template: &NameTemplate,
+    meta: &MetaStore,
+    access: &AccessLog,
+    sort: (SortMode, bool),
+    query: &str,
+) -> Vec<(PathBuf, TryEntry)> {
+    let matcher = SkimMatcherV2::default();
+    let pinned_first = sort.0 == SortMode::Pinned;
+    let mut ranked = Vec::new();
+    for dir in tries_dirs {
+        let mut entries = tui::scan_entries(dir, detectors, name_template, meta, access);
+        tui::sort_entries(&mut entries, dir, sort.0, sort.1);
+        for entry in tui::rank_entries(&entries, query, &matcher, pinned_first) {
+            ranked.push((dir.join(&entry.name), entry));
+        }
+    }
+    if pinned_first {
+        ranked.sort_by_key(|(_, e)| (std::cmp::Reverse(e.pinned), std::cmp::Reverse(e.score)));
+    } else {
+        ranked.sort_by_key(|(_, e)| std::cmp::Reverse(e.score));
+    }
+    ranked
+}
+
+/// Handles `--filter`: one line per match with its score, display name,
+/// comma-separated match indices into the display name, and path.
+pub fn run_filter_command(
+    tries_dirs: &[PathBuf],
+    detectors: &[Detector],
+    name_template: &NameTemplate,
+    sort: (SortMode, bool),
+    query: &str,
+) {
+    let meta = MetaStore::load(crate::meta::get_meta_path());
+    let access = AccessLog::load(crate::access::get_access_path());
+    for warning in meta.warning().into_iter().chain(access.warning()) {
+        eprintln!("Warning: {}", warning);
+    }
+    for (path, entry) in filter_entries(
+        tries_dirs,
+        detectors,
+        name_template,
+        &meta,
+        &access,
+        sort,
+        query,
+    ) {
+        let indices: Vec<String> = entry.match_indices.iter().map(|i| i.to_string()).collect();
+        println!(
+            "{}\t{}\t{}\t{}",
+            entry.score,
+            tsv_cell(entry.display_name),
+            indices.join(","),
+            path.display()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{Result, anyhow, bail};
 
+use chrono::Local;
 use clap::Parser;
 use crossterm::{
     cursor::MoveTo,
@@ -17,27 +18,53 @@ use std::{
     process::Stdio,
 };
 
+mod access;
+mod actions;
 mod cli;
+mod complete;
 mod config;
+mod detectors;
+mod doctor;
+mod hooks;
+mod list;
+mod meta;
+mod migrate;
+mod naming;
+mod prune;
 mod shell;
+mod templates;
 mod themes;
+mod trash;
 mod tui;
 mod utils;
-
-use cli::{Cli, Shell};
-use config::{AppConfig, load_configuration};
+mod worktrees;
+
+use access::AccessLog;
+use actions::{Action, EnterOptions, Output};
+use cli::{Cli, Commands, Shell};
+use config::{AppConfig, HooksConfig, load_configuration};
+use hooks::{HookContext, run_optional_hook};
+use meta::MetaStore;
+use prune::PrunePolicy;
 use shell::{clear_shell_setup, generate_completions, get_shell_content, setup_shell};
+use trash::Trash;
 use tui::{App, run_app};
+use worktrees::BranchSource;
 
-use crate::utils::{SelectionResult, generate_prefix_date};
+use crate::naming::NameTemplate;
+use crate::utils::SelectionResult;
 
-/// Prints the cd/editor command to stdout for the shell wrapper to eval.
-fn print_cd_or_editor(path: &std::path::Path, open_editor: bool, editor_cmd: &Option<String>) {
-    if open_editor && let Some(cmd) = editor_cmd {
-        println!("{} '{}'", cmd, path.to_string_lossy());
-    } else {
-        println!("cd '{}'", path.to_string_lossy());
+/// Prints the action to stdout for the shell wrapper to run,
+/// and records the visit for frecency ranking.
+fn print_action(action: &Action, output: &Output) {
+    let mut access_log = AccessLog::load(access::get_access_path());
+    if let Some(warning) = access_log.warning() {
+        eprintln!("Warning: {}", warning);
+    }
+    if let Err(e) = access_log.record(action.path(), access::now()) {
+        eprintln!("Warning: could not update the access log: {}", e);
     }
+    action.print(output);
 }
 
 #[cfg(not(windows))]
@@ -118,9 +145,12 @@ fn compute_inline_picker_area(
 /// Handles the --worktree flag: creates a git worktree in the tries dir.
 fn handle_worktree(
     branch_name: &str,
+    base: Option<&str>,
     tries_dir: &std::path::Path,
-    apply_date_prefix: Option<bool>,
-    date_prefix_format: Option<&str>,
+    name_template: &NameTemplate,
+    worktree_copy: &[String],
+    hooks: &HooksConfig,
+    enter: &EnterOptions,
 ) -> Result<()> {
     if !utils::is_inside_git_repo(".") {
         eprintln!("Error: Not inside a git repository.");
@@ -128,54 +158,81 @@ fn handle_worktree(
         std::process::exit(1);
     }
 
-    let mut folder_name = branch_name.to_string();
-    if Some(true) == apply_date_prefix {
-        folder_name = format!("{} {}", generate_prefix_date(date_prefix_format), folder_name);
-    }
+    let folder_name = name_template.new_folder_name(
+        &worktrees::branch_folder_name(branch_name),
+        Local::now().date_naive(),
+    );
 
     let new_path = tries_dir.join(&folder_name);
 
     if new_path.exists() {
         eprintln!("Worktree at '{}' already exists.", folder_name);
-        println!("cd '{}'", new_path.to_string_lossy());
+        run_optional_hook(
+            "on_enter",
+            hooks.on_enter.as_deref(),
+            &HookContext::new(&new_path, None),
+        );
+        print_action(&enter.action(&new_path, false), &enter.output);
         return Ok(());
     }
 
+    let source = worktrees::branch_source(std::path::Path::new("."), branch_name, base)
+        .unwrap_or_else(|e| {
+            eprintln!("Error: Failed to create worktree: {}", e);
+            std::process::exit(1);
+        });
+    match &source {
+        BranchSource::Local if base.is_some() => {
+            eprintln!("Branch '{}' already exists, ignoring --base.", branch_name)
+        }
+        BranchSource::Remote(remote) => eprintln!("Tracking '{}'.", remote),
+        _ => {}
+    }
+
     eprintln!(
         "Creating worktree '{}' at {}...",
         branch_name,
         new_path.display()
     );
 
-    let branch_exists = std::process::Command::new("git")
-        .args(["show-ref", &format!("refs/heads/{branch_name}")])
+    let status = std::process::Command::new("git")
+        .args(worktrees::worktree_add_args(
+            branch_name,
+            &new_path,
+            &source,
+        ))
         .stdout(std::io::stderr())
         .stderr(Stdio::inherit())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or_else(|_| {
-            eprintln!("Error: Failed to create worktree.");
-            std::process::exit(1);
-        });
-
-    let new_path_str = new_path.to_string_lossy();
-    let status = if branch_exists {
-        std::process::Command::new("git")
-            .args(["worktree", "add", &new_path_str, branch_name])
-            .stdout(std::io::stderr())
-            .stderr(Stdio::inherit())
-            .status()
-    } else {
-        std::process::Command::new("git")
-            .args(["worktree", "add", "-b", branch_name, &new_path_str])
-            .stdout(std::io::stderr())
-            .stderr(Stdio::inherit())
-            .status()
-    };
+        .status();
 
     match status {
         Ok(s) if s.success() => {
-            println!("cd '{}'", new_path.to_string_lossy());
+            let source_repo = std::process::Command::new("git")
+                .args(["rev-parse", "--show-toplevel"])
+                .output()
+                .ok()
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+            if !worktree_copy.is_empty() {
+                let copied = worktrees::main_worktree(std::path::Path::new(".")).and_then(|main| {
+                    worktrees::copy_into_worktree(&main, &new_path, worktree_copy)
+                });
+                match copied {
+                    Ok(copied) if !copied.is_empty() => eprintln!(
+                        "Copied from the main worktree: {}",
+                        copied
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Warning: could not copy worktree_copy files: {}", e),
+                }
+            }
+            let ctx = HookContext::new(&new_path, source_repo.as_deref());
+            run_optional_hook("post_worktree", hooks.post_worktree.as_deref(), &ctx);
+            run_optional_hook("on_enter", hooks.on_enter.as_deref(), &ctx);
+            print_action(&enter.action(&new_path, false), &enter.output);
         }
         _ => {
             eprintln!("Error: Failed to create worktree.");
@@ -188,33 +245,18 @@ fn handle_worktree(
 
 /// Detects the current shell and offers to set up shell integration if not configured.
 fn detect_and_setup_shell() -> Result<()> {
-    let shell_type = if cfg!(windows) {
-        Some(Shell::PowerShell)
-    } else if std::env::var("NU_VERSION").is_ok() {
-        Some(Shell::NuShell)
-    } else {
-        let shell = std::env::var("SHELL").unwrap_or_default();
-        if shell.contains("fish") {
-            Some(Shell::Fish)
-        } else if shell.contains("zsh") {
-            Some(Shell::Zsh)
-        } else if shell.contains("bash") {
-            Some(Shell::Bash)
-        } else {
-            None
-        }
-    };
+    let shell_type = shell::detect_shell();
 
-    if let Some(ref s) = shell_type {
-        if !shell::is_shell_integration_configured(s) {
-            eprintln!("Detected shell: {:?}", s);
-            eprint!("Shell integration not configured. Do you want to set it up? [Y/n] ");
-            io::stderr().flush()?;
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            if input.trim().is_empty() || input.trim().eq_ignore_ascii_case("y") {
-                setup_shell(s)?;
-            }
+    if let Some(ref s) = shell_type
+        && !shell::is_shell_integration_configured(s)
+    {
+        eprintln!("Detected shell: {:?}", s);
+        eprint!("Shell integration not configured. Do you want to set it up? [Y/n] ");
+        io::stderr().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim().is_empty() || input.trim().eq_ignore_ascii_case("y") {
+            setup_shell(s)?;
         }
     }
 
@@ -222,21 +264,20 @@ fn detect_and_setup_shell() -> Result<()> {
 }
 
 /// Clones a git repository into the tries directory.
+#[allow(clippy::too_many_arguments)]
 fn handle_clone(
     url: &str,
     destination: Option<String>,
     full_clone: bool,
     tries_dir: &std::path::Path,
-    apply_date_prefix: Option<bool>,
-    date_prefix_format: Option<&str>,
+    name_template: &NameTemplate,
     open_editor: bool,
-    editor_cmd: &Option<String>,
+    enter: &EnterOptions,
+    hooks: &HooksConfig,
 ) -> Result<()> {
     let repo_name = utils::extract_repo_name(url);
-    let mut folder_name = destination.unwrap_or(repo_name);
-    if Some(true) == apply_date_prefix {
-        folder_name = format!("{} {}", generate_prefix_date(date_prefix_format), folder_name);
-    }
+    let folder_name =
+        name_template.new_folder_name(&destination.unwrap_or(repo_name), Local::now().date_naive());
 
     let new_path = tries_dir.join(&folder_name);
     eprintln!("Cloning {} into {}...", url, folder_name);
@@ -257,31 +298,57 @@ fn handle_clone(
         .status();
 
     match status {
-        Ok(s) if s.success() => print_cd_or_editor(&new_path, open_editor, editor_cmd),
+        Ok(s) if s.success() => {
+            let ctx = HookContext::new(&new_path, Some(url));
+            run_optional_hook("post_clone", hooks.post_clone.as_deref(), &ctx);
+            run_optional_hook("on_enter", hooks.on_enter.as_deref(), &ctx);
+            print_action(&enter.action(&new_path, open_editor), &enter.output);
+        }
         _ => eprintln!("Error: Failed to clone the repository."),
     }
 
     Ok(())
 }
 
-/// Creates a new folder in the tries directory.
+/// Creates a new folder in the tries directory, optionally populated from a template.
+#[allow(clippy::too_many_arguments)]
 fn handle_new_folder(
     name: &str,
     tries_dir: &std::path::Path,
-    apply_date_prefix: Option<bool>,
-    date_prefix_format: Option<&str>,
+    name_template: &NameTemplate,
+    template: Option<&str>,
     open_editor: bool,
-    editor_cmd: &Option<String>,
+    enter: &EnterOptions,
+    hooks: &HooksConfig,
 ) -> Result<()> {
-    let mut new_name = name.to_string();
-    let date_prefix = generate_prefix_date(date_prefix_format);
-    if Some(true) == apply_date_prefix && !new_name.starts_with(&date_prefix) {
-        new_name = format!("{date_prefix} {new_name}");
-    }
+    let today = Local::now().date_naive();
+    let new_name = name_template.new_folder_name(name, today);
 
     let new_path = tries_dir.join(&new_name);
+    let created = !new_path.exists();
     fs::create_dir_all(&new_path)?;
-    print_cd_or_editor(&new_path, open_editor, editor_cmd);
+    if let Some(template) = template {
+        // The date in the folder's name, in the same format
+        let (date, plain_name) = match name_template.extract(name) {
+            Some((date, remainder, _)) => (date.unwrap_or(today), remainder),
+            None => (today, name.to_string()),
+        };
+        let vars = templates::TemplateVars::new(&plain_name, &name_template.format_date(date));
+        if let Err(e) =
+            templates::apply_template(&templates::get_templates_dir(), template, &new_path, &vars)
+        {
+            // Don't leave a half-populated experiment behind, but never
+            // remove a try that was already there
+            if created {
+                let _ = fs::remove_dir_all(&new_path);
+            }
+            return Err(e);
+        }
+    }
+    let ctx = HookContext::new(&new_path, None);
+    run_optional_hook("post_create", hooks.post_create.as_deref(), &ctx);
+    run_optional_hook("on_enter", hooks.on_enter.as_deref(), &ctx);
+    print_action(&enter.action(&new_path, open_editor), &enter.output);
     Ok(())
 }
 
@@ -302,14 +369,37 @@ fn main() -> Result<()> {
         config_path,
         apply_date_prefix,
         date_prefix_format,
+        name_template,
         transparent_background,
         show_disk,
         show_preview,
         show_legend,
         show_right_panel,
         right_panel_width,
+        hooks,
+        trash_dir,
+        trash_retention_days,
+        prune,
+        archive_dir,
+        detectors,
+        default_sort,
+        sort_reverse,
+        enter_command,
+        multiplexer,
+        worktree_copy,
+        config_issues,
     }: AppConfig = load_configuration();
 
+    // Completions run on every <Tab>, and `config check` reports the issues itself
+    if !matches!(
+        cli.command,
+        Some(Commands::Complete { .. } | Commands::Config { .. })
+    ) {
+        for issue in &config_issues {
+            eprintln!("Warning: {}", issue);
+        }
+    }
+
     let resolve_visibility = |cli_show: bool, cli_hide: bool, config_show: Option<bool>| -> bool {
         if !cli_hide {
             false
@@ -325,7 +415,10 @@ fn main() -> Result<()> {
     let show_legend = resolve_visibility(cli.show_legend, cli.hide_legend, show_legend);
     let show_right_panel =
         resolve_visibility(cli.show_right_panel, cli.hide_right_panel, show_right_panel);
-    let right_panel_width = right_panel_width.unwrap_or(25).clamp(10, 90);
+    let right_panel_width = right_panel_width.unwrap_or(25).clamp(
+        *config::RIGHT_PANEL_WIDTH_RANGE.start(),
+        *config::RIGHT_PANEL_WIDTH_RANGE.end(),
+    );
 
     // Handle output-only / config-only short-circuits before touching the
     // tries directory. These must work in environments where HOME is not
@@ -351,6 +444,139 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if let Some(query) = &cli.filter {
+        list::run_filter_command(
+            &tries_dirs,
+            &detectors,
+            &name_template,
+            (default_sort, sort_reverse),
+            query,
+        );
+        return Ok(());
+    }
+
+    let trash = Trash::new(trash_dir);
+
+    let enter = EnterOptions {
+        output: if cli.actions {
+            Output::Protocol
+        } else {
+            // Without the shell integration, print a POSIX command.
+            Output::Eval(cli.shell.unwrap_or(Shell::Bash))
+        },
+        editor_cmd: editor_cmd.clone(),
+        enter_command,
+    };
+
+    match &cli.command {
+        Some(Commands::List { json, tsv, format }) => {
+            let output = if *json {
+                list::ListOutput::Json
+            } else if *tsv {
+                list::ListOutput::Tsv
+            } else {
+                list::ListOutput::Format(format.clone().unwrap_or_else(|| "{name}".to_string()))
+            };
+            list::run_list_command(
+                &tries_dirs,
+                &detectors,
+                &name_template,
+                (default_sort, sort_reverse),
+                &output,
+            )?;
+            return Ok(());
+        }
+        Some(Commands::Migrate { dry_run, yes }) => {
+            if !migrate::run_migrate_command(
+                &tries_dirs,
+                &name_template,
+                date_prefix_format.as_deref(),
+                *dry_run,
+                *yes,
+            )? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Worktree { repo, command }) => {
+            let repo = match repo {
+                Some(repo) => repo.clone(),
+                None => std::env::current_dir()?,
+            };
+            if let Some(path) =
+                worktrees::run_worktree_command(command, &repo, &tries_dirs, &name_template)?
+            {
+                run_optional_hook(
+                    "on_enter",
+                    hooks.on_enter.as_deref(),
+                    &HookContext::new(&path, None),
+                );
+                print_action(&enter.action(&path, false), &enter.output);
+            }
+            return Ok(());
+        }
+        Some(Commands::Doctor) => {
+            if !doctor::run_doctor_command(&tries_dirs) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Config { command }) => {
+            if !config::run_config_command(command) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Complete { kind }) => {
+            complete::run_complete_command(*kind, &tries_dirs, &name_template);
+            return Ok(());
+        }
+        Some(Commands::Trash { command }) => {
+            trash::run_trash_command(command, &trash, trash_retention_days)?;
+            return Ok(());
+        }
+        Some(Commands::Prune {
+            older_than,
+            larger_than,
+            empty,
+            dry_run,
+        }) => {
+            let flags = PrunePolicy {
+                older_than_days: *older_than,
+                larger_than_mb: *larger_than,
+                empty: *empty,
+            };
+            let policy = if flags.is_empty() {
+                PrunePolicy::configured(&prune)
+            } else {
+                flags
+            };
+            // Without any criterion only show what the default policy would
+            // remove, a bare `try-rs prune` shouldn't trash anything
+            let (policy, dry_run) = if policy.is_empty() {
+                eprintln!(
+                    "No prune criteria given: pass --older-than, --larger-than or --empty, \
+                     or set them in the [prune] config table. Showing a dry run instead."
+                );
+                (PrunePolicy::from_config(&prune), true)
+            } else {
+                (policy, *dry_run)
+            };
+            if !prune::run_prune_command(
+                &tries_dirs,
+                &policy,
+                dry_run,
+                &trash,
+                hooks.pre_delete.as_deref(),
+                &name_template,
+            ) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
     let tries_dir = tries_dirs[active_tab].clone();
 
     if !tries_dir.exists() {
@@ -358,7 +584,15 @@ fn main() -> Result<()> {
     }
 
     if let Some(ref worktree_branch_name) = cli.worktree {
-        handle_worktree(worktree_branch_name, &tries_dir, apply_date_prefix, date_prefix_format.as_deref())?;
+        handle_worktree(
+            worktree_branch_name,
+            cli.base.as_deref(),
+            &tries_dir,
+            &name_template,
+            &worktree_copy,
+            &hooks,
+            &enter,
+        )?;
         return Ok(());
     }
 
@@ -368,6 +602,7 @@ fn main() -> Result<()> {
 
     let selection_result: SelectionResult;
     let mut open_editor = false;
+    let mut template = cli.template.clone();
     let mut selected_dir = tries_dirs[active_tab].clone();
 
     let (matching_folders, query) = match &cli.name_or_url {
@@ -381,14 +616,11 @@ fn main() -> Result<()> {
 
             let mut all_matches: Vec<(PathBuf, String)> = Vec::new();
             for dir in &tries_dirs {
-                let matches = utils::matching_folders(folder_name, dir);
+                let matches = utils::matching_folders(folder_name, dir, &name_template);
                 all_matches.extend(matches);
             }
 
-            (
-                all_matches,
-                Some(folder_name.to_string()),
-            )
+            (all_matches, Some(folder_name.to_string()))
         }
         None => (Vec::<(PathBuf, String)>::new(), None),
     };
@@ -408,7 +640,7 @@ fn main() -> Result<()> {
         }
     } else {
         let has_multiple_tabs = tries_dirs.len() > 1;
-        
+
         if !has_multiple_tabs && matching_folders.len() > 1 {
             selection_result = matching_folders
                 .into_iter()
@@ -472,6 +704,39 @@ fn main() -> Result<()> {
             app.show_legend = show_legend;
             app.right_panel_visible = show_right_panel;
             app.right_panel_width = right_panel_width;
+            app.selected_template = template.clone();
+            app.hooks = hooks.clone();
+            app.prune_policy = PrunePolicy::from_config(&prune);
+            app.detectors = detectors;
+            app.name_template = name_template.clone();
+            app.access = AccessLog::load(access::get_access_path());
+            app.sort_mode = default_sort;
+            app.sort_reversed = sort_reverse;
+            if let Some(issue) = config_issues.first() {
+                app.status_message = Some(match config_issues.len() {
+                    1 => format!("Config: {}", issue),
+                    n => format!(
+                        "Config: {} (and {} more, see `try-rs config check`)",
+                        issue,
+                        n - 1
+                    ),
+                });
+            }
+            app.set_meta(MetaStore::load(meta::get_meta_path()));
+            if let Some(warning) = app.meta.warning().or(app.access.warning()) {
+                app.status_message = Some(format!("Warning: {}", warning));
+            }
+            match trash.purge_expired(trash_retention_days) {
+                Ok(purged) => {
+                    for entry in purged {
+                        let _ = app.meta.remove(&entry.original_path);
+                    }
+                }
+                Err(e) => eprintln!("Warning: could not purge expired trash: {}", e),
+            }
+            app.trash = Some(trash);
+            app.archive_dir = Some(archive_dir);
+            app.multiplexer = multiplexer;
             let res = run_app(&mut terminal, app);
 
             disable_raw_mode()?;
@@ -494,9 +759,10 @@ fn main() -> Result<()> {
             }
             terminal.show_cursor()?;
 
-            let (result_selection, result_editor, result_tab) = res?;
+            let (result_selection, result_editor, result_tab, result_template) = res?;
             selection_result = result_selection;
             open_editor = result_editor;
+            template = result_template;
             selected_dir = tries_dirs[result_tab].clone();
         }
     }
@@ -504,7 +770,28 @@ fn main() -> Result<()> {
     match selection_result {
         SelectionResult::Folder(selection) => {
             let target_path = selected_dir.join(&selection);
-            print_cd_or_editor(&target_path, open_editor, &editor_cmd);
+            run_optional_hook(
+                "on_enter",
+                hooks.on_enter.as_deref(),
+                &HookContext::new(&target_path, None),
+            );
+            print_action(&enter.action(&target_path, open_editor), &enter.output);
+        }
+        SelectionResult::Multiplexer(selection) => {
+            let target_path = selected_dir.join(&selection);
+            if let Some(multiplexer) = multiplexer {
+                run_optional_hook(
+                    "on_enter",
+                    hooks.on_enter.as_deref(),
+                    &HookContext::new(&target_path, None),
+                );
+                let argv = multiplexer.argv(&target_path, &selection, multiplexer.is_running());
+                let action = Action::Mux {
+                    path: target_path,
+                    argv,
+                };
+                print_action(&action, &enter.output);
+            }
         }
         SelectionResult::New(selection) => {
             if utils::is_git_url(&selection) {
@@ -513,19 +800,20 @@ fn main() -> Result<()> {
                     cli.destination.clone(),
                     cli.full_clone,
                     &selected_dir,
-                    apply_date_prefix,
-                    date_prefix_format.as_deref(),
+                    &name_template,
                     open_editor,
-                    &editor_cmd,
+                    &enter,
+                    &hooks,
                 )?;
             } else {
                 handle_new_folder(
                     &selection,
                     &selected_dir,
-                    apply_date_prefix,
-                    date_prefix_format.as_deref(),
+                    &name_template,
+                    template.as_deref(),
                     open_editor,
-                    &editor_cmd,
+                    &enter,
+                    &hooks,
                 )?;
             }
         }
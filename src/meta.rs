@@ -0,0 +1,133 @@
+use crate::config::get_config_dir;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What the user has attached to a try by hand.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq)]
+pub struct TryMeta {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+impl TryMeta {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.is_none() && !self.pinned
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct MetaFile {
+    #[serde(default)]
+    tries: BTreeMap<String, TryMeta>,
+}
+
+/// Metadata for all tries, kept in one file keyed by each try's full path.
+/// The default store lives only in memory.
+#[derive(Default)]
+pub struct MetaStore {
+    path: Option<PathBuf>,
+    tries: BTreeMap<String, TryMeta>,
+    warning: Option<String>,
+}
+
+/// Returns the default metadata file, `meta.toml` in the config directory.
+pub fn get_meta_path() -> PathBuf {
+    get_config_dir().join("meta.toml")
+}
+
+fn key(path: &Path) -> String {
+    std::path::absolute(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Splits user input like "rust, cli wip" into tags, dropping `#` prefixes and duplicates.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input
+        .split([',', ' '])
+        .map(|t| t.trim().trim_start_matches('#'))
+        .filter(|t| !t.is_empty())
+    {
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+impl MetaStore {
+    /// Loads the store from `path`. A missing file gives an empty store; one
+    /// that can't be parsed is moved to `meta.toml.bak` (or the next free
+    /// `.bak.N`) first, or, if that fails, the store stays in memory only. See
+    /// `warning`.
+    pub fn load(path: PathBuf) -> Self {
+        let (file, warning, writable) = utils::load_data_file::<MetaFile>(&path);
+        Self {
+            path: writable.then_some(path),
+            tries: file.tries,
+            warning,
+        }
+    }
+
+    /// What went wrong reading the file, if anything.
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = MetaFile {
+            tries: self.tries.clone(),
+        };
+        let contents = toml::to_string(&file).map_err(std::io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&TryMeta> {
+        self.tries.get(&key(path))
+    }
+
+    /// Applies `f` to the metadata of `path` and saves the store.
+    pub fn update(&mut self, path: &Path, f: impl FnOnce(&mut TryMeta)) -> std::io::Result<()> {
+        let key = key(path);
+        let meta = self.tries.entry(key.clone()).or_default();
+        f(meta);
+        if meta.is_empty() {
+            self.tries.remove(&key);
+        }
+        self.save()
+    }
+
+    /// Drops the metadata of a try that was deleted for good.
+    pub fn remove(&mut self, path: &Path) -> std::io::Result<()> {
+        match self.tries.remove(&key(path)) {
+            Some(_) => self.save(),
+            None => Ok(()),
+        }
+    }
+
+    /// Carries metadata over when a try is renamed or moved.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> std::io::Result<()> {
+        match self.tries.remove(&key(from)) {
+            Some(meta) => {
+                self.tries.insert(key(to), meta);
+                self.save()
+            }
+            None => Ok(()),
+        }
+    }
+}
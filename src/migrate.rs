@@ -0,0 +1,241 @@
+use crate::access::{self, AccessLog};
+use crate::meta::{self, MetaStore};
+use crate::naming::NameTemplate;
+use crate::utils;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Date-prefixed names used before `name_template`, recognized when migrating.
+pub const LEGACY_TEMPLATES: &[&str] = &[
+    "{date:%Y-%m-%d} {name}",
+    "{date:%Y-%m-%d}_{name}",
+    "{date:%Y_%m_%d}_{name}",
+    "{date:%Y%m%d}_{name}",
+    "{date:%Y-%m-%d}-{name}",
+];
+
+/// Where the date of a renamed folder comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateSource {
+    Prefix,
+    Created,
+    Modified,
+}
+
+impl DateSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateSource::Prefix => "date prefix",
+            DateSource::Created => "created time",
+            DateSource::Modified => "modified time",
+        }
+    }
+}
+
+/// One planned rename.
+#[derive(Debug)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub date_source: DateSource,
+    /// Moved with `git worktree move`, so the worktree's `.git` pointers stay valid.
+    pub is_worktree: bool,
+    /// Why the folder is left alone, when it can't be renamed.
+    pub skip: Option<String>,
+}
+
+/// The names folders may already have: the old `date_prefix_format`, then
+/// `LEGACY_TEMPLATES`.
+pub fn known_templates(date_prefix_format: Option<&str>) -> Vec<NameTemplate> {
+    let mut known: Vec<NameTemplate> = date_prefix_format
+        .and_then(|format| NameTemplate::from_config(None, Some(true), Some(format)).ok())
+        .into_iter()
+        .collect();
+    known.extend(
+        LEGACY_TEMPLATES
+            .iter()
+            .map(|template| NameTemplate::parse(template).expect("legacy templates are valid")),
+    );
+    known
+}
+
+/// Plans renaming every folder in `tries_dir` to `template`. The date comes
+/// from a prefix in one of the `known` formats, or else from the folder's
+/// created or modified time. Folders that already follow `template` and
+/// hidden folders are left out.
+pub fn plan_migration(
+    tries_dir: &Path,
+    template: &NameTemplate,
+    known: &[NameTemplate],
+) -> Vec<Rename> {
+    let Ok(read_dir) = fs::read_dir(tries_dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<(String, fs::Metadata)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_dir())?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            (!name.starts_with('.')).then_some((name, metadata))
+        })
+        .collect();
+    folders.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut taken = HashSet::new();
+    let mut renames = Vec::new();
+    for (name, metadata) in folders {
+        if template.extract(&name).is_some() {
+            continue;
+        }
+        let prefixed = known.iter().find_map(|known| match known.extract(&name) {
+            Some((Some(date), plain, _)) => Some((date, plain)),
+            _ => None,
+        });
+        let (date, plain, date_source) = match prefixed {
+            Some((date, plain)) => (date, plain, DateSource::Prefix),
+            None => {
+                let (time, source) = match metadata.created() {
+                    Ok(time) => (time, DateSource::Created),
+                    Err(_) => match metadata.modified() {
+                        Ok(time) => (time, DateSource::Modified),
+                        Err(_) => continue,
+                    },
+                };
+                let date: NaiveDate = DateTime::<Local>::from(time).date_naive();
+                (date, name.clone(), source)
+            }
+        };
+
+        let from = tries_dir.join(&name);
+        let to = tries_dir.join(template.format(&plain, date));
+        let skip = if to.exists() {
+            Some(format!("{} already exists", to.display()))
+        } else if !taken.insert(to.clone()) {
+            Some("another folder is renamed to the same name".to_string())
+        } else {
+            None
+        };
+        renames.push(Rename {
+            is_worktree: utils::is_git_worktree(&from),
+            from,
+            to,
+            date_source,
+            skip,
+        });
+    }
+    renames
+}
+
+/// Renames a folder. Worktrees are moved with `git worktree move`, and a
+/// repository that has worktrees is repaired afterwards, since both sides
+/// store the other's path.
+pub fn apply_rename(rename: &Rename) -> io::Result<()> {
+    if rename.is_worktree {
+        return utils::move_git_worktree(&rename.from, &rename.to);
+    }
+    fs::rename(&rename.from, &rename.to)?;
+    if rename.to.join(".git").join("worktrees").is_dir() {
+        let output = Command::new("git")
+            .args(["worktree", "repair"])
+            .current_dir(&rename.to)
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "renamed, but `git worktree repair` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Handles the `migrate` subcommand: prints the planned renames and applies
+/// them once confirmed. Returns false when nothing could be migrated to, or
+/// a rename failed.
+pub fn run_migrate_command(
+    tries_dirs: &[PathBuf],
+    template: &NameTemplate,
+    date_prefix_format: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+) -> io::Result<bool> {
+    if !template.applies_to_new() {
+        eprintln!(
+            "No naming scheme is configured: set `name_template`, or `apply_date_prefix = true`, in the config file"
+        );
+        return Ok(false);
+    }
+
+    eprintln!("Migrating tries to `{template}`");
+    let known = known_templates(date_prefix_format);
+    let renames: Vec<Rename> = tries_dirs
+        .iter()
+        .flat_map(|dir| plan_migration(dir, template, &known))
+        .collect();
+    for rename in &renames {
+        let (action, reason) = match &rename.skip {
+            Some(reason) => ("skip", reason.as_str()),
+            None if rename.is_worktree => ("move worktree", rename.date_source.label()),
+            None => ("rename", rename.date_source.label()),
+        };
+        println!(
+            "{action}\t{}\t{}\t{reason}",
+            rename.from.display(),
+            rename.to.display()
+        );
+    }
+
+    let pending: Vec<&Rename> = renames.iter().filter(|r| r.skip.is_none()).collect();
+    if pending.is_empty() {
+        eprintln!("Nothing to migrate");
+        return Ok(true);
+    }
+    if dry_run {
+        return Ok(true);
+    }
+    if !yes {
+        eprint!("Rename {} folder(s)? [y/N] ", pending.len());
+        io::stderr().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            eprintln!("Nothing was renamed");
+            return Ok(true);
+        }
+    }
+
+    let mut meta = MetaStore::load(meta::get_meta_path());
+    let mut access = AccessLog::load(access::get_access_path());
+    for warning in meta.warning().into_iter().chain(access.warning()) {
+        eprintln!("Warning: {}", warning);
+    }
+    let mut renamed = 0;
+    let mut failed = 0;
+    for rename in pending {
+        match apply_rename(rename) {
+            Ok(()) => {
+                renamed += 1;
+                if let Err(e) = meta
+                    .rename(&rename.from, &rename.to)
+                    .and_then(|_| access.rename(&rename.from, &rename.to))
+                {
+                    eprintln!(
+                        "Warning: metadata of {} was not carried over: {}",
+                        rename.from.display(),
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error: could not rename {}: {}", rename.from.display(), e);
+            }
+        }
+    }
+    eprintln!("Renamed {renamed} folder(s)");
+    Ok(failed == 0)
+}
@@ -0,0 +1,255 @@
+use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
+
+use crate::utils::DATE_PREFIX_FORMAT;
+
+/// The template used when `name_template` is not set.
+pub const DEFAULT_NAME_TEMPLATE: &str = "{date:%Y-%m-%d} {name}";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Date(String),
+    Name,
+}
+
+/// How try folders are named, e.g. `{date:%Y%m%d}_{name}`.
+///
+/// `{name}` is the experiment name and `{date:FORMAT}` today's date as a
+/// chrono format string (`{date}` uses `%Y-%m-%d`). A template is only
+/// accepted if `extract` gives back the name and date `format` was given.
+#[derive(Clone, Debug)]
+pub struct NameTemplate {
+    segments: Vec<Segment>,
+    /// Whether new folders are named with the template, or just with the name.
+    applies_to_new: bool,
+}
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        let mut template =
+            NameTemplate::parse(DEFAULT_NAME_TEMPLATE).expect("the default template is valid");
+        template.applies_to_new = false;
+        template
+    }
+}
+
+impl std::fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => write!(f, "{text}")?,
+                Segment::Date(format) => write!(f, "{{date:{format}}}")?,
+                Segment::Name => write!(f, "{{name}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NameTemplate {
+    /// Parses and validates `template`.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            let Some(start) = rest.find(['{', '}']) else {
+                segments.push(Segment::Literal(rest.to_string()));
+                break;
+            };
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            if rest[start..].starts_with('}') {
+                return Err("unexpected '}'".to_string());
+            }
+            let Some(len) = rest[start..].find('}') else {
+                return Err("unclosed '{'".to_string());
+            };
+            let placeholder = &rest[start + 1..start + len];
+            segments.push(match placeholder.split_once(':') {
+                None if placeholder == "name" => Segment::Name,
+                None if placeholder == "date" => Segment::Date(DATE_PREFIX_FORMAT.to_string()),
+                Some(("date", format)) => Segment::Date(format.to_string()),
+                _ => {
+                    return Err(format!(
+                        "unknown placeholder '{{{placeholder}}}', expected {{name}} or {{date:FORMAT}}"
+                    ));
+                }
+            });
+            rest = &rest[start + len + 1..];
+        }
+
+        let count = |wanted: fn(&Segment) -> bool| segments.iter().filter(|s| wanted(s)).count();
+        if count(|s| *s == Segment::Name) != 1 {
+            return Err("expected {name} exactly once".to_string());
+        }
+        if count(|s| matches!(s, Segment::Date(_))) > 1 {
+            return Err("expected {date} at most once".to_string());
+        }
+        if segments
+            .windows(2)
+            .any(|pair| matches!(pair, [Segment::Name, Segment::Date(_)]))
+        {
+            return Err("{name} must be separated from {date}".to_string());
+        }
+        for segment in &segments {
+            if let Segment::Date(format) = segment
+                && StrftimeItems::new(format).any(|item| item == Item::Error)
+            {
+                return Err(format!("invalid date format '{format}'"));
+            }
+        }
+
+        let template = NameTemplate {
+            segments,
+            applies_to_new: true,
+        };
+        template.check_round_trip()?;
+        Ok(template)
+    }
+
+    /// The template from the config file: `name_template`, or else the date
+    /// prefix of `apply_date_prefix` and `date_prefix_format`.
+    pub fn from_config(
+        name_template: Option<&str>,
+        apply_date_prefix: Option<bool>,
+        date_prefix_format: Option<&str>,
+    ) -> Result<Self, String> {
+        if let Some(template) = name_template {
+            return NameTemplate::parse(template);
+        }
+        let mut template = match date_prefix_format {
+            Some(format) => NameTemplate::parse(&format!("{{date:{format}}} {{name}}"))?,
+            None => NameTemplate::default(),
+        };
+        template.applies_to_new = apply_date_prefix == Some(true);
+        Ok(template)
+    }
+
+    /// Whether new folders are named with the template.
+    pub fn applies_to_new(&self) -> bool {
+        self.applies_to_new
+    }
+
+    /// Whether folders named with this template carry a date.
+    pub fn has_date(&self) -> bool {
+        self.segments.iter().any(|s| matches!(s, Segment::Date(_)))
+    }
+
+    /// Fills in the template.
+    pub fn format(&self, name: &str, date: NaiveDate) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Date(format) => date.format(format).to_string(),
+                Segment::Name => name.to_string(),
+            })
+            .collect()
+    }
+
+    /// `date` formatted as the template's `{date}`, or as `%Y-%m-%d` when
+    /// the template has none.
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let format = self
+            .segments
+            .iter()
+            .find_map(|segment| match segment {
+                Segment::Date(format) => Some(format.as_str()),
+                _ => None,
+            })
+            .unwrap_or(DATE_PREFIX_FORMAT);
+        date.format(format).to_string()
+    }
+
+    /// The folder name for a new try called `name`, created on `date`. A
+    /// name that already follows the template is kept as it is.
+    pub fn new_folder_name(&self, name: &str, date: NaiveDate) -> String {
+        if !self.applies_to_new || (self.has_date() && self.extract(name).is_some()) {
+            return name.to_string();
+        }
+        self.format(name, date)
+    }
+
+    /// Reads the date and name back from a folder name, the inverse of
+    /// `format`, along with the char offset of the name in `folder`. Returns
+    /// `None` when `folder` does not follow the template.
+    pub fn extract(&self, folder: &str) -> Option<(Option<NaiveDate>, String, usize)> {
+        let (date, name, from_end) = self.match_segments(0, folder, None, None)?;
+        let offset = folder[..folder.len() - from_end].chars().count();
+        Some((date, name.to_string(), offset))
+    }
+
+    /// `name` is the matched name and how many bytes of the folder name are
+    /// left where it starts.
+    fn match_segments<'a>(
+        &self,
+        index: usize,
+        rest: &'a str,
+        date: Option<NaiveDate>,
+        name: Option<(&'a str, usize)>,
+    ) -> Option<(Option<NaiveDate>, &'a str, usize)> {
+        let Some(segment) = self.segments.get(index) else {
+            let (name, from_end) = name?;
+            return rest.is_empty().then_some((date, name, from_end));
+        };
+        match segment {
+            Segment::Literal(text) => {
+                self.match_segments(index + 1, rest.strip_prefix(text.as_str())?, date, name)
+            }
+            Segment::Date(format) => {
+                let (parsed, remainder) = NaiveDate::parse_and_remainder(rest, format).ok()?;
+                // chrono also accepts unpadded numbers, which `format` never writes.
+                let text = &rest[..rest.len() - remainder.len()];
+                if parsed.format(format).to_string() != text {
+                    return None;
+                }
+                self.match_segments(index + 1, remainder, Some(parsed), name)
+            }
+            // The name can contain anything, so try the longest one first.
+            Segment::Name => (1..=rest.len())
+                .rev()
+                .filter(|&end| rest.is_char_boundary(end))
+                .find_map(|end| {
+                    let name = Some((&rest[..end], rest.len()));
+                    self.match_segments(index + 1, &rest[end..], date, name)
+                }),
+        }
+    }
+
+    /// Checks that `extract` inverts `format` for names that contain the
+    /// template's own separators and dates that take every width.
+    fn check_round_trip(&self) -> Result<(), String> {
+        let mut names = vec![
+            "x".to_string(),
+            "my project".to_string(),
+            "v2".to_string(),
+            "2024-06-15 notes".to_string(),
+        ];
+        for segment in &self.segments {
+            if let Segment::Literal(text) = segment {
+                names.push(format!("a{text}b"));
+                names.push(format!("{text}x"));
+            }
+        }
+        let dates = [(2024, 1, 5), (2031, 12, 25), (1999, 10, 31)]
+            .map(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).expect("valid date"));
+
+        for date in dates {
+            for name in &names {
+                let folder = self.format(name, date);
+                if folder.contains(['/', '\\']) {
+                    return Err(format!("'{folder}' contains a path separator"));
+                }
+                let extracted = self.extract(&folder).map(|(date, name, _)| (date, name));
+                if extracted != Some((self.has_date().then_some(date), name.clone())) {
+                    return Err(format!(
+                        "'{folder}' can't be read back as the name '{name}' and the date {date}"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use crate::config::PruneConfig;
+use crate::hooks;
+use crate::meta::{self, MetaStore};
+use crate::naming::NameTemplate;
+use crate::trash::{Trash, TrashEntry};
+use crate::utils;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Days of inactivity after which a try is pruned when no policy is configured.
+pub const DEFAULT_OLDER_THAN_DAYS: u64 = 30;
+
+/// How long after creation a try may still be written to and count as untouched.
+const UNTOUCHED_GRACE: Duration = Duration::from_secs(60);
+
+/// Which tries to prune. Every criterion that is set must match.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PrunePolicy {
+    pub older_than_days: Option<u64>,
+    pub larger_than_mb: Option<u64>,
+    /// Only tries that are empty or were never modified after creation.
+    pub empty: bool,
+}
+
+impl PrunePolicy {
+    /// The criteria set in the `[prune]` config table, which may be none.
+    pub fn configured(config: &PruneConfig) -> Self {
+        Self {
+            older_than_days: config.older_than_days,
+            larger_than_mb: config.larger_than_mb,
+            empty: config.empty.unwrap_or(false),
+        }
+    }
+
+    /// Builds the policy from the `[prune]` config table, falling back to
+    /// pruning tries older than `DEFAULT_OLDER_THAN_DAYS`.
+    pub fn from_config(config: &PruneConfig) -> Self {
+        let policy = Self::configured(config);
+        if policy.is_empty() {
+            Self {
+                older_than_days: Some(DEFAULT_OLDER_THAN_DAYS),
+                ..policy
+            }
+        } else {
+            policy
+        }
+    }
+
+    /// True when no criterion is set, which would match every try.
+    pub fn is_empty(&self) -> bool {
+        self.older_than_days.is_none() && self.larger_than_mb.is_none() && !self.empty
+    }
+
+    /// Short human-readable summary, e.g. "older than 30d, empty".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(days) = self.older_than_days {
+            parts.push(format!("older than {days}d"));
+        }
+        if let Some(mb) = self.larger_than_mb {
+            parts.push(format!("larger than {mb}MB"));
+        }
+        if self.empty {
+            parts.push("empty or untouched".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PruneCandidate {
+    pub name: String,
+    pub path: PathBuf,
+    pub age_days: u64,
+    pub size_mb: u64,
+    pub is_worktree: bool,
+    pub is_worktree_locked: bool,
+}
+
+pub enum PruneOutcome {
+    Trashed(TrashEntry),
+    Deleted,
+    WorktreeRemoved,
+    Skipped(String),
+    Failed(String),
+}
+
+/// Returns the tries in `tries_dir` matching `policy`, oldest first.
+pub fn find_candidates(
+    tries_dir: &Path,
+    policy: &PrunePolicy,
+    name_template: &NameTemplate,
+) -> Vec<PruneCandidate> {
+    let now = SystemTime::now();
+    let mut candidates: Vec<PruneCandidate> = fs::read_dir(tries_dir)
+        .map(|read_dir| {
+            read_dir
+                .flatten()
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok().filter(|m| m.is_dir())?;
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let path = entry.path();
+
+                    let dated = utils::extract_prefix_date(&name, name_template)
+                        .map(|(date, _, _)| date)
+                        .or_else(|| metadata.modified().ok())
+                        .unwrap_or(now);
+                    let age_days = now
+                        .duration_since(dated)
+                        .map(|d| d.as_secs() / (24 * 60 * 60))
+                        .unwrap_or(0);
+                    if policy.older_than_days.is_some_and(|days| age_days < days) {
+                        return None;
+                    }
+                    if policy.empty && !is_empty_or_untouched(&path, &metadata) {
+                        return None;
+                    }
+                    let size_mb = utils::get_folder_size_mb(&path);
+                    if policy.larger_than_mb.is_some_and(|mb| size_mb <= mb) {
+                        return None;
+                    }
+
+                    Some(PruneCandidate {
+                        name,
+                        is_worktree: utils::is_git_worktree(&path),
+                        is_worktree_locked: utils::is_git_worktree_locked(&path),
+                        path,
+                        age_days,
+                        size_mb,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    candidates.sort_by(|a, b| b.age_days.cmp(&a.age_days).then(a.name.cmp(&b.name)));
+    candidates
+}
+
+/// A try is untouched when nothing in it (outside `.git`) was modified
+/// more than a minute after the folder was created.
+fn is_empty_or_untouched(path: &Path, metadata: &fs::Metadata) -> bool {
+    let Ok(mut entries) = fs::read_dir(path) else {
+        return false;
+    };
+    if entries.next().is_none() {
+        return true;
+    }
+    let Ok(created) = metadata.created() else {
+        return false;
+    };
+    latest_modification(path).is_none_or(|latest| latest <= created + UNTOUCHED_GRACE)
+}
+
+fn latest_modification(path: &Path) -> Option<SystemTime> {
+    let mut latest = None;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(entry.path());
+            }
+            if let Ok(modified) = meta.modified() {
+                latest = latest.max(Some(modified));
+            }
+        }
+    }
+    latest
+}
+
+/// Removes one candidate: worktrees through `git worktree remove` (locked
+/// ones are skipped), everything else into the trash, or permanently when
+/// there is no trash. A failing `pre_delete` hook skips the candidate.
+pub fn remove_candidate(
+    candidate: &PruneCandidate,
+    trash: Option<&Trash>,
+    pre_delete: Option<&str>,
+) -> PruneOutcome {
+    if candidate.is_worktree_locked {
+        return PruneOutcome::Skipped("worktree is locked".to_string());
+    }
+    if let Some(reason) = hooks::pre_delete_veto(pre_delete, &candidate.path) {
+        return PruneOutcome::Skipped(format!("pre_delete hook: {reason}"));
+    }
+
+    if candidate.is_worktree {
+        return match utils::remove_git_worktree(&candidate.path) {
+            Ok(output) if output.status.success() => PruneOutcome::WorktreeRemoved,
+            Ok(output) => PruneOutcome::Failed(
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .take(1)
+                    .collect(),
+            ),
+            Err(e) => PruneOutcome::Failed(e.to_string()),
+        };
+    }
+
+    match trash {
+        Some(trash) => match trash.move_to_trash(&candidate.path) {
+            Ok(entry) => PruneOutcome::Trashed(entry),
+            Err(e) => PruneOutcome::Failed(format!("{e:#}")),
+        },
+        None => match fs::remove_dir_all(&candidate.path) {
+            Ok(()) => PruneOutcome::Deleted,
+            Err(e) => PruneOutcome::Failed(e.to_string()),
+        },
+    }
+}
+
+/// Handles the `prune` subcommand across all tries directories. Returns
+/// false when any matching try could not be removed.
+pub fn run_prune_command(
+    tries_dirs: &[PathBuf],
+    policy: &PrunePolicy,
+    dry_run: bool,
+    trash: &Trash,
+    pre_delete: Option<&str>,
+    name_template: &NameTemplate,
+) -> bool {
+    eprintln!("Pruning tries {}", policy.describe());
+    let mut removed = 0;
+    let mut found = 0;
+    let mut failed = 0;
+    let mut meta = MetaStore::load(meta::get_meta_path());
+    for dir in tries_dirs {
+        for candidate in find_candidates(dir, policy, name_template) {
+            found += 1;
+            let summary = format!(
+                "{}\t{}d\t{}MB",
+                candidate.path.display(),
+                candidate.age_days,
+                candidate.size_mb
+            );
+            if dry_run {
+                let action = if candidate.is_worktree_locked {
+                    "skip (locked worktree)"
+                } else if candidate.is_worktree {
+                    "remove worktree"
+                } else {
+                    "trash"
+                };
+                println!("{action}\t{summary}");
+                continue;
+            }
+            match remove_candidate(&candidate, Some(trash), pre_delete) {
+                PruneOutcome::Trashed(_) => {
+                    removed += 1;
+                    println!("trashed\t{summary}");
+                }
+                PruneOutcome::Deleted => {
+                    removed += 1;
+                    let _ = meta.remove(&candidate.path);
+                    println!("deleted\t{summary}");
+                }
+                PruneOutcome::WorktreeRemoved => {
+                    removed += 1;
+                    let _ = meta.remove(&candidate.path);
+                    println!("removed worktree\t{summary}");
+                }
+                PruneOutcome::Skipped(reason) => println!("skipped ({reason})\t{summary}"),
+                PruneOutcome::Failed(reason) => {
+                    failed += 1;
+                    eprintln!("Error pruning {}: {}", candidate.path.display(), reason);
+                }
+            }
+        }
+    }
+    if dry_run {
+        eprintln!("{found} tries would be pruned (dry run, nothing removed)");
+    } else {
+        eprintln!("Pruned {removed} of {found} matching tries");
+    }
+    failed == 0
+}
@@ -1,11 +1,32 @@
-use crate::cli::Shell;
+use crate::cli::{Cli, Shell};
 use crate::config::{get_base_config_dir, get_config_dir};
 use anyhow::Result;
+use clap::CommandFactory;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const FISH_PICKER_FUNCTION: &str = r#"# Runs the tab-separated actions printed by `try-rs --actions`, one per argument
+function __try_rs_run_actions
+    for line in $argv
+        set -l fields
+        for field in (string split \t -- $line)
+            set -a fields (printf '%b' $field | string collect -N)
+        end
+        switch $fields[1]
+            case cd
+                cd -- $fields[2]
+            case edit
+                eval $fields[3] (string escape -- $fields[2])
+            case run
+                cd -- $fields[2]; and eval $fields[3]
+            case mux
+                command $fields[3..-1]
+        end
+    end
+end
 
-const FISH_PICKER_FUNCTION: &str = r#"function try-rs-picker
+function try-rs-picker
     set -l picker_args --inline-picker
 
     if set -q TRY_RS_PICKER_HEIGHT
@@ -18,11 +39,11 @@ const FISH_PICKER_FUNCTION: &str = r#"function try-rs-picker
         printf "\n"
     end
 
-    set command (command try-rs $picker_args | string collect)
+    set actions (command try-rs --actions $picker_args)
     set command_status $status
 
-    if test $command_status -eq 0; and test -n "$command"
-        eval $command
+    if test $command_status -eq 0
+        __try_rs_run_actions $actions
     end
 
     if status --is-interactive
@@ -32,29 +53,341 @@ const FISH_PICKER_FUNCTION: &str = r#"function try-rs-picker
 end
 "#;
 
+/// `__try_rs_run_actions` for bash and zsh. Positional parameters are the
+/// only array both index the same way.
+const BASH_ZSH_RUN_ACTIONS: &str = r#"# Runs the tab-separated actions printed by `try-rs --actions`, one per line
+__try_rs_run_actions() {
+    local actions=$1 line field
+    while IFS= read -r line; do
+        [ -n "$line" ] || continue
+        # Fields are tab-separated, with tabs, newlines and backslashes escaped
+        set --
+        while :; do
+            printf -v field '%b' "${line%%$'\t'*}"
+            set -- "$@" "$field"
+            [[ $line == *$'\t'* ]] || break
+            line=${line#*$'\t'}
+        done
+        case "$1" in
+            cd) cd -- "$2" ;;
+            edit) eval "$3"' "$2"' ;;
+            run) cd -- "$2" && eval "$3" ;;
+            mux) shift 2; command "$@" ;;
+        esac
+    done <<< "$actions"
+}
+"#;
+
+const ZSH_PICKER_WIDGET: &str = r#"# Inline picker widget, bind it to a key as `try-rs --setup` shows
+try-rs-picker() {
+    local -a picker_args=(--inline-picker)
+    if [[ "${TRY_RS_PICKER_HEIGHT:-}" == <-> ]]; then
+        picker_args+=(--inline-height "$TRY_RS_PICKER_HEIGHT")
+    fi
+
+    # Draw the picker below the prompt, then move back up to redraw it
+    printf '\n'
+    local output
+    output=$(command try-rs --actions "${picker_args[@]}" < /dev/tty)
+    local picker_status=$?
+    printf '\033[A'
+
+    if [ $picker_status -eq 0 ]; then
+        __try_rs_run_actions "$output"
+    fi
+
+    # Refresh prompts that show the current directory
+    local precmd
+    for precmd in $precmd_functions; do
+        "$precmd"
+    done
+    zle reset-prompt
+}
+
+if [[ -o interactive ]]; then
+    zle -N try-rs-picker
+fi
+"#;
+
+const BASH_PICKER_FUNCTION: &str = r#"# Inline picker, bind it to a key as `try-rs --setup` shows
+try-rs-picker() {
+    local -a picker_args=(--inline-picker)
+    if [[ "${TRY_RS_PICKER_HEIGHT:-}" =~ ^[0-9]+$ ]]; then
+        picker_args+=(--inline-height "$TRY_RS_PICKER_HEIGHT")
+    fi
+
+    # Draw the picker below the prompt, then move back up so readline
+    # redraws the prompt line in place
+    printf '\n'
+    local output
+    output=$(command try-rs --actions "${picker_args[@]}" < /dev/tty)
+    local picker_status=$?
+    printf '\033[A'
+
+    if [ $picker_status -eq 0 ]; then
+        __try_rs_run_actions "$output"
+    fi
+}
+"#;
+
+const NUSHELL_PICKER_FUNCTION: &str = r#"# Runs the tab-separated actions printed by `try-rs --actions`
+def --env __try_rs_run_actions [actions: list<string>] {
+    for line in ($actions | where { |line| $line | is-not-empty }) {
+        # Fields are tab-separated; their escapes are valid JSON string escapes
+        let fields = ($line | split row "\t" | each { |field|
+            $field | str replace --all '"' '\"' | $'"($in)"' | from json
+        })
+        let path = ($fields | get 1)
+        match ($fields | first) {
+            "cd" => { cd $path }
+            "edit" => { nu -c $"($fields | get 2) ($path | to nuon)" }
+            "run" => {
+                cd $path
+                nu -c ($fields | get 2)
+            }
+            "mux" => { run-external ($fields | get 2) ...($fields | skip 3) }
+        }
+    }
+}
+
+# Inline picker, bind it to a key as `try-rs --setup` shows
+def --env try-rs-picker [] {
+    let height = ($env.TRY_RS_PICKER_HEIGHT? | default "" | into string)
+    let picker_args = if ($height =~ '^[0-9]+$') {
+        [--inline-picker --inline-height $height]
+    } else {
+        [--inline-picker]
+    }
+    __try_rs_run_actions (^try-rs --actions ...$picker_args | lines)
+}
+"#;
+
+const ELVISH_RUN_ACTIONS: &str = r#"# Runs the tab-separated actions printed by `try-rs --actions`, one per argument
+fn __try_rs_run_actions {|@actions|
+    for line $actions {
+        if (eq $line '') {
+            continue
+        }
+        # Fields are tab-separated; their escapes are valid JSON string escapes
+        var fields = [(str:split "\t" $line | each {|field|
+            echo '"'(str:replace '"' '\"' $field)'"' | from-json
+        })]
+        var path = $fields[1]
+        if (eq $fields[0] cd) {
+            cd $path
+        } elif (eq $fields[0] edit) {
+            eval $fields[2]' $path' &ns=(ns [&path=$path])
+        } elif (eq $fields[0] run) {
+            cd $path
+            eval $fields[2]
+        } elif (eq $fields[0] mux) {
+            (external $fields[2]) (all $fields[3..])
+        }
+    }
+}
+"#;
+
+const XONSH_RUN_ACTIONS: &str = r#"def _try_rs_run_actions(actions):
+    """Runs the tab-separated actions printed by `try-rs --actions`, one per line"""
+    for line in actions:
+        if not line:
+            continue
+        # Fields are tab-separated; their escapes are valid JSON string escapes
+        fields = [
+            _try_rs_json.loads('"' + field.replace('"', '\\"') + '"')
+            for field in line.split('\t')
+        ]
+        path = fields[1]
+        if fields[0] == 'cd':
+            cd @(path)
+        elif fields[0] == 'edit':
+            ![@(_try_rs_shlex.split(fields[2])) @(path)]
+        elif fields[0] == 'run':
+            cd @(path)
+            execx(fields[2])
+        elif fields[0] == 'mux':
+            ![@(fields[2:])]
+"#;
+
+const SH_RUN_ACTIONS: &str = r#"# Runs the tab-separated actions printed by `try-rs --actions`, one per line
+__try_rs_run_actions() {
+    __try_rs_tab=$(printf '\t')
+    while IFS= read -r __try_rs_line; do
+        [ -n "$__try_rs_line" ] || continue
+        # Fields are tab-separated, with tabs, newlines and backslashes escaped
+        set -f
+        __try_rs_ifs=$IFS
+        IFS=$__try_rs_tab
+        set -- $__try_rs_line
+        IFS=$__try_rs_ifs
+        set +f
+        __try_rs_count=$#
+        while [ "$__try_rs_count" -gt 0 ]; do
+            # The trailing x keeps the newlines command substitution strips
+            __try_rs_field=$(printf '%bx' "$1")
+            shift
+            set -- "$@" "${__try_rs_field%x}"
+            __try_rs_count=$((__try_rs_count - 1))
+        done
+        case "$1" in
+            cd) cd -- "$2" ;;
+            edit) eval "$3"' "$2"' ;;
+            run) cd -- "$2" && eval "$3" ;;
+            mux) shift 2; command "$@" ;;
+        esac
+    done <<EOF
+$1
+EOF
+}
+"#;
+
+/// What to add to the shell's config to open the inline picker with Ctrl+T.
+/// Not bound by the integration itself, since Ctrl+T is often taken (fzf uses it).
+pub fn picker_binding(shell: &Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Fish => Some("bind \\ct try-rs-picker\nbind -M insert \\ct try-rs-picker"),
+        Shell::Zsh => Some("bindkey '^T' try-rs-picker"),
+        Shell::Bash => Some(r#"bind -x '"\C-t": try-rs-picker'"#),
+        Shell::NuShell => Some(
+            r#"$env.config.keybindings = ($env.config.keybindings | append {
+    name: try_rs_picker
+    modifier: control
+    keycode: char_t
+    mode: [emacs vi_normal vi_insert]
+    event: { send: executehostcommand cmd: "try-rs-picker" }
+})"#,
+        ),
+        _ => None,
+    }
+}
+
+/// Tells the user how to bind the inline picker, if `shell` has one.
+fn print_picker_binding(shell: &Shell, rc_path: &Path) {
+    if let Some(binding) = picker_binding(shell) {
+        eprintln!(
+            "Optional: append the following to {} to bind the inline picker to Ctrl+T:",
+            rc_path.display()
+        );
+        eprintln!("{}", binding);
+    }
+}
+
+/// Names of the CLI subcommands. Their output is meant for the user, so the
+/// wrappers run them directly instead of evaluating what they print.
+fn subcommand_names() -> Vec<String> {
+    let mut command = Cli::command();
+    command.build();
+    command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect()
+}
+
+/// Quotes `path` as a single literal word for `shell`, so names containing
+/// quotes, `$(...)` or backticks are never expanded by the wrapper's `eval`.
+pub fn quote_path(shell: &Shell, path: &Path) -> String {
+    quote(shell, &path.to_string_lossy())
+}
+
+/// Quotes `word` as a single literal word for `shell`.
+pub fn quote(shell: &Shell, word: &str) -> String {
+    match shell {
+        Shell::Bash | Shell::Zsh | Shell::Sh => format!("'{}'", word.replace('\'', "'\\''")),
+        Shell::Fish => format!("'{}'", word.replace('\\', "\\\\").replace('\'', "\\'")),
+        Shell::Elvish => format!("'{}'", word.replace('\'', "''")),
+        // Python string literal rules: no raw newlines or other control characters.
+        Shell::Xonsh => {
+            let mut quoted = String::from("'");
+            for c in word.chars() {
+                match c {
+                    '\'' => quoted.push_str("\\'"),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('\'');
+            quoted
+        }
+        // PowerShell also treats the typographic single quotes as quote characters.
+        Shell::PowerShell => {
+            let mut quoted = String::from("'");
+            for c in word.chars() {
+                if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+                    quoted.push(c);
+                }
+                quoted.push(c);
+            }
+            quoted.push('\'');
+            quoted
+        }
+        // Plain double-quoted strings are not interpolated in Nushell.
+        Shell::NuShell => {
+            let mut quoted = String::from("\"");
+            for c in word.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+    }
+}
+
+/// Builds the command the shell wrapper evaluates to enter `path`, or to
+/// open it with `editor` when one is given.
+pub fn cd_command(shell: &Shell, path: &Path, editor: Option<&str>) -> String {
+    let quoted = quote_path(shell, path);
+    match (editor, shell) {
+        (Some(editor), _) => format!("{} {}", editor, quoted),
+        // `cd` in PowerShell expands wildcards such as `[` in the path.
+        (None, Shell::PowerShell) => format!("Set-Location -LiteralPath {}", quoted),
+        (None, _) => format!("cd {}", quoted),
+    }
+}
+
 /// Returns the shell integration script content for the given shell type.
 /// This is used by --setup-stdout to print the content to stdout.
 pub fn get_shell_content(shell: &Shell) -> String {
     let completions = get_completions_script(shell);
+    let subcommands = subcommand_names();
     match shell {
         Shell::Fish => {
             format!(
                 r#"function try-rs
-    # Pass flags/options directly to stdout without capturing
+    # Subcommands and flags/options print directly to stdout without capturing
+    if contains -- "$argv[1]" {subcommands}
+        command try-rs $argv
+        return
+    end
     for arg in $argv
+        # After `--` everything is a name, even one like a subcommand
+        if test "$arg" = --
+            break
+        end
         if string match -q -- '-*' $arg
             command try-rs $argv
             return
         end
     end
 
-    # Captures the output of the binary (stdout) which is the "cd" command
+    # Captures the actions the binary prints on stdout, one per line.
     # The TUI is rendered on stderr, so it doesn't interfere.
-    set command (command try-rs $argv | string collect)
+    set actions (command try-rs --actions $argv)
     set command_status $status
 
-    if test $command_status -eq 0; and test -n "$command"
-        eval $command
+    if test $command_status -eq 0
+        __try_rs_run_actions $actions
     end
 end
 
@@ -62,67 +395,90 @@ end
 
 {completions}"#,
                 picker_function = FISH_PICKER_FUNCTION,
+                subcommands = subcommands.join(" "),
             )
         }
         Shell::Zsh => {
             format!(
                 r#"try-rs() {{
-    # Pass flags/options directly to stdout without capturing
+    # Subcommands and flags/options print directly to stdout without capturing
+    case "${{1:-}}" in
+        {subcommands}) command try-rs "$@"; return ;;
+    esac
     for arg in "$@"; do
         case "$arg" in
+            # After `--` everything is a name, even one like a subcommand
+            --) break ;;
             -*) command try-rs "$@"; return ;;
         esac
     done
 
-    # Captures the output of the binary (stdout) which is the "cd" command
+    # Captures the actions the binary prints on stdout, one per line.
     # The TUI is rendered on stderr, so it doesn't interfere.
     local output
-    output=$(command try-rs "$@")
-
-    if [ -n "$output" ]; then
-        eval "$output"
-    fi
+    output=$(command try-rs --actions "$@") || return
+    __try_rs_run_actions "$output"
 }}
 
-{completions}"#
+{run_actions}
+{picker_widget}
+
+{completions}"#,
+                run_actions = BASH_ZSH_RUN_ACTIONS,
+                picker_widget = ZSH_PICKER_WIDGET,
+                subcommands = subcommands.join("|"),
             )
         }
         Shell::Bash => {
             format!(
                 r#"try-rs() {{
-    # Pass flags/options directly to stdout without capturing
+    # Subcommands and flags/options print directly to stdout without capturing
+    case "${{1:-}}" in
+        {subcommands}) command try-rs "$@"; return ;;
+    esac
     for arg in "$@"; do
         case "$arg" in
+            # After `--` everything is a name, even one like a subcommand
+            --) break ;;
             -*) command try-rs "$@"; return ;;
         esac
     done
 
-    # Captures the output of the binary (stdout) which is the "cd" command
+    # Captures the actions the binary prints on stdout, one per line.
     # The TUI is rendered on stderr, so it doesn't interfere.
     local output
-    output=$(command try-rs "$@")
-
-    if [ -n "$output" ]; then
-        eval "$output"
-    fi
+    output=$(command try-rs --actions "$@") || return
+    __try_rs_run_actions "$output"
 }}
 
-{completions}"#
+{run_actions}
+{picker_function}
+
+{completions}"#,
+                run_actions = BASH_ZSH_RUN_ACTIONS,
+                picker_function = BASH_PICKER_FUNCTION,
+                subcommands = subcommands.join("|"),
             )
         }
         Shell::PowerShell => {
             format!(
                 r#"# try-rs integration for PowerShell
 function try-rs {{
-    # Pass flags/options directly to stdout without capturing
+    # Subcommands and flags/options print directly to stdout without capturing
+    if ($args.Count -gt 0 -and @({subcommands}) -contains $args[0]) {{
+        & try-rs.exe @args
+        return
+    }}
     foreach ($a in $args) {{
+        # After `--` everything is a name, even one like a subcommand
+        if ($a -eq '--') {{ break }}
         if ($a -like '-*') {{
             & try-rs.exe @args
             return
         }}
     }}
 
-    # Use file redirect to capture the output path from try-rs.exe.
+    # Use file redirect to capture the actions printed by try-rs.exe.
     # The TUI is rendered on stderr, so it doesn't interfere.
     # On Windows, capturing stdout with $(try-rs.exe @args) can fail after the
     # TUI exits raw mode / alternate screen, causing the "cd" command to print
@@ -130,15 +486,31 @@ function try-rs {{
     # kernel-level handle redirect set up before the process starts, so it
     # is not affected by console mode changes.
     $tempFile = [System.IO.Path]::GetTempFileName()
-    try-rs.exe @args > $tempFile
-    $command = Get-Content $tempFile -Raw
+    try-rs.exe --actions @args > $tempFile
+    $actions = @(Get-Content $tempFile -Encoding UTF8)
     Remove-Item $tempFile -ErrorAction SilentlyContinue
-    if ($command) {{
-        Invoke-Expression $command.Trim()
+
+    # One action per line: tab-separated fields, with tabs, newlines and
+    # backslashes escaped
+    foreach ($line in $actions) {{
+        if (-not $line) {{ continue }}
+        $fields = @($line.Split("`t") | ForEach-Object {{ [regex]::Unescape($_) }})
+        $path = $fields[1]
+        switch ($fields[0]) {{
+            'cd' {{ Set-Location -LiteralPath $path }}
+            'edit' {{ Invoke-Expression "$($fields[2]) `$path" }}
+            'run' {{ Set-Location -LiteralPath $path; Invoke-Expression $fields[2] }}
+            'mux' {{ & $fields[2] @($fields | Select-Object -Skip 3) }}
+        }}
     }}
 }}
 
-{completions}"#
+{completions}"#,
+                subcommands = subcommands
+                    .iter()
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
             )
         }
         Shell::NuShell => {
@@ -149,31 +521,128 @@ function try-rs {{
 ] {{
     let all_args = (if $name_or_url == null {{ [] }} else {{ [$name_or_url] }} | append $args)
 
-    # Pass flags/options directly to stdout without capturing
-    if ($all_args | any {{ |arg| $arg | str starts-with '-' }}) {{
+    # Subcommands and flags/options print directly to stdout without capturing.
+    # After `--` everything is a name, even one like a subcommand.
+    let flags = ($all_args | take while {{ |arg| $arg != '--' }})
+    if ($name_or_url in [{subcommands}]) or ($flags | any {{ |arg| $arg | str starts-with '-' }}) {{
         ^try-rs ...$all_args
         return
     }}
 
-    # Capture output. Stderr (TUI) goes directly to terminal.
-    let output = (^try-rs ...$all_args | str trim)
+    # Capture the actions, one per line. Stderr (TUI) goes directly to terminal.
+    __try_rs_run_actions (^try-rs --actions ...$all_args | lines)
+}}
 
-    if ($output | is-not-empty) {{
-        if ($output | str starts-with "cd ") {{
-            # Grabs the path out of stdout returned by the binary and removes the single quotes
-            let path = ($output | str replace --regex '^cd ' '' | str replace --all "'" "" | str replace --all '"' "")
-            if ($path | path exists) {{
-                cd $path
-            }}
-        }} else {{
-            # If it's not a cd command, it's likely an editor command
-            nu -c $output
-        }}
+{picker_function}
+
+{completions}"#,
+                completions = get_completions_script(shell),
+                picker_function = NUSHELL_PICKER_FUNCTION,
+                subcommands = subcommands
+                    .iter()
+                    .map(|name| format!("\"{name}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        }
+        Shell::Elvish => {
+            format!(
+                r#"# try-rs integration for Elvish, loaded with `use try-rs`
+use str
+
+{run_actions}
+fn try-rs {{|@args|
+    # Subcommands and flags/options print directly to stdout without capturing.
+    # With `--` everything after it is a name, even one like a subcommand.
+    if (or (and (> (count $args) 0) (has-value [{subcommands}] $args[0])) ^
+           (and (not (has-value $args --)) ^
+                (has-value [(each {{|arg| str:has-prefix $arg -}} $args)] $true))) {{
+        e:try-rs $@args
+        return
     }}
+
+    # Captures the actions the binary prints on stdout, one per line.
+    # The TUI is rendered on stderr, so it doesn't interfere.
+    __try_rs_run_actions (e:try-rs --actions $@args)
 }}
 
+# Functions of a module are not visible at the prompt until added to it
+edit:add-var try-rs~ $try-rs~
+
 {completions}"#,
-                completions = get_completions_script(shell),
+                run_actions = ELVISH_RUN_ACTIONS,
+                subcommands = subcommands.join(" "),
+            )
+        }
+        Shell::Xonsh => {
+            format!(
+                r#"# try-rs integration for Xonsh
+import json as _try_rs_json
+import shlex as _try_rs_shlex
+import subprocess as _try_rs_subprocess
+from xonsh.tools import unthreadable as _try_rs_unthreadable
+
+_TRY_RS_SUBCOMMANDS = [{subcommands}]
+
+{run_actions}
+# The TUI needs the terminal, so the alias must not run in a thread
+@_try_rs_unthreadable
+def _try_rs(args):
+    # Subcommands and flags/options print directly to stdout without capturing.
+    # `subprocess` runs the binary, since `try-rs` here is this alias.
+    # After `--` everything is a name, even one like a subcommand.
+    flags = args[: args.index('--')] if '--' in args else args
+    if (args and args[0] in _TRY_RS_SUBCOMMANDS) or any(arg.startswith('-') for arg in flags):
+        return _try_rs_subprocess.call(['try-rs', *args])
+
+    # Captures the actions the binary prints on stdout, one per line.
+    # The TUI is rendered on stderr, so it doesn't interfere.
+    result = _try_rs_subprocess.run(
+        ['try-rs', '--actions', *args], stdout=_try_rs_subprocess.PIPE, text=True
+    )
+    if result.returncode == 0:
+        _try_rs_run_actions(result.stdout.splitlines())
+    return result.returncode
+
+aliases['try-rs'] = _try_rs
+
+{completions}"#,
+                run_actions = XONSH_RUN_ACTIONS,
+                subcommands = subcommands
+                    .iter()
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        }
+        Shell::Sh => {
+            format!(
+                r#"# try-rs integration for POSIX sh (dash, ash, ...)
+{run_actions}
+# POSIX function names cannot contain '-', so `try-rs` is an alias
+try_rs() {{
+    # Subcommands and flags/options print directly to stdout without capturing
+    case "${{1:-}}" in
+        {subcommands}) command try-rs "$@"; return ;;
+    esac
+    for __try_rs_arg in "$@"; do
+        case "$__try_rs_arg" in
+            # After `--` everything is a name, even one like a subcommand
+            --) break ;;
+            -*) command try-rs "$@"; return ;;
+        esac
+    done
+
+    # Captures the actions the binary prints on stdout, one per line.
+    # The TUI is rendered on stderr, so it doesn't interfere.
+    __try_rs_output=$(command try-rs --actions "$@") || return
+    __try_rs_run_actions "$__try_rs_output"
+}}
+
+alias try-rs=try_rs
+{completions}"#,
+                run_actions = SH_RUN_ACTIONS,
+                subcommands = subcommands.join("|"),
             )
         }
     }
@@ -184,277 +653,123 @@ function try-rs {{
 pub fn get_completions_script(shell: &Shell) -> String {
     match shell {
         Shell::Fish => {
-            r#"# try-rs tab completion for directory names
-function __try_rs_get_tries_path
-    # Check TRY_PATH environment variable first
-    if set -q TRY_PATH
-        # Check if contains comma
-        if echo "$TRY_PATH" | command grep -q ","
-            for path in (string split "," $TRY_PATH)
-                printf '%s\n' (string trim $path)
-            end
-        else
-            printf '%s\n' $TRY_PATH
-        end
-        return
-    end
-    
-    # Try to read from config file
-    set -l config_paths "$HOME/.config/try-rs/config.toml" "$HOME/.try-rs/config.toml"
-    for config_path in $config_paths
-        if test -f $config_path
-            # Try tries_path (supports single or multiple paths with comma)
-            set -l tries_path (command grep -E '^\s*tries_path\s*=' $config_path 2>/dev/null | command sed -E 's/.*=[[:space:]]*"?([^"]*)"?.*/\1/' | command sed "s|~|$HOME|" | string trim)
-            if test -n "$tries_path"
-                # Check if it contains comma (multiple paths)
-                if echo "$tries_path" | command grep -q ","
-                    for path in (string split "," $tries_path)
-                        printf '%s\n' (string trim $path)
-                    end
-                else
-                    printf '%s\n' $tries_path
-                end
-                return
-            end
-        end
-    end
-    
-    # Default path
-    printf '%s\n' "$HOME/work/tries"
-end
-
-function __try_rs_complete_directories
-    for tries_path in (__try_rs_get_tries_path)
-        if test -d $tries_path
-            command ls -1 $tries_path 2>/dev/null | while read -l dir
-                if test -d "$tries_path/$dir"
-                    echo $dir
-                end
-            end
-        end
-    end
-end
-
-complete -f -c try-rs -n '__fish_use_subcommand' -a '(__try_rs_complete_directories)' -d 'Try directory'
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
+complete -f -c try-rs -n '__fish_use_subcommand' -a '(command try-rs complete names 2>/dev/null)' -d 'Try directory'
+complete -f -c try-rs -s w -l worktree -x -a '(command try-rs complete branches 2>/dev/null)' -d 'Branch'
 "#.to_string()
         }
         Shell::Zsh => {
-            r#"# try-rs tab completion for directory names
-_try_rs_get_tries_path() {
-    # Check TRY_PATH environment variable first
-    if [[ -n "${TRY_PATH}" ]]; then
-        if [[ "${TRY_PATH}" == *","* ]]; then
-            echo "${TRY_PATH}" | tr ',' '\n'
-        else
-            echo "${TRY_PATH}"
-        fi
-        return
-    fi
-    
-    # Try to read from config file
-    local config_paths=("$HOME/.config/try-rs/config.toml" "$HOME/.try-rs/config.toml")
-    for config_path in "${config_paths[@]}"; do
-        if [[ -f "$config_path" ]]; then
-            # Try tries_path (supports single or multiple paths with comma)
-            local tries_path=$(grep -E '^[[:space:]]*tries_path[[:space:]]*=' "$config_path" 2>/dev/null | sed -E 's/.*=[[:space:]]*"?([^"]*)"?.*/\1/' | sed "s|~|$HOME|" | tr -d '[:space:]')
-            if [[ -n "$tries_path" ]]; then
-                if [[ "$tries_path" == *","* ]]; then
-                    echo "$tries_path" | tr ',' '\n'
-                else
-                    echo "$tries_path"
-                fi
-                return
-            fi
-        fi
-    done
-    
-    # Default path
-    echo "$HOME/work/tries"
-}
-
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
 _try_rs_complete() {
-    local -a dirs=()
-    local tries_path
-
-    while IFS= read -r tries_path; do
-        if [[ -d "$tries_path" ]]; then
-            local -a entries=("$tries_path"/*(N-/))
-            dirs+=("${entries[@]:t}")
-        fi
-    done < <(_try_rs_get_tries_path)
-
-    compadd -a dirs
+    local kind=names
+    case "${words[CURRENT-1]}" in
+        -w|--worktree) kind=branches ;;
+    esac
+
+    local -a candidates
+    candidates=("${(@f)$(command try-rs complete "$kind" 2>/dev/null)}")
+    compadd -a candidates
 }
 
 compdef _try_rs_complete try-rs
 "#.to_string()
         }
         Shell::Bash => {
-            r#"# try-rs tab completion for directory names
-_try_rs_get_tries_path() {
-    # Check TRY_PATH environment variable first
-    if [[ -n "${TRY_PATH}" ]]; then
-        if [[ "${TRY_PATH}" == *","* ]]; then
-            echo "${TRY_PATH}" | tr ',' '\n'
-        else
-            echo "${TRY_PATH}"
-        fi
-        return
-    fi
-    
-    # Try to read from config file
-    local config_paths=("$HOME/.config/try-rs/config.toml" "$HOME/.try-rs/config.toml")
-    for config_path in "${config_paths[@]}"; do
-        if [[ -f "$config_path" ]]; then
-            # Try tries_path (supports single or multiple paths with comma)
-            local tries_path=$(grep -E '^[[:space:]]*tries_path[[:space:]]*=' "$config_path" 2>/dev/null | sed -E 's/.*=[[:space:]]*"?([^"]*)"?.*/\1/' | sed "s|~|$HOME|" | tr -d '[:space:]')
-            if [[ -n "$tries_path" ]]; then
-                if [[ "$tries_path" == *","* ]]; then
-                    echo "$tries_path" | tr ',' '\n'
-                else
-                    echo "$tries_path"
-                fi
-                return
-            fi
-        fi
-    done
-    
-    # Default path
-    echo "$HOME/work/tries"
-}
-
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
 _try_rs_complete() {
     local cur="${COMP_WORDS[COMP_CWORD]}"
-    local tries_paths=$(_try_rs_get_tries_path)
-    local dirs=""
-    
-    # Split by comma if multiple paths
-    IFS=',' read -ra PATH_ARRAY <<< "$tries_paths"
-    
-    for tries_path in "${PATH_ARRAY[@]}"; do
-        # Trim whitespace
-        tries_path=$(echo "$tries_path" | xargs)
-        
-        if [[ -d "$tries_path" ]]; then
-            # Get list of directories
-            while IFS= read -r dir; do
-                if [[ -d "$tries_path/$dir" ]]; then
-                    dirs="$dirs $dir"
-                fi
-            done < <(ls -1 "$tries_path" 2>/dev/null)
+    local kind=names candidate
+    case "${COMP_WORDS[COMP_CWORD-1]}" in
+        -w|--worktree) kind=branches ;;
+    esac
+
+    COMPREPLY=()
+    while IFS= read -r candidate; do
+        if [[ "$candidate" == "$cur"* ]]; then
+            COMPREPLY+=("$candidate")
         fi
-    done
-    
-    COMPREPLY=($(compgen -W "$dirs" -- "$cur"))
+    done < <(command try-rs complete "$kind" 2>/dev/null)
 }
 
 complete -o default -F _try_rs_complete try-rs
 "#.to_string()
         }
         Shell::PowerShell => {
-            r#"# try-rs tab completion for directory names
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
 Register-ArgumentCompleter -CommandName try-rs -ScriptBlock {
     param($wordToComplete, $commandAst, $cursorPosition)
-    
-    # Get tries path from environment variable or default
-    $triesPaths = $env:TRY_PATH
-    if (-not $triesPaths) {
-        # Try to read from config file
-        $configPaths = @(
-            "$env:USERPROFILE/.config/try-rs/config.toml",
-            "$env:USERPROFILE/.try-rs/config.toml"
-        )
-        foreach ($configPath in $configPaths) {
-            if (Test-Path $configPath) {
-                $content = Get-Content $configPath -Raw
-                # Try tries_path (supports single or multiple paths with comma)
-                if ($content -match 'tries_path\s*=\s*["'']?([^"'']+)["'']?') {
-                    $triesPaths = $matches[1].Replace('~', $env:USERPROFILE).Trim()
-                    break
-                }
-            }
-        }
-    }
-    
-    # Default path
-    if (-not $triesPaths) {
-        $triesPaths = "$env:USERPROFILE/work/tries"
+
+    $previous = $commandAst.CommandElements |
+        Where-Object { $_.Extent.EndOffset -lt $cursorPosition } |
+        Select-Object -Last 1
+    $kind = switch ("$previous") {
+        { $_ -in '-w', '--worktree' } { 'branches' }
+        default { 'names' }
     }
-    
-    # Split by comma if multiple paths
-    $pathArray = $triesPaths -split ','
-    
-    # Get directories from all paths
-    foreach ($triesPath in $pathArray) {
-        $triesPath = $triesPath.Trim()
-        if (Test-Path $triesPath) {
-            Get-ChildItem -Path $triesPath -Directory | 
-                Where-Object { $_.Name -like "$wordToComplete*" } |
-                ForEach-Object { 
-                    [System.Management.Automation.CompletionResult]::new(
-                        $_.Name, 
-                        $_.Name, 
-                        'ParameterValue', 
-                        $_.Name
-                    )
-                }
+
+    & try-rs.exe complete $kind 2>$null |
+        Where-Object { $_ -like "$wordToComplete*" } |
+        ForEach-Object {
+            $text = if ($_ -match '\s') { "'" + ($_ -replace "'", "''") + "'" } else { $_ }
+            [System.Management.Automation.CompletionResult]::new(
+                $text,
+                $_,
+                'ParameterValue',
+                $_
+            )
         }
-    }
 }
 "#.to_string()
         }
         Shell::NuShell => {
-            r#"# try-rs tab completion for directory names
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
 # Add this to your Nushell config or env file
 
-export def __try_rs_get_tries_paths [] {
-    # Check TRY_PATH environment variable first
-    if ($env.TRY_PATH? | is-not-empty) {
-        return ($env.TRY_PATH | split row "," | each { |s| $s | str trim })
-    }
-    
-    # Try to read from config file
-    let config_paths = [
-        ($env.HOME | path join ".config" "try-rs" "config.toml"),
-        ($env.HOME | path join ".try-rs" "config.toml")
-    ]
-    
-    for config_path in $config_paths {
-        if ($config_path | path exists) {
-            let content = (open $config_path | str trim)
-            # Try tries_path (supports single or multiple paths with comma)
-            if ($content =~ 'tries_path\\s*=\\s*"?([^"]+)"?') {
-                let path = ($content | parse -r 'tries_path\\s*=\\s*"?([^"]+)"?' | get capture0.0? | default "")
-                if ($path | is-not-empty) {
-                    # Check if contains comma (multiple paths)
-                    if ($path | str contains ",") {
-                        return ($path | split row "," | each { |s| ($s | str trim | str replace "~" $env.HOME) })
-                    else
-                        return ([($path | str replace "~" $env.HOME)])
-                    }
-                }
-            }
-        }
-    }
-    
-    # Default path
-    [($env.HOME | path join "work" "tries")]
+export def __try_rs_complete [context: string] {
+    ^try-rs complete names | lines
 }
 
-export def __try_rs_complete [context: string] {
-    let tries_paths = (__try_rs_get_tries_paths)
-    
-    mut all_dirs = []
-    for tries_path in $tries_paths {
-        if ($tries_path | path exists) {
-            let dirs = (ls $tries_path | where type == "dir" | get name | path basename)
-            $all_dirs = ($all_dirs | append $dirs)
+export def __try_rs_complete_branches [] {
+    ^try-rs complete branches | lines
+}
+"#.to_string()
+        }
+        Shell::Elvish => {
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
+set edit:completion:arg-completer[try-rs] = {|@words|
+    var kind = names
+    if (> (count $words) 2) {
+        var previous = $words[-2]
+        if (has-value [-w --worktree] $previous) {
+            set kind = branches
         }
     }
-    $all_dirs
+    e:try-rs complete $kind 2>/dev/null | from-lines
 }
 "#.to_string()
         }
+        Shell::Xonsh => {
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
+import subprocess as _try_rs_subprocess
+from xonsh.completers.completer import add_one_completer as _try_rs_add_completer
+from xonsh.completers.tools import contextual_command_completer_for as _try_rs_completer_for
+
+@_try_rs_completer_for('try-rs')
+def _try_rs_complete(command):
+    previous = command.args[command.arg_index - 1].value
+    kind = {'-w': 'branches', '--worktree': 'branches'}.get(previous, 'names')
+    result = _try_rs_subprocess.run(
+        ['try-rs', 'complete', kind],
+        stdout=_try_rs_subprocess.PIPE,
+        stderr=_try_rs_subprocess.DEVNULL,
+        text=True,
+    )
+    return {c for c in result.stdout.splitlines() if c.startswith(command.prefix)}
+
+_try_rs_add_completer('try_rs', _try_rs_complete, 'start')
+"#.to_string()
+        }
+        Shell::Sh => "# POSIX sh has no programmable tab completion\n".to_string(),
     }
 }
 
@@ -464,42 +779,15 @@ pub fn get_completion_script_only(shell: &Shell) -> String {
     match shell {
         Shell::NuShell => {
             // For NuShell, we need to provide a different format when used standalone
-            r#"# try-rs tab completion for directory names
+            r#"# try-rs tab completion, with candidates listed by `try-rs complete`
 # Add this to your Nushell config
 
-def __try_rs_get_tries_path [] {
-    if ($env.TRY_PATH? | is-not-empty) {
-        return $env.TRY_PATH
-    }
-    
-    let config_paths = [
-        ($env.HOME | path join ".config" "try-rs" "config.toml"),
-        ($env.HOME | path join ".try-rs" "config.toml")
-    ]
-    
-    for config_path in $config_paths {
-        if ($config_path | path exists) {
-            let content = (open $config_path | str trim)
-            if ($content =~ 'tries_path\\s*=\\s*"?([^"]+)"?') {
-                let path = ($content | parse -r 'tries_path\\s*=\\s*"?([^"]+)"?' | get capture0.0? | default "")
-                if ($path | is-not-empty) {
-                    return ($path | str replace "~" $env.HOME)
-                }
-            }
-        }
-    }
-    
-    ($env.HOME | path join "work" "tries")
+def __try_rs_complete [context: string] {
+    ^try-rs complete names | lines
 }
 
-def __try_rs_complete [context: string] {
-    let tries_path = (__try_rs_get_tries_path)
-    
-    if ($tries_path | path exists) {
-        ls $tries_path | where type == "dir" | get name | path basename
-    } else {
-        []
-    }
+def __try_rs_complete_branches [] {
+    ^try-rs complete branches | lines
 }
 
 # Register completion
@@ -510,9 +798,10 @@ export extern try-rs [
     --setup-stdout: string
     --completions: string
     --shallow-clone(-s)
-    --worktree(-w): string
+    --worktree(-w): string@__try_rs_complete_branches
 ]
-"#.to_string()
+"#
+            .to_string()
         }
         _ => completions,
     }
@@ -520,7 +809,7 @@ export extern try-rs [
 
 pub fn get_shell_integration_path(shell: &Shell) -> PathBuf {
     let config_dir = match shell {
-        Shell::Fish => get_base_config_dir(),
+        Shell::Fish | Shell::Elvish => get_base_config_dir(),
         _ => get_config_dir(),
     };
 
@@ -530,6 +819,10 @@ pub fn get_shell_integration_path(shell: &Shell) -> PathBuf {
         Shell::Bash => config_dir.join("try-rs.bash"),
         Shell::PowerShell => config_dir.join("try-rs.ps1"),
         Shell::NuShell => config_dir.join("try-rs.nu"),
+        // A module in Elvish's library directory, so `use try-rs` finds it.
+        Shell::Elvish => config_dir.join("elvish").join("lib").join("try-rs.elv"),
+        Shell::Xonsh => config_dir.join("try-rs.xsh"),
+        Shell::Sh => config_dir.join("try-rs.sh"),
     }
 }
 
@@ -537,13 +830,12 @@ fn get_fish_functions_dir() -> PathBuf {
     if let Ok(output) = std::process::Command::new("fish")
         .args(["-c", "echo $__fish_config_dir"])
         .output()
+        && output.status.success()
     {
-        if output.status.success() {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let path = PathBuf::from(output_str.trim()).join("functions");
-            if path.exists() || path.parent().map(|p| p.exists()).unwrap_or(false) {
-                return path;
-            }
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        let path = PathBuf::from(output_str.trim()).join("functions");
+        if path.exists() || path.parent().map(|p| p.exists()).unwrap_or(false) {
+            return path;
         }
     }
     get_base_config_dir().join("fish").join("functions")
@@ -564,6 +856,32 @@ fn write_fish_picker_function() -> Result<PathBuf> {
     Ok(file_path)
 }
 
+/// The shell try-rs was started from, from `$SHELL` (or Nushell's own variable).
+pub fn detect_shell() -> Option<Shell> {
+    if cfg!(windows) {
+        Some(Shell::PowerShell)
+    } else if std::env::var("NU_VERSION").is_ok() {
+        Some(Shell::NuShell)
+    } else {
+        let shell = std::env::var("SHELL").unwrap_or_default();
+        if shell.contains("fish") {
+            Some(Shell::Fish)
+        } else if shell.contains("zsh") {
+            Some(Shell::Zsh)
+        } else if shell.contains("bash") {
+            Some(Shell::Bash)
+        } else if shell.contains("elvish") {
+            Some(Shell::Elvish)
+        } else if shell.contains("xonsh") {
+            Some(Shell::Xonsh)
+        } else if shell.ends_with("/sh") || shell.contains("dash") {
+            Some(Shell::Sh)
+        } else {
+            None
+        }
+    }
+}
+
 pub fn is_shell_integration_configured(shell: &Shell) -> bool {
     get_shell_integration_path(shell).exists()
 }
@@ -592,7 +910,7 @@ fn append_source_to_rc(rc_path: &std::path::Path, source_cmd: &str) -> Result<()
 }
 
 /// Writes the shell integration file and returns its path.
-fn write_shell_integration(shell: &Shell) -> Result<std::path::PathBuf> {
+pub fn write_shell_integration(shell: &Shell) -> Result<std::path::PathBuf> {
     let file_path = get_shell_integration_path(shell);
     if let Some(parent) = file_path.parent()
         && !parent.exists()
@@ -608,6 +926,15 @@ fn write_shell_integration(shell: &Shell) -> Result<std::path::PathBuf> {
     Ok(file_path)
 }
 
+/// The rc file line that loads the integration file at `file_path`.
+pub fn source_command(shell: &Shell, file_path: &Path) -> String {
+    match shell {
+        Shell::PowerShell | Shell::Sh => format!(". '{}'", file_path.display()),
+        Shell::Elvish => "use try-rs".to_string(),
+        _ => format!("source '{}'", file_path.display()),
+    }
+}
+
 /// Sets up shell integration for the given shell.
 pub fn setup_shell(shell: &Shell) -> Result<()> {
     let file_path = write_shell_integration(shell)?;
@@ -621,20 +948,17 @@ pub fn setup_shell(shell: &Shell) -> Result<()> {
                 "You may need to restart your shell or run 'source {}' to apply changes.",
                 file_path.display()
             );
-            eprintln!(
-                "Optional: append the following to {} to bind Ctrl+T:",
-                fish_config_path.display()
-            );
-            eprintln!("bind \\ct try-rs-picker");
-            eprintln!("bind -M insert \\ct try-rs-picker");
+            print_picker_binding(shell, &fish_config_path);
         }
         Shell::Zsh => {
-            let source_cmd = format!("source '{}'", file_path.display());
+            let source_cmd = source_command(shell, &file_path);
             append_source_to_rc(&home_dir.join(".zshrc"), &source_cmd)?;
+            print_picker_binding(shell, &home_dir.join(".zshrc"));
         }
         Shell::Bash => {
-            let source_cmd = format!("source '{}'", file_path.display());
+            let source_cmd = source_command(shell, &file_path);
             append_source_to_rc(&home_dir.join(".bashrc"), &source_cmd)?;
+            print_picker_binding(shell, &home_dir.join(".bashrc"));
         }
         Shell::PowerShell => {
             let profile_path_ps7 = home_dir
@@ -659,7 +983,7 @@ pub fn setup_shell(shell: &Shell) -> Result<()> {
                 fs::create_dir_all(parent)?;
             }
 
-            let source_cmd = format!(". '{}'", file_path.display());
+            let source_cmd = source_command(shell, &file_path);
             if profile_path.exists() {
                 append_source_to_rc(&profile_path, &source_cmd)?;
             } else {
@@ -685,7 +1009,7 @@ pub fn setup_shell(shell: &Shell) -> Result<()> {
                 .expect("Could not find config directory")
                 .join("nushell")
                 .join("config.nu");
-            let source_cmd = format!("source '{}'", file_path.display());
+            let source_cmd = source_command(shell, &file_path);
             if nu_config_path.exists() {
                 append_source_to_rc(&nu_config_path, &source_cmd)?;
             } else {
@@ -693,12 +1017,52 @@ pub fn setup_shell(shell: &Shell) -> Result<()> {
                 eprintln!("Please add the following line manually:");
                 eprintln!("{}", source_cmd);
             }
+            print_picker_binding(shell, &nu_config_path);
+        }
+        Shell::Elvish => {
+            let rc_path = elvish_rc_path();
+            if let Some(parent) = rc_path.parent()
+                && !parent.exists()
+            {
+                fs::create_dir_all(parent)?;
+            }
+            if !rc_path.exists() {
+                fs::File::create(&rc_path)?;
+            }
+            append_source_to_rc(&rc_path, &source_command(shell, &file_path))?;
+        }
+        Shell::Xonsh => {
+            let source_cmd = source_command(shell, &file_path);
+            append_source_to_rc(&home_dir.join(".xonshrc"), &source_cmd)?;
+        }
+        Shell::Sh => {
+            let rc_path = sh_rc_path(&home_dir);
+            let source_cmd = source_command(shell, &file_path);
+            append_source_to_rc(&rc_path, &source_cmd)?;
+            if std::env::var_os("ENV").is_none() {
+                eprintln!(
+                    "Interactive sh only reads {} when $ENV names it; add this to ~/.profile:",
+                    rc_path.display()
+                );
+                eprintln!("export ENV=\"{}\"", rc_path.display());
+            }
         }
     }
 
     Ok(())
 }
 
+fn elvish_rc_path() -> PathBuf {
+    get_base_config_dir().join("elvish").join("rc.elv")
+}
+
+/// The file interactive POSIX shells read at startup: `$ENV`, or `~/.shrc`.
+fn sh_rc_path(home_dir: &Path) -> PathBuf {
+    std::env::var_os("ENV")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir.join(".shrc"))
+}
+
 /// Generates a standalone completion script for the given shell.
 pub fn generate_completions(shell: &Shell) -> Result<()> {
     let script = get_completion_script_only(shell);
@@ -714,6 +1078,9 @@ pub fn get_installed_shells() -> Vec<Shell> {
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
     ] {
         if is_shell_installed(&shell) {
             shells.push(shell);
@@ -729,6 +1096,9 @@ fn is_shell_installed(shell: &Shell) -> bool {
         Shell::Bash => "bash",
         Shell::PowerShell => "pwsh",
         Shell::NuShell => "nu",
+        Shell::Elvish => "elvish",
+        Shell::Xonsh => "xonsh",
+        Shell::Sh => "sh",
     };
 
     let output = std::process::Command::new("whereis")
@@ -756,7 +1126,7 @@ pub fn clear_shell_setup() -> Result<()> {
     }
 
     eprintln!("Detected shells: {:?}\n", installed_shells);
-    
+
     eprintln!("Files to be removed:");
 
     for shell in &installed_shells {
@@ -765,17 +1135,6 @@ pub fn clear_shell_setup() -> Result<()> {
         for path in &paths {
             eprintln!("  - {}", path.display());
         }
-
-        match shell {
-            Shell::Fish => {
-                let fish_functions = get_fish_functions_dir();
-                eprintln!(
-                    "  - {}",
-                    fish_functions.join("try-rs-picker.fish").display()
-                );
-            }
-            _ => {}
-        }
     }
 
     eprintln!("\nRemoving files...");
@@ -804,16 +1163,30 @@ fn clear_shell_config(shell: &Shell) -> Result<()> {
     }
 
     // Clean up RC files instead of deleting them
+    for rc_path in get_rc_files(shell) {
+        if rc_path.exists() {
+            remove_source_from_rc(&rc_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The startup files that source the integration for `shell`. Fish has none,
+/// since it autoloads the function file.
+pub fn get_rc_files(shell: &Shell) -> Vec<PathBuf> {
     let home_dir = dirs::home_dir().expect("Could not find home directory");
-    let rc_files = match shell {
+    match shell {
         Shell::Zsh => vec![home_dir.join(".zshrc")],
         Shell::Bash => vec![home_dir.join(".bashrc")],
-        Shell::NuShell => vec![dirs::config_dir()
-            .expect("Could not find config directory")
-            .join("nushell")
-            .join("config.nu")],
+        Shell::NuShell => vec![
+            dirs::config_dir()
+                .expect("Could not find config directory")
+                .join("nushell")
+                .join("config.nu"),
+        ],
         Shell::PowerShell => {
-             let profile_path_ps7 = home_dir
+            let profile_path_ps7 = home_dir
                 .join("Documents")
                 .join("PowerShell")
                 .join("Microsoft.PowerShell_profile.ps1");
@@ -822,17 +1195,12 @@ fn clear_shell_config(shell: &Shell) -> Result<()> {
                 .join("WindowsPowerShell")
                 .join("Microsoft.PowerShell_profile.ps1");
             vec![profile_path_ps7, profile_path_ps5]
-        },
-        _ => vec![],
-    };
-
-    for rc_path in rc_files {
-        if rc_path.exists() {
-            remove_source_from_rc(&rc_path)?;
         }
+        Shell::Elvish => vec![elvish_rc_path()],
+        Shell::Xonsh => vec![home_dir.join(".xonshrc")],
+        Shell::Sh => vec![sh_rc_path(&home_dir)],
+        Shell::Fish => vec![],
     }
-
-    Ok(())
 }
 
 fn remove_source_from_rc(rc_path: &std::path::Path) -> Result<()> {
@@ -840,12 +1208,17 @@ fn remove_source_from_rc(rc_path: &std::path::Path) -> Result<()> {
     if content.contains("try-rs") {
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
         let initial_count = lines.len();
-        
+
         // Remove lines containing try-rs integration marker or typical source commands
         lines.retain(|line| {
-            !line.contains("# try-rs integration") && 
-            !(line.contains("source") && line.contains("try-rs")) &&
-            !(line.contains(".") && line.contains("try-rs") && rc_path.extension().map_or(false, |ext| ext == "ps1"))
+            let is_marker = line.contains("# try-rs integration");
+            let is_source = line.contains("source") && line.contains("try-rs");
+            let is_ps_dot_source = line.contains(".")
+                && line.contains("try-rs")
+                && rc_path.extension().is_some_and(|ext| ext == "ps1");
+            let is_dot_source = line.starts_with(". ") && line.contains("try-rs");
+            let is_elvish_use = line.trim() == "use try-rs";
+            !(is_marker || is_source || is_ps_dot_source || is_dot_source || is_elvish_use)
         });
 
         if lines.len() < initial_count {
@@ -860,29 +1233,11 @@ fn remove_source_from_rc(rc_path: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
-fn get_shell_config_paths(shell: &Shell) -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-    let config_dir = get_base_config_dir();
-
-    match shell {
-        Shell::Fish => {
-            let fish_functions = get_fish_functions_dir();
-            paths.push(fish_functions.join("try-rs.fish"));
-        }
-        Shell::Zsh => {
-            paths.push(config_dir.join("try-rs.zsh"));
-        }
-        Shell::Bash => {
-            paths.push(config_dir.join("try-rs.bash"));
-        }
-        Shell::PowerShell => {
-            paths.push(config_dir.join("try-rs.ps1"));
-        }
-        Shell::NuShell => {
-            paths.push(config_dir.join("try-rs.nu"));
-        }
+/// The files `setup_shell` writes for `shell`, which `--setup-clear` removes.
+pub fn get_shell_config_paths(shell: &Shell) -> Vec<PathBuf> {
+    let mut paths = vec![get_shell_integration_path(shell)];
+    if shell == &Shell::Fish {
+        paths.push(get_fish_functions_dir().join("try-rs-picker.fish"));
     }
-
     paths
 }
-
@@ -0,0 +1,136 @@
+use crate::config::get_config_dir;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Values substituted into template file names and contents.
+pub struct TemplateVars {
+    pub name: String,
+    pub date: String,
+    pub author: String,
+}
+
+impl TemplateVars {
+    pub fn new(name: &str, date: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            date: date.to_string(),
+            author: detect_author(),
+        }
+    }
+}
+
+/// Returns the directory holding one sub-directory per template.
+pub fn get_templates_dir() -> PathBuf {
+    get_config_dir().join("templates")
+}
+
+/// Lists template names (sub-directories of `templates_dir`), sorted by name.
+pub fn list_templates(templates_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(templates_dir)
+        .map(|read_dir| {
+            read_dir
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Replaces `{{name}}`, `{{date}}` and `{{author}}` in `text`.
+pub fn render(text: &str, vars: &TemplateVars) -> String {
+    text.replace("{{name}}", &vars.name)
+        .replace("{{date}}", &vars.date)
+        .replace("{{author}}", &vars.author)
+}
+
+/// Copies the template `name` from `templates_dir` into `dest`, rendering
+/// placeholders in both file names and UTF-8 file contents. Files that
+/// already exist in `dest` are kept, with a warning.
+pub fn apply_template(
+    templates_dir: &Path,
+    name: &str,
+    dest: &Path,
+    vars: &TemplateVars,
+) -> Result<()> {
+    let template_dir = templates_dir.join(name);
+    // Only a plain folder name: `.`, `..` or a path would copy some other directory
+    let is_plain_name = !name.contains(['/', '\\'])
+        && matches!(
+            Path::new(name).components().collect::<Vec<_>>()[..],
+            [Component::Normal(_)]
+        );
+    if !is_plain_name || !template_dir.is_dir() {
+        let available = list_templates(templates_dir);
+        if available.is_empty() {
+            bail!(
+                "Template '{}' not found: no templates in {}",
+                name,
+                templates_dir.display()
+            );
+        }
+        bail!(
+            "Template '{}' not found. Available templates: {}",
+            name,
+            available.join(", ")
+        );
+    }
+    copy_rendered(&template_dir, dest, vars)
+        .with_context(|| format!("Failed to apply template '{}'", name))
+}
+
+fn copy_rendered(src: &Path, dest: &Path, vars: &TemplateVars) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == ".git" {
+            continue;
+        }
+        let src_path = entry.path();
+        let dest_path = dest.join(render(&file_name, vars));
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_rendered(&src_path, &dest_path, vars)?;
+        } else if dest_path.symlink_metadata().is_ok() {
+            eprintln!(
+                "Warning: kept the existing '{}' instead of the template's",
+                dest_path.display()
+            );
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(&src_path)?, &dest_path)?;
+            #[cfg(not(unix))]
+            eprintln!(
+                "Warning: skipped the symlink '{}', symlinks in templates are only copied on Unix",
+                src_path.display()
+            );
+        } else {
+            let bytes = fs::read(&src_path)?;
+            match String::from_utf8(bytes) {
+                Ok(text) => fs::write(&dest_path, render(&text, vars))?,
+                Err(err) => fs::write(&dest_path, err.into_bytes())?,
+            }
+            fs::set_permissions(&dest_path, fs::metadata(&src_path)?.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+fn detect_author() -> String {
+    Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_default()
+}
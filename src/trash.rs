@@ -0,0 +1,246 @@
+use crate::cli::TrashCommand;
+use crate::meta::{self, MetaStore};
+use crate::utils;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+/// Metadata stored next to each trashed folder.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TrashEntry {
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    pub original_path: PathBuf,
+    /// Unix timestamp (seconds) of the deletion.
+    pub deleted_at: u64,
+}
+
+impl TrashEntry {
+    pub fn deleted_at_local(&self) -> DateTime<Local> {
+        DateTime::from(UNIX_EPOCH + std::time::Duration::from_secs(self.deleted_at))
+    }
+}
+
+/// A trash directory laid out as `files/<id>` (the folder itself) and
+/// `info/<id>.toml` (its `TrashEntry`).
+pub struct Trash {
+    dir: PathBuf,
+}
+
+/// Returns the default trash location, next to other try-rs data.
+pub fn get_default_trash_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .map(|dir| dir.join("try-rs"))
+        .unwrap_or_else(crate::config::get_config_dir)
+        .join("trash")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl Trash {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.dir.join("files")
+    }
+
+    fn info_dir(&self) -> PathBuf {
+        self.dir.join("info")
+    }
+
+    fn info_path(&self, id: &str) -> PathBuf {
+        self.info_dir().join(format!("{id}.toml"))
+    }
+
+    /// Moves `path` into the trash and records where it came from.
+    pub fn move_to_trash(&self, path: &Path) -> Result<TrashEntry> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .context("Cannot trash a path without a folder name")?;
+        let deleted_at = now_secs();
+        fs::create_dir_all(self.files_dir())?;
+        fs::create_dir_all(self.info_dir())?;
+
+        let mut id = format!("{deleted_at}-{name}");
+        let mut suffix = 1;
+        while self.files_dir().join(&id).exists() || self.info_path(&id).exists() {
+            suffix += 1;
+            id = format!("{deleted_at}-{name}-{suffix}");
+        }
+
+        let entry = TrashEntry {
+            id: id.clone(),
+            name,
+            original_path: std::path::absolute(path)?,
+            deleted_at,
+        };
+        // Write the metadata first so a folder in files/ always has an origin
+        fs::write(self.info_path(&id), toml::to_string(&entry)?)?;
+        if let Err(e) = utils::move_dir(path, &self.files_dir().join(&id)) {
+            let _ = fs::remove_file(self.info_path(&id));
+            return Err(e).with_context(|| format!("Failed to trash {}", path.display()));
+        }
+        Ok(entry)
+    }
+
+    /// Lists trashed folders, most recently deleted first.
+    pub fn list(&self) -> Vec<TrashEntry> {
+        let mut entries: Vec<TrashEntry> = fs::read_dir(self.info_dir())
+            .map(|read_dir| {
+                read_dir
+                    .flatten()
+                    .filter_map(|file| {
+                        let path = file.path();
+                        let id = path.file_stem()?.to_string_lossy().to_string();
+                        let contents = fs::read_to_string(&path).ok()?;
+                        let mut entry: TrashEntry = toml::from_str(&contents).ok()?;
+                        entry.id = id;
+                        Some(entry)
+                    })
+                    .filter(|entry| self.files_dir().join(&entry.id).exists())
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then(b.id.cmp(&a.id)));
+        entries
+    }
+
+    /// Finds an entry by id, or the most recently deleted one with that name.
+    pub fn find(&self, id_or_name: &str) -> Option<TrashEntry> {
+        let entries = self.list();
+        entries
+            .iter()
+            .find(|e| e.id == id_or_name)
+            .or_else(|| entries.iter().find(|e| e.name == id_or_name))
+            .cloned()
+    }
+
+    /// Moves a trashed folder back to its original location.
+    pub fn restore(&self, entry: &TrashEntry) -> Result<PathBuf> {
+        let dest = &entry.original_path;
+        if dest.exists() {
+            bail!("Cannot restore: '{}' already exists", dest.display());
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        utils::move_dir(&self.files_dir().join(&entry.id), dest)
+            .with_context(|| format!("Failed to restore '{}'", entry.name))?;
+        let _ = fs::remove_file(self.info_path(&entry.id));
+        Ok(dest.clone())
+    }
+
+    /// Permanently deletes a trashed folder and its metadata.
+    pub fn remove(&self, entry: &TrashEntry) -> Result<()> {
+        let files = self.files_dir().join(&entry.id);
+        if files.exists() {
+            fs::remove_dir_all(&files)?;
+        }
+        fs::remove_file(self.info_path(&entry.id))?;
+        Ok(())
+    }
+
+    /// Permanently deletes everything in the trash. Returns the removed entries.
+    pub fn empty(&self) -> Result<Vec<TrashEntry>> {
+        let entries = self.list();
+        for entry in &entries {
+            self.remove(entry)?;
+        }
+        Ok(entries)
+    }
+
+    /// Permanently deletes entries trashed more than `retention_days` ago.
+    /// A retention of 0 keeps everything. Returns the removed entries.
+    pub fn purge_expired(&self, retention_days: u64) -> Result<Vec<TrashEntry>> {
+        if retention_days == 0 {
+            return Ok(Vec::new());
+        }
+        let cutoff = now_secs().saturating_sub(retention_days.saturating_mul(24 * 60 * 60));
+        let expired: Vec<TrashEntry> = self
+            .list()
+            .into_iter()
+            .filter(|e| e.deleted_at < cutoff)
+            .collect();
+        for entry in &expired {
+            self.remove(entry)?;
+        }
+        Ok(expired)
+    }
+}
+
+/// Handles the `trash` subcommand.
+pub fn run_trash_command(command: &TrashCommand, trash: &Trash, retention_days: u64) -> Result<()> {
+    match command {
+        TrashCommand::List => {
+            let entries = trash.list();
+            if entries.is_empty() {
+                eprintln!("Trash is empty ({})", trash.dir().display());
+            }
+            for entry in entries {
+                println!(
+                    "{}\t{}\t{}",
+                    entry.deleted_at_local().format("%Y-%m-%d %H:%M"),
+                    entry.id,
+                    entry.original_path.display()
+                );
+            }
+        }
+        TrashCommand::Restore { target } => {
+            let Some(entry) = trash.find(target) else {
+                bail!("'{}' is not in the trash", target);
+            };
+            let restored = trash.restore(&entry)?;
+            eprintln!("Restored '{}' to {}", entry.name, restored.display());
+        }
+        TrashCommand::Empty { expired, yes } => {
+            if !*expired && !*yes {
+                let count = trash.list().len();
+                if count == 0 {
+                    eprintln!("Trash is empty ({})", trash.dir().display());
+                    return Ok(());
+                }
+                eprint!("Permanently delete {} trashed folder(s)? [y/N] ", count);
+                io::stderr().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+                    eprintln!("Nothing was deleted");
+                    return Ok(());
+                }
+            }
+            let removed = if *expired {
+                trash.purge_expired(retention_days)?
+            } else {
+                trash.empty()?
+            };
+            // Their tags, notes and pins were kept for a restore, drop them now
+            let mut meta = MetaStore::load(meta::get_meta_path());
+            for entry in &removed {
+                if let Err(e) = meta.remove(&entry.original_path) {
+                    eprintln!("Warning: could not update metadata: {}", e);
+                }
+            }
+            eprintln!("Permanently deleted {} trashed folder(s)", removed.len());
+        }
+    }
+    Ok(())
+}
@@ -14,6 +14,7 @@ use std::{
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
     },
     thread,
     time::SystemTime,
@@ -21,11 +22,107 @@ use std::{
 
 pub use crate::themes::Theme;
 use crate::{
-    config::{get_file_config_toml_name, save_config},
-    utils::{self, SelectionResult},
+    access::{self, AccessLog},
+    actions::Multiplexer,
+    config::{HooksConfig, config_save_locations, save_config},
+    detectors::{self, Detector},
+    hooks,
+    meta::{self, MetaStore, TryMeta},
+    naming::NameTemplate,
+    prune::{self, PruneCandidate, PruneOutcome, PrunePolicy},
+    templates,
+    trash::{Trash, TrashEntry},
+    utils::{self, GitStatus, SelectionResult},
+    worktrees::{self, Worktree},
 };
 
-#[derive(Clone, Copy, PartialEq)]
+/// Order of the folder list, cycled with Ctrl+S.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SortMode {
+    /// Pinned tries first, then by frecency.
+    #[default]
+    Pinned,
+    Frecency,
+    Modified,
+    /// By the date prefix, or the folder's creation time without one.
+    Created,
+    Name,
+    Size,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 6] = [
+        SortMode::Pinned,
+        SortMode::Frecency,
+        SortMode::Modified,
+        SortMode::Created,
+        SortMode::Name,
+        SortMode::Size,
+    ];
+
+    /// The name used in `default_sort`.
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Pinned => "pinned",
+            SortMode::Frecency => "frecency",
+            SortMode::Modified => "modified",
+            SortMode::Created => "created",
+            SortMode::Name => "name",
+            SortMode::Size => "size",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Short description for the folder box title, e.g. "oldest first".
+    pub fn describe(self, reversed: bool) -> &'static str {
+        match (self, reversed) {
+            (SortMode::Pinned, false) => "pinned first",
+            (SortMode::Pinned, true) => "pinned last",
+            (SortMode::Frecency, false) => "most used",
+            (SortMode::Frecency, true) => "least used",
+            (SortMode::Modified, false) => "recently modified",
+            (SortMode::Modified, true) => "least recently modified",
+            (SortMode::Created, false) => "newest first",
+            (SortMode::Created, true) => "oldest first",
+            (SortMode::Name, false) => "name A-Z",
+            (SortMode::Name, true) => "name Z-A",
+            (SortMode::Size, false) => "largest first",
+            (SortMode::Size, true) => "smallest first",
+        }
+    }
+
+    fn compare(self, a: &TryEntry, b: &TryEntry) -> std::cmp::Ordering {
+        let by_frecency = || {
+            b.frecency
+                .total_cmp(&a.frecency)
+                .then(b.modified.cmp(&a.modified))
+        };
+        match self {
+            SortMode::Pinned => b.pinned.cmp(&a.pinned).then_with(by_frecency),
+            SortMode::Frecency => by_frecency(),
+            SortMode::Modified => b.modified.cmp(&a.modified),
+            SortMode::Created => b.created.cmp(&a.created),
+            SortMode::Name => a
+                .display_name
+                .to_lowercase()
+                .cmp(&b.display_name.to_lowercase()),
+            SortMode::Size => b.size_mb.cmp(&a.size_mb),
+        }
+        .then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum AppMode {
     Normal,
     DeleteConfirm,
@@ -35,6 +132,33 @@ pub enum AppMode {
     ConfigSaveLocationSelect,
     About,
     MoveFolder,
+    TemplateSelect,
+    PruneReview,
+    Worktrees,
+    TagsPrompt,
+    NotePrompt,
+    BatchConfirm,
+}
+
+/// An action applied to every selected entry after one confirmation.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BatchAction {
+    Delete,
+    /// Move to the tries directory with this tab index.
+    Move(usize),
+    Archive,
+    /// Add these tags.
+    Tag(Vec<String>),
+}
+
+/// What a key on the worktree screen does.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WorktreeAction {
+    Lock,
+    Unlock,
+    Remove,
+    /// Drop the entries of worktrees whose folder is gone.
+    Prune,
 }
 
 #[derive(Clone)]
@@ -50,12 +174,28 @@ pub struct TryEntry {
     pub is_worktree: bool,
     pub is_worktree_locked: bool,
     pub is_gitmodules: bool,
-    pub is_mise: bool,
-    pub is_cargo: bool,
-    pub is_maven: bool,
-    pub is_flutter: bool,
-    pub is_go: bool,
-    pub is_python: bool,
+    /// Filled in by a background worker after the first paint.
+    pub git_status: Option<GitStatus>,
+    /// Labels of the detectors that matched, e.g. "Rust".
+    pub project_types: Vec<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    /// Frecency from the access log; 0 for tries that were never entered.
+    pub frecency: f64,
+    /// Only computed while sorting by size.
+    pub size_mb: Option<u64>,
+}
+
+/// The display settings that can be toggled in the TUI and saved to the config.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DisplaySettings {
+    pub transparent_background: bool,
+    pub show_disk: bool,
+    pub show_preview: bool,
+    pub show_legend: bool,
+    pub show_right_panel: bool,
+    pub right_panel_width: u16,
 }
 
 pub struct App {
@@ -71,6 +211,7 @@ pub struct App {
     pub theme: Theme,
     pub editor_cmd: Option<String>,
     pub wants_editor: bool,
+    pub multiplexer: Option<Multiplexer>,
     pub apply_date_prefix: Option<bool>,
     pub date_prefix_format: Option<String>,
     pub transparent_background: bool,
@@ -90,7 +231,12 @@ pub struct App {
     pub original_transparent_background: Option<bool>,
 
     pub config_path: Option<PathBuf>,
+    /// The display settings the session started with. Saving the config only
+    /// writes the ones changed since, not every runtime flag.
+    pub initial_display: DisplaySettings,
     pub config_location_state: ListState,
+    /// Where a new config file can be saved, in the order they are loaded.
+    pub config_locations: Vec<PathBuf>,
 
     pub cached_free_space_mb: Option<u64>,
     pub folder_size_mb: Arc<AtomicU64>,
@@ -98,6 +244,46 @@ pub struct App {
     pub rename_input: String,
     pub move_folder_state: ListState,
 
+    pub templates: Vec<String>,
+    pub template_list_state: ListState,
+    pub selected_template: Option<String>,
+    pub hooks: HooksConfig,
+    /// Where deleted folders go. `None` deletes permanently.
+    pub trash: Option<Trash>,
+    /// Folders trashed during this session, most recent last, for undo.
+    pub deleted: Vec<TrashEntry>,
+
+    pub prune_policy: PrunePolicy,
+    pub prune_candidates: Vec<PruneCandidate>,
+    /// Parallel to `prune_candidates`: whether each one will be pruned.
+    pub prune_selected: Vec<bool>,
+    pub prune_list_state: ListState,
+
+    /// Where git runs for the worktree screen.
+    pub worktree_repo: Option<PathBuf>,
+    pub worktrees: Vec<Worktree>,
+    pub worktree_list_state: ListState,
+
+    pub meta: MetaStore,
+    pub access: AccessLog,
+    pub sort_mode: SortMode,
+    pub sort_reversed: bool,
+    /// Project type detectors, in legend order.
+    pub detectors: Vec<Detector>,
+    /// How folder names are read back into a date and a display name.
+    pub name_template: NameTemplate,
+    git_status_rx: Option<Receiver<(String, GitStatus)>>,
+    size_rx: Option<Receiver<(String, u64)>>,
+    pub meta_input: String,
+
+    /// Names of the entries selected for a batch action in the current tab.
+    pub selected: HashSet<String>,
+    /// Where archived folders go. `None` disables archiving.
+    pub archive_dir: Option<PathBuf>,
+    pub batch_action: Option<BatchAction>,
+    pub batch_targets: Vec<String>,
+    pub batch_size_mb: u64,
+
     current_entries: HashSet<String>,
     matcher: SkimMatcherV2,
 }
@@ -131,6 +317,7 @@ impl App {
         false
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         path: PathBuf,
         theme: Theme,
@@ -144,100 +331,15 @@ impl App {
         active_tab: usize,
         show_disk: bool,
     ) -> Self {
-        let mut entries = Vec::new();
-        let mut current_entries = HashSet::new();
-        let cwd_unresolved = std::env::var_os("PWD")
-            .map(PathBuf::from)
-            .filter(|p| !p.as_os_str().is_empty())
-            .or_else(|| std::env::current_dir().ok())
-            .unwrap_or_else(|| PathBuf::from("."));
-        let cwd_real = std::env::current_dir()
-            .ok()
-            .and_then(|cwd| cwd.canonicalize().ok())
-            .unwrap_or_else(|| cwd_unresolved.clone());
-        let base_real = path.canonicalize().unwrap_or_else(|_| path.clone());
-
-        if let Ok(read_dir) = fs::read_dir(&path) {
-            for entry in read_dir.flatten() {
-                if let Ok(metadata) = entry.metadata()
-                    && metadata.is_dir()
-                {
-                    let entry_path = entry.path();
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let git_path = entry_path.join(".git");
-                    let is_git = git_path.exists();
-                    let is_worktree = git_path.is_file();
-                    let is_worktree_locked = utils::is_git_worktree_locked(&entry_path);
-                    let is_gitmodules = entry_path.join(".gitmodules").exists();
-                    let is_mise = entry_path.join("mise.toml").exists();
-                    let is_cargo = entry_path.join("Cargo.toml").exists();
-                    let is_maven = entry_path.join("pom.xml").exists();
-                    let is_symlink = entry
-                        .file_type()
-                        .map(|kind| kind.is_symlink())
-                        .unwrap_or(false);
-                    let is_current = Self::is_current_entry(
-                        &entry_path,
-                        &name,
-                        is_symlink,
-                        &cwd_unresolved,
-                        &cwd_real,
-                        &base_real,
-                    );
-                    if is_current {
-                        current_entries.insert(name.clone());
-                    }
-
-                    let created;
-                    let display_name;
-                    if let Some((date_prefix, remainder)) = utils::extract_prefix_date(&name) {
-                        created = date_prefix;
-                        display_name = remainder;
-                    } else {
-                        created = metadata.created().unwrap_or(SystemTime::UNIX_EPOCH);
-                        display_name = name.clone();
-                    }
-                    let display_offset = name
-                        .chars()
-                        .count()
-                        .saturating_sub(display_name.chars().count());
-                    let is_flutter = entry_path.join("pubspec.yaml").exists();
-                    let is_go = entry_path.join("go.mod").exists();
-                    let is_python = entry_path.join("pyproject.toml").exists()
-                        || entry_path.join("requirements.txt").exists();
-                    entries.push(TryEntry {
-                        name,
-                        display_name,
-                        display_offset,
-                        match_indices: Vec::new(),
-                        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
-                        created,
-                        score: 0,
-                        is_git,
-                        is_worktree,
-                        is_worktree_locked,
-                        is_gitmodules,
-                        is_mise,
-                        is_cargo,
-                        is_maven,
-                        is_flutter,
-                        is_go,
-                        is_python,
-                    });
-                }
-            }
-        }
-        entries.sort_by(|a, b| b.modified.cmp(&a.modified));
-
         let themes = Theme::all();
 
         let mut theme_state = ListState::default();
         theme_state.select(Some(0));
 
         let mut app = Self {
-            query: query.unwrap_or_else(|| String::new()),
-            all_entries: entries.clone(),
-            filtered_entries: entries,
+            query: query.unwrap_or_default(),
+            all_entries: Vec::new(),
+            filtered_entries: Vec::new(),
             selected_index: 0,
             should_quit: false,
             final_selection: SelectionResult::None,
@@ -247,6 +349,7 @@ impl App {
             theme,
             editor_cmd,
             wants_editor: false,
+            multiplexer: None,
             apply_date_prefix,
             date_prefix_format,
             transparent_background,
@@ -262,13 +365,46 @@ impl App {
             theme_list_state: theme_state,
             original_theme: None,
             original_transparent_background: None,
+            initial_display: DisplaySettings::default(),
             config_path,
             config_location_state: ListState::default(),
-            cached_free_space_mb: if show_disk { utils::get_free_disk_space_mb(&path) } else { None },
+            config_locations: config_save_locations(),
+            cached_free_space_mb: if show_disk {
+                utils::get_free_disk_space_mb(&path)
+            } else {
+                None
+            },
             folder_size_mb: Arc::new(AtomicU64::new(0)),
             rename_input: String::new(),
             move_folder_state: ListState::default(),
-            current_entries,
+            templates: templates::list_templates(&templates::get_templates_dir()),
+            template_list_state: ListState::default(),
+            selected_template: None,
+            hooks: HooksConfig::default(),
+            trash: None,
+            deleted: Vec::new(),
+            prune_policy: PrunePolicy::from_config(&Default::default()),
+            prune_candidates: Vec::new(),
+            prune_selected: Vec::new(),
+            prune_list_state: ListState::default(),
+            worktree_repo: None,
+            worktrees: Vec::new(),
+            worktree_list_state: ListState::default(),
+            meta_input: String::new(),
+            selected: HashSet::new(),
+            archive_dir: None,
+            batch_action: None,
+            batch_targets: Vec::new(),
+            batch_size_mb: 0,
+            meta: MetaStore::default(),
+            access: AccessLog::default(),
+            sort_mode: SortMode::default(),
+            sort_reversed: false,
+            detectors: detectors::builtin_detectors(),
+            name_template: NameTemplate::default(),
+            git_status_rx: None,
+            size_rx: None,
+            current_entries: HashSet::new(),
             matcher: SkimMatcherV2::default(),
         };
 
@@ -282,10 +418,48 @@ impl App {
             });
         }
 
+        app.load_entries();
         app.update_search();
         app
     }
 
+    pub fn display_settings(&self) -> DisplaySettings {
+        DisplaySettings {
+            transparent_background: self.transparent_background,
+            show_disk: self.show_disk,
+            show_preview: self.show_preview,
+            show_legend: self.show_legend,
+            show_right_panel: self.right_panel_visible,
+            right_panel_width: self.right_panel_width,
+        }
+    }
+
+    /// Saves the theme to `path`, with the display settings changed since
+    /// `initial_display`. Keys for the others are left as they are.
+    pub fn save_theme(&self, path: &Path) -> std::io::Result<()> {
+        let now = self.display_settings();
+        let before = self.initial_display;
+        fn changed<T: PartialEq>(now: T, before: T) -> Option<T> {
+            (now != before).then_some(now)
+        }
+        // The tries paths and editor can't be changed here, and may come
+        // from `TRY_PATH` or `$EDITOR` rather than the file
+        save_config(
+            path,
+            &self.theme,
+            None,
+            &None,
+            self.apply_date_prefix,
+            self.date_prefix_format.clone(),
+            changed(now.transparent_background, before.transparent_background),
+            changed(now.show_disk, before.show_disk),
+            changed(now.show_preview, before.show_preview),
+            changed(now.show_legend, before.show_legend),
+            changed(now.show_right_panel, before.show_right_panel),
+            changed(now.right_panel_width, before.right_panel_width),
+        )
+    }
+
     pub fn switch_tab(&mut self, new_tab: usize) {
         if new_tab >= self.tries_dirs.len() {
             return;
@@ -305,153 +479,739 @@ impl App {
         }
 
         self.query.clear();
+        self.selected.clear();
+        // Statuses and sizes are kept by name, so drop the other tab's
+        self.all_entries.clear();
+        self.size_rx = None;
         self.load_entries();
         self.update_search();
     }
 
+    /// Re-reads the folders in `base_path`, with their markers and metadata.
     fn load_entries(&mut self) {
-        self.all_entries.clear();
+        // Keep the last known git status until the worker refreshes it, and
+        // the sizes already measured.
+        let mut previous_status: std::collections::HashMap<String, GitStatus> =
+            std::collections::HashMap::new();
+        let mut previous_size: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        for entry in self.all_entries.drain(..) {
+            if let Some(status) = entry.git_status {
+                previous_status.insert(entry.name.clone(), status);
+            }
+            if let Some(size) = entry.size_mb {
+                previous_size.insert(entry.name, size);
+            }
+        }
+        self.current_entries.clear();
+        let cwd_unresolved = std::env::var_os("PWD")
+            .map(PathBuf::from)
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        let cwd_real = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| cwd.canonicalize().ok())
+            .unwrap_or_else(|| cwd_unresolved.clone());
+        let base_real = self
+            .base_path
+            .canonicalize()
+            .unwrap_or_else(|_| self.base_path.clone());
+
+        self.all_entries = scan_entries(
+            &self.base_path,
+            &self.detectors,
+            &self.name_template,
+            &self.meta,
+            &self.access,
+        );
+        for entry in &mut self.all_entries {
+            let entry_path = self.base_path.join(&entry.name);
+            let is_symlink = entry_path
+                .symlink_metadata()
+                .map(|metadata| metadata.is_symlink())
+                .unwrap_or(false);
+            if Self::is_current_entry(
+                &entry_path,
+                &entry.name,
+                is_symlink,
+                &cwd_unresolved,
+                &cwd_real,
+                &base_real,
+            ) {
+                self.current_entries.insert(entry.name.clone());
+            }
+            entry.git_status = previous_status.remove(&entry.name);
+            entry.size_mb = previous_size.remove(&entry.name);
+        }
+        self.sort_entries();
+        self.spawn_git_status_worker();
+    }
+
+    /// Sorts `all_entries` by the current sort mode. Sizes that aren't known
+    /// yet are measured in the background, see `receive_sizes`.
+    fn sort_entries(&mut self) {
+        if self.sort_mode == SortMode::Size {
+            self.spawn_size_worker();
+        }
+        order_entries(&mut self.all_entries, self.sort_mode, self.sort_reversed);
+    }
+
+    /// Changes the sort mode and direction and re-sorts the list.
+    pub fn set_sort(&mut self, mode: SortMode, reversed: bool) {
+        self.sort_mode = mode;
+        self.sort_reversed = reversed;
+        self.sort_entries();
+        self.update_search();
+    }
 
-        if let Ok(read_dir) = fs::read_dir(&self.base_path) {
-            for entry in read_dir.flatten() {
-                if let Ok(metadata) = entry.metadata()
-                    && metadata.is_dir()
+    /// Computes the git status of every git entry on a background thread.
+    /// Replacing the receiver stops the previous worker at its next send.
+    fn spawn_git_status_worker(&mut self) {
+        let repos: Vec<(String, PathBuf)> = self
+            .all_entries
+            .iter()
+            .filter(|e| e.is_git)
+            .map(|e| (e.name.clone(), self.base_path.join(&e.name)))
+            .collect();
+        if repos.is_empty() {
+            self.git_status_rx = None;
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        self.git_status_rx = Some(rx);
+        thread::spawn(move || {
+            for (name, path) in repos {
+                if let Some(status) = utils::get_git_status(&path)
+                    && tx.send((name, status)).is_err()
                 {
-                    let entry_path = entry.path();
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let git_path = entry_path.join(".git");
-                    let is_git = git_path.exists();
-                    let is_worktree = git_path.is_file();
-                    let is_worktree_locked = utils::is_git_worktree_locked(&entry_path);
-                    let is_gitmodules = entry_path.join(".gitmodules").exists();
-                    let is_mise = entry_path.join("mise.toml").exists();
-                    let is_cargo = entry_path.join("Cargo.toml").exists();
-                    let is_maven = entry_path.join("pom.xml").exists();
-
-                    let created;
-                    let display_name;
-                    if let Some((date_prefix, remainder)) = utils::extract_prefix_date(&name) {
-                        created = date_prefix;
-                        display_name = remainder;
-                    } else {
-                        created = metadata.created().unwrap_or(SystemTime::UNIX_EPOCH);
-                        display_name = name.clone();
-                    }
-                    let display_offset = name
-                        .chars()
-                        .count()
-                        .saturating_sub(display_name.chars().count());
-                    let is_flutter = entry_path.join("pubspec.yaml").exists();
-                    let is_go = entry_path.join("go.mod").exists();
-                    let is_python = entry_path.join("pyproject.toml").exists()
-                        || entry_path.join("requirements.txt").exists();
-                    self.all_entries.push(TryEntry {
-                        name,
-                        display_name,
-                        display_offset,
-                        match_indices: Vec::new(),
-                        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
-                        created,
-                        score: 0,
-                        is_git,
-                        is_worktree,
-                        is_worktree_locked,
-                        is_gitmodules,
-                        is_mise,
-                        is_cargo,
-                        is_maven,
-                        is_flutter,
-                        is_go,
-                        is_python,
-                    });
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Measures the folders whose size isn't known yet on a background thread.
+    /// A worker that is still running keeps going.
+    fn spawn_size_worker(&mut self) {
+        if self.size_rx.is_some() {
+            return;
+        }
+        let folders: Vec<(String, PathBuf)> = self
+            .all_entries
+            .iter()
+            .filter(|e| e.size_mb.is_none())
+            .map(|e| (e.name.clone(), self.base_path.join(&e.name)))
+            .collect();
+        if folders.is_empty() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        self.size_rx = Some(rx);
+        thread::spawn(move || {
+            for (name, path) in folders {
+                if tx.send((name, utils::get_folder_size_mb(&path))).is_err() {
+                    return;
                 }
             }
+        });
+    }
+
+    /// Applies folder sizes measured since the last call and, while sorting
+    /// by size, re-sorts with them. Returns true when the worker has finished.
+    pub fn receive_sizes(&mut self) -> bool {
+        let Some(rx) = &self.size_rx else {
+            return true;
+        };
+        let mut received = Vec::new();
+        let finished = loop {
+            match rx.try_recv() {
+                Ok(result) => received.push(result),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if finished {
+            self.size_rx = None;
+        }
+        if received.is_empty() {
+            return finished;
+        }
+        for (name, size) in received {
+            for entry in self
+                .all_entries
+                .iter_mut()
+                .chain(self.filtered_entries.iter_mut())
+                .filter(|e| e.name == name)
+            {
+                entry.size_mb = Some(size);
+            }
+        }
+        if self.sort_mode == SortMode::Size {
+            // Keep the selection on the same folder while the list reorders
+            let selected = self
+                .filtered_entries
+                .get(self.selected_index)
+                .map(|e| e.name.clone());
+            order_entries(&mut self.all_entries, self.sort_mode, self.sort_reversed);
+            let show_new_option = self.show_new_option;
+            let selected_index = self.selected_index;
+            self.update_search();
+            self.show_new_option = show_new_option;
+            self.selected_index = selected
+                .and_then(|name| self.filtered_entries.iter().position(|e| e.name == name))
+                .unwrap_or(selected_index);
+        }
+        finished
+    }
+
+    /// Applies git statuses computed since the last call. Returns true when
+    /// the worker has finished.
+    pub fn receive_git_status(&mut self) -> bool {
+        let Some(rx) = &self.git_status_rx else {
+            return true;
+        };
+        let mut received = Vec::new();
+        let finished = loop {
+            match rx.try_recv() {
+                Ok(result) => received.push(result),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if finished {
+            self.git_status_rx = None;
+        }
+        for (name, status) in received {
+            for entry in self
+                .all_entries
+                .iter_mut()
+                .chain(self.filtered_entries.iter_mut())
+                .filter(|e| e.name == name)
+            {
+                entry.git_status = Some(status.clone());
+            }
         }
-        self.all_entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+        finished
+    }
+
+    /// Replaces the metadata store and re-reads the entries with it.
+    pub fn set_meta(&mut self, meta: MetaStore) {
+        self.meta = meta;
+        self.load_entries();
+        self.update_search();
     }
 
     pub fn has_exact_match(&self) -> bool {
         self.all_entries.iter().any(|e| e.name == self.query)
     }
 
-    pub fn update_search(&mut self) {
-        if self.query.is_empty() {
-            self.filtered_entries = self.all_entries.clone();
-        } else {
-            self.filtered_entries = self
-                .all_entries
-                .iter()
-                .filter_map(|entry| {
-                    self.matcher
-                        .fuzzy_indices(&entry.name, &self.query)
-                        .map(|(score, indices)| {
-                            let mut e = entry.clone();
-                            e.score = score;
-                            if entry.display_offset == 0 {
-                                e.match_indices = indices;
-                            } else {
-                                e.match_indices = indices
-                                    .into_iter()
-                                    .filter_map(|idx| idx.checked_sub(entry.display_offset))
-                                    .collect();
-                            }
-                            e
-                        })
-                })
-                .collect();
+    /// Quits to open the highlighted try in the configured multiplexer.
+    pub fn open_in_multiplexer(&mut self) {
+        let is_new_selected =
+            self.show_new_option && self.selected_index == self.filtered_entries.len();
+        if self.multiplexer.is_none() {
+            self.status_message = Some("No multiplexer configured in config.toml".to_string());
+        } else if !self.filtered_entries.is_empty() && !is_new_selected {
+            self.final_selection = SelectionResult::Multiplexer(
+                self.filtered_entries[self.selected_index].name.clone(),
+            );
+            self.should_quit = true;
+        }
+    }
 
-            self.filtered_entries.sort_by(|a, b| b.score.cmp(&a.score));
+    /// Selects the query as a new folder, asking for a template first when
+    /// templates exist and none was chosen on the command line.
+    pub fn select_new(&mut self, wants_editor: bool) {
+        self.final_selection = SelectionResult::New(self.query.clone());
+        self.wants_editor = wants_editor;
+        if self.selected_template.is_none()
+            && !self.templates.is_empty()
+            && !utils::is_git_url(&self.query)
+        {
+            self.template_list_state.select(Some(0));
+            self.mode = AppMode::TemplateSelect;
+        } else {
+            self.should_quit = true;
         }
-        self.show_new_option = !self.query.is_empty() && !self.has_exact_match();
+    }
+
+    pub fn update_search(&mut self) {
+        let (tag_filters, text) = parse_query(&self.query);
+        self.filtered_entries = rank_entries(
+            &self.all_entries,
+            &self.query,
+            &self.matcher,
+            self.sort_mode == SortMode::Pinned,
+        );
+        self.show_new_option =
+            !text.is_empty() && tag_filters.is_empty() && !self.has_exact_match();
         self.selected_index = 0;
     }
 
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.filtered_entries
+            .get(self.selected_index)
+            .map(|e| self.base_path.join(&e.name))
+    }
+
+    /// Updates the selected entry's metadata, keeping the selection on it.
+    fn update_selected_meta(&mut self, f: impl FnOnce(&mut TryMeta)) -> bool {
+        let Some(path) = self.selected_path() else {
+            return false;
+        };
+        let name = self.filtered_entries[self.selected_index].name.clone();
+        if let Err(e) = self.meta.update(&path, f) {
+            self.status_message = Some(format!("Error saving metadata: {}", e));
+            return false;
+        }
+        let meta = self.meta.get(&path).cloned().unwrap_or_default();
+        if let Some(entry) = self.all_entries.iter_mut().find(|e| e.name == name) {
+            entry.tags = meta.tags;
+            entry.note = meta.note;
+            entry.pinned = meta.pinned;
+        }
+        self.update_search();
+        if let Some(index) = self.filtered_entries.iter().position(|e| e.name == name) {
+            self.selected_index = index;
+        }
+        true
+    }
+
+    pub fn toggle_pin_selected(&mut self) {
+        let mut pinned = false;
+        if self.update_selected_meta(|meta| {
+            meta.pinned = !meta.pinned;
+            pinned = meta.pinned;
+        }) {
+            self.status_message = Some(if pinned { "Pinned" } else { "Unpinned" }.to_string());
+        }
+    }
+
+    /// Opens the tags or note prompt for the selected entry, pre-filled with its current value.
+    pub fn open_meta_prompt(&mut self, mode: AppMode) {
+        let Some(entry) = self.filtered_entries.get(self.selected_index) else {
+            return;
+        };
+        self.meta_input = match mode {
+            // Tags typed for a selection are added to each entry's own tags.
+            AppMode::TagsPrompt if !self.selected.is_empty() => String::new(),
+            AppMode::TagsPrompt => entry.tags.join(", "),
+            _ => entry.note.clone().unwrap_or_default(),
+        };
+        self.mode = mode;
+    }
+
+    /// Saves the tags or note prompt input to the selected entry.
+    pub fn submit_meta_prompt(&mut self) {
+        let input = self.meta_input.trim().to_string();
+        if self.mode == AppMode::TagsPrompt && !self.selected.is_empty() {
+            self.mode = AppMode::Normal;
+            let tags = meta::parse_tags(&input);
+            if !tags.is_empty() {
+                self.open_batch_confirm(BatchAction::Tag(tags));
+            }
+            return;
+        }
+        let saved = match self.mode {
+            AppMode::TagsPrompt => {
+                let tags = meta::parse_tags(&input);
+                self.update_selected_meta(|meta| meta.tags = tags)
+            }
+            _ => self.update_selected_meta(|meta| {
+                meta.note = (!input.is_empty()).then_some(input);
+            }),
+        };
+        if saved {
+            self.status_message = Some(match self.mode {
+                AppMode::TagsPrompt => "Tags saved".to_string(),
+                _ => "Note saved".to_string(),
+            });
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Deletes one entry of the current tab: worktrees through `git worktree
+    /// remove`, other folders into the trash (or permanently without one).
+    /// Returns the status message, or the error to show.
+    fn delete_entry(&mut self, entry_name: &str) -> Result<String, String> {
+        let path_to_remove = self.base_path.join(entry_name);
+
+        if let Some(reason) =
+            hooks::pre_delete_veto(self.hooks.pre_delete.as_deref(), &path_to_remove)
+        {
+            return Err(format!("Delete blocked by pre_delete hook: {}", reason));
+        }
+
+        // Only use git worktree remove if it's actually a worktree (not main working tree)
+        let message = if utils::is_git_worktree(&path_to_remove) {
+            match utils::remove_git_worktree(&path_to_remove) {
+                Ok(output) if output.status.success() => {
+                    let _ = self.meta.remove(&path_to_remove);
+                    format!("Worktree removed: {path_to_remove:?}")
+                }
+                Ok(output) => {
+                    return Err(format!(
+                        "Error deleting: {}",
+                        String::from_utf8_lossy(&output.stderr)
+                            .lines()
+                            .take(1)
+                            .collect::<String>()
+                    ));
+                }
+                Err(e) => return Err(format!("Error removing worktree: {}", e)),
+            }
+        } else if let Some(trash) = &self.trash {
+            let trashed = trash
+                .move_to_trash(&path_to_remove)
+                .map_err(|e| format!("Error deleting: {:#}", e))?;
+            self.deleted.push(trashed);
+            format!(
+                "Moved to trash: {} (Ctrl+Z to undo)",
+                path_to_remove.display()
+            )
+        } else {
+            // Regular directory or main git repo - just delete it
+            fs::remove_dir_all(&path_to_remove).map_err(|e| format!("Error deleting: {}", e))?;
+            // Trashed tries keep theirs until the trash drops them, for restores
+            let _ = self.meta.remove(&path_to_remove);
+            format!("Deleted: {}", path_to_remove.display())
+        };
+
+        self.all_entries.retain(|e| e.name != entry_name);
+        self.selected.remove(entry_name);
+        Ok(message)
+    }
+
     pub fn delete_selected(&mut self) {
         if let Some(entry_name) = self
             .filtered_entries
             .get(self.selected_index)
             .map(|e| e.name.clone())
         {
-            let path_to_remove = self.base_path.join(&entry_name);
-
-            // Only use git worktree remove if it's actually a worktree (not main working tree)
-            if utils::is_git_worktree(&path_to_remove) {
-                match utils::remove_git_worktree(&path_to_remove) {
-                    Ok(output) => {
-                        if output.status.success() {
-                            self.all_entries.retain(|e| e.name != entry_name);
-                            self.update_search();
-                            self.status_message =
-                                Some(format!("Worktree removed: {path_to_remove:?}"));
-                        } else {
-                            self.status_message = Some(format!(
-                                "Error deleting: {}",
-                                String::from_utf8_lossy(&output.stderr)
-                                    .lines()
-                                    .take(1)
-                                    .collect::<String>()
-                            ));
-                        }
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error removing worktree: {}", e));
-                    }
-                };
+            let result = self.delete_entry(&entry_name);
+            if result.is_ok() {
+                self.update_search();
+            }
+            self.status_message = Some(result.unwrap_or_else(|e| e));
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Restores the most recently trashed folder of this session.
+    pub fn undo_delete(&mut self) {
+        let (Some(trash), Some(entry)) = (&self.trash, self.deleted.pop()) else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return;
+        };
+        match trash.restore(&entry) {
+            Ok(path) => {
+                self.load_entries();
+                self.update_search();
+                if let Some(index) = self
+                    .filtered_entries
+                    .iter()
+                    .position(|e| e.name == entry.name)
+                {
+                    self.selected_index = index;
+                }
+                self.status_message = Some(format!("Restored: {}", path.display()));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("{:#}", e));
+                self.deleted.push(entry);
+            }
+        }
+    }
+
+    /// Finds prune candidates in the current tab and opens the review screen.
+    pub fn open_prune_review(&mut self) {
+        self.prune_candidates =
+            prune::find_candidates(&self.base_path, &self.prune_policy, &self.name_template);
+        if self.prune_candidates.is_empty() {
+            self.status_message = Some(format!(
+                "Nothing to prune ({})",
+                self.prune_policy.describe()
+            ));
+            return;
+        }
+        self.prune_selected = self
+            .prune_candidates
+            .iter()
+            .map(|c| !c.is_worktree_locked)
+            .collect();
+        self.prune_list_state.select(Some(0));
+        self.mode = AppMode::PruneReview;
+    }
+
+    /// Prunes the candidates still selected on the review screen.
+    pub fn confirm_prune(&mut self) {
+        let mut pruned = 0;
+        let mut skipped = 0;
+        let mut last_error = None;
+        for (candidate, selected) in self.prune_candidates.iter().zip(&self.prune_selected) {
+            if !selected {
+                continue;
+            }
+            match prune::remove_candidate(
+                candidate,
+                self.trash.as_ref(),
+                self.hooks.pre_delete.as_deref(),
+            ) {
+                PruneOutcome::Trashed(entry) => {
+                    self.deleted.push(entry);
+                    pruned += 1;
+                }
+                PruneOutcome::Deleted | PruneOutcome::WorktreeRemoved => {
+                    let _ = self.meta.remove(&candidate.path);
+                    pruned += 1;
+                }
+                PruneOutcome::Skipped(_) => skipped += 1,
+                PruneOutcome::Failed(e) => {
+                    last_error = Some(format!("Error pruning '{}': {}", candidate.name, e));
+                }
+            }
+        }
+        self.prune_candidates.clear();
+        self.prune_selected.clear();
+        self.load_entries();
+        self.update_search();
+        self.selected_index = 0;
+        self.status_message = Some(last_error.unwrap_or_else(|| {
+            if skipped > 0 {
+                format!("Pruned {} tries ({} skipped)", pruned, skipped)
             } else {
-                // Regular directory or main git repo - just delete it
-                match fs::remove_dir_all(&path_to_remove) {
-                    Ok(_) => {
-                        self.all_entries.retain(|e| e.name != entry_name);
-                        self.update_search();
-                        self.status_message =
-                            Some(format!("Deleted: {}", path_to_remove.display()));
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error deleting: {}", e));
-                    }
+                format!("Pruned {} tries", pruned)
+            }
+        }));
+        self.mode = AppMode::Normal;
+    }
+
+    /// Lists the worktrees of the current directory's repository, or else of
+    /// the selected try's, and opens the worktree screen.
+    pub fn open_worktrees(&mut self) {
+        let selected = self
+            .filtered_entries
+            .get(self.selected_index)
+            .filter(|entry| entry.is_git || entry.is_worktree)
+            .map(|entry| self.base_path.join(&entry.name));
+        for repo in std::env::current_dir().ok().into_iter().chain(selected) {
+            if let Ok(list) = worktrees::list_worktrees(&repo, &self.tries_dirs) {
+                self.worktrees = list;
+                self.worktree_repo = Some(repo);
+                self.worktree_list_state.select(Some(0));
+                self.mode = AppMode::Worktrees;
+                return;
+            }
+        }
+        self.status_message =
+            Some("Not inside a git repository, and the selected try isn't one".to_string());
+    }
+
+    /// Runs `action` on the selected worktree, or prunes stale entries, then
+    /// lists the worktrees again.
+    pub fn run_worktree_action(&mut self, action: WorktreeAction) {
+        let Some(repo) = self.worktree_repo.clone() else {
+            return;
+        };
+        let selected = self
+            .worktree_list_state
+            .selected()
+            .and_then(|i| self.worktrees.get(i).cloned());
+        let result = match (action, &selected) {
+            (WorktreeAction::Prune, _) => worktrees::prune(&repo, false)
+                .map(|pruned| format!("Pruned {} stale worktree entries", pruned.len())),
+            (_, None) => return,
+            (WorktreeAction::Lock, Some(worktree)) => worktrees::lock(&repo, worktree, None)
+                .map(|_| format!("Locked {}", worktree.path.display())),
+            (WorktreeAction::Unlock, Some(worktree)) => worktrees::unlock(&repo, worktree)
+                .map(|_| format!("Unlocked {}", worktree.path.display())),
+            (WorktreeAction::Remove, Some(worktree)) => worktrees::remove(&repo, worktree, false)
+                .map(|_| format!("Removed {}", worktree.path.display())),
+        };
+        self.status_message = Some(result.unwrap_or_else(|e| format!("Error: {}", e)));
+
+        if let Ok(list) = worktrees::list_worktrees(&repo, &self.tries_dirs) {
+            self.worktrees = list;
+        }
+        let last = self.worktrees.len().saturating_sub(1);
+        let i = self.worktree_list_state.selected().unwrap_or(0).min(last);
+        self.worktree_list_state.select(Some(i));
+        if action == WorktreeAction::Remove {
+            self.load_entries();
+            self.update_search();
+        }
+    }
+
+    /// Quits and enters the selected worktree.
+    pub fn enter_selected_worktree(&mut self) {
+        let Some(worktree) = self
+            .worktree_list_state
+            .selected()
+            .and_then(|i| self.worktrees.get(i))
+        else {
+            return;
+        };
+        if worktree.missing {
+            self.status_message = Some(format!("{} no longer exists", worktree.path.display()));
+            return;
+        }
+        // An absolute path replaces the tries directory it is joined to.
+        self.final_selection = SelectionResult::Folder(worktree.path.to_string_lossy().to_string());
+        self.should_quit = true;
+    }
+
+    /// Moves one entry of the current tab into `dest_dir`, carrying its metadata along.
+    fn move_entry(&mut self, name: &str, dest_dir: &Path) -> Result<(), String> {
+        let src = self.base_path.join(name);
+        let dst = dest_dir.join(name);
+        if src.exists() && dst.exists() {
+            return Err(format!("Folder '{}' already exists in target", name));
+        }
+        if let Err(e) = fs::create_dir_all(dest_dir).and_then(|_| utils::move_try(&src, &dst)) {
+            return Err(format!("Error moving folder: {}", e));
+        }
+        self.all_entries.retain(|e| e.name != name);
+        self.selected.remove(name);
+        self.meta
+            .rename(&src, &dst)
+            .and_then(|_| self.access.rename(&src, &dst))
+            .map_err(|e| format!("Moved, but metadata was not saved: {}", e))
+    }
+
+    /// Moves the selected folder into the tries directory `target_idx`.
+    pub fn move_selected(&mut self, target_idx: usize) {
+        let Some(selected_entry) = self.filtered_entries.get(self.selected_index) else {
+            return;
+        };
+        let name = selected_entry.name.clone();
+        let target = self.tries_dirs[target_idx].clone();
+        let result = self.move_entry(&name, &target);
+        self.update_search();
+        self.status_message = Some(match result {
+            Ok(()) => format!("Moved '{}' to {}", name, target.display()),
+            Err(e) => e,
+        });
+    }
+
+    /// Toggles the entry under the cursor in the selection and moves down.
+    pub fn toggle_selection(&mut self) {
+        let Some(entry) = self.filtered_entries.get(self.selected_index) else {
+            return;
+        };
+        if !self.selected.remove(&entry.name) {
+            self.selected.insert(entry.name.clone());
+        }
+        if self.selected_index + 1 < self.filtered_entries.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    /// Selects every filtered entry, or clears the selection if they all are selected.
+    pub fn toggle_select_all(&mut self) {
+        let all_selected = self
+            .filtered_entries
+            .iter()
+            .all(|e| self.selected.contains(&e.name));
+        if all_selected {
+            self.selected.clear();
+        } else {
+            self.selected
+                .extend(self.filtered_entries.iter().map(|e| e.name.clone()));
+        }
+    }
+
+    /// The entries a batch action applies to: the selection in list order, or
+    /// the entry under the cursor when nothing is selected.
+    fn action_targets(&self) -> Vec<String> {
+        if self.selected.is_empty() {
+            return self
+                .filtered_entries
+                .get(self.selected_index)
+                .map(|e| vec![e.name.clone()])
+                .unwrap_or_default();
+        }
+        self.all_entries
+            .iter()
+            .filter(|e| self.selected.contains(&e.name))
+            .map(|e| e.name.clone())
+            .collect()
+    }
+
+    /// Opens the confirmation popup for `action` on the current targets.
+    pub fn open_batch_confirm(&mut self, action: BatchAction) {
+        if action == BatchAction::Archive && self.archive_dir.is_none() {
+            self.status_message = Some("No archive directory configured".to_string());
+            return;
+        }
+        let targets = self.action_targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.batch_size_mb = targets
+            .iter()
+            .map(|name| utils::get_folder_size_mb(&self.base_path.join(name)))
+            .sum();
+        self.batch_targets = targets;
+        self.batch_action = Some(action);
+        self.mode = AppMode::BatchConfirm;
+    }
+
+    /// Applies the confirmed batch action to every target.
+    pub fn confirm_batch(&mut self) {
+        let Some(action) = self.batch_action.take() else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+        let targets = std::mem::take(&mut self.batch_targets);
+        let mut done = 0;
+        let mut last_error = None;
+        for name in &targets {
+            let result = match &action {
+                BatchAction::Delete => self.delete_entry(name).map(|_| ()),
+                BatchAction::Move(target_idx) => {
+                    let dest = self.tries_dirs[*target_idx].clone();
+                    self.move_entry(name, &dest)
+                }
+                BatchAction::Archive => {
+                    let dest = self.archive_dir.clone().unwrap_or_default();
+                    self.move_entry(name, &dest)
+                }
+                BatchAction::Tag(tags) => {
+                    let path = self.base_path.join(name);
+                    self.meta
+                        .update(&path, |meta| {
+                            for tag in tags {
+                                if !meta.tags.contains(tag) {
+                                    meta.tags.push(tag.clone());
+                                }
+                            }
+                        })
+                        .map_err(|e| format!("Error saving metadata: {}", e))
                 }
             };
+            match result {
+                Ok(()) => done += 1,
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        self.selected.clear();
+        if let BatchAction::Tag(_) = action {
+            self.load_entries();
         }
+        self.update_search();
+        let verb = match &action {
+            BatchAction::Delete if self.trash.is_some() => "Trashed",
+            BatchAction::Delete => "Deleted",
+            BatchAction::Move(_) => "Moved",
+            BatchAction::Archive => "Archived",
+            BatchAction::Tag(_) => "Tagged",
+        };
+        self.status_message = Some(match last_error {
+            Some(e) => format!("{} {} of {} ({})", verb, done, targets.len(), e),
+            None => format!("{} {} folder(s)", verb, done),
+        });
         self.mode = AppMode::Normal;
     }
 
@@ -482,32 +1242,34 @@ impl App {
             return;
         }
 
-        if let Err(e) = fs::rename(&old_path, &new_path) {
+        if let Err(e) = utils::move_try(&old_path, &new_path) {
             self.status_message = Some(format!("Error renaming: {}", e));
             self.mode = AppMode::Normal;
             return;
         }
+        let meta_error = self
+            .meta
+            .rename(&old_path, &new_path)
+            .and_then(|_| self.access.rename(&old_path, &new_path))
+            .err();
 
         for e in &mut self.all_entries {
             if e.name != old_name {
                 continue;
             }
             e.name = new_name.clone();
-            let display_name =
-                if let Some((_date, remainder)) = utils::extract_prefix_date(&new_name) {
-                    remainder
-                } else {
-                    new_name.clone()
+            (e.display_name, e.display_offset) =
+                match utils::extract_prefix_date(&new_name, &self.name_template) {
+                    Some((_date, remainder, offset)) => (remainder, offset),
+                    None => (new_name.clone(), 0),
                 };
-            e.display_offset = new_name
-                .chars()
-                .count()
-                .saturating_sub(display_name.chars().count());
-            e.display_name = display_name;
             break;
         }
         self.update_search();
-        self.status_message = Some(format!("Renamed '{}' → '{}'", old_name, new_name));
+        self.status_message = Some(match meta_error {
+            Some(e) => format!("Renamed, but metadata was not saved: {}", e),
+            None => format!("Renamed '{}' → '{}'", old_name, new_name),
+        });
         self.mode = AppMode::Normal;
     }
 }
@@ -653,33 +1415,301 @@ fn draw_config_location_select(f: &mut Frame, app: &mut App) {
     f.render_widget(Clear, popup_area);
 
     let block = Block::default()
-        .title(" Select Config Location ")
+        .title(" Select Config Location ")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(app.theme.popup_bg));
+
+    let items: Vec<ListItem> = app
+        .config_locations
+        .iter()
+        .map(|path| {
+            ListItem::new(path.display().to_string())
+                .style(Style::default().fg(app.theme.list_highlight_fg))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.list_highlight_bg)
+                .fg(app.theme.list_selected_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, popup_area, &mut app.config_location_state);
+}
+
+fn draw_move_folder_select(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Min(6),
+            Constraint::Percentage(30),
+        ])
+        .split(area);
+
+    let popup_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+        ])
+        .split(popup_layout[1])[1];
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Move Folder To ")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(app.theme.popup_bg));
+
+    let items: Vec<ListItem> = app
+        .tries_dirs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let name = p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| p.to_string_lossy().to_string());
+            let marker = if i == app.active_tab {
+                " (current)"
+            } else {
+                ""
+            };
+            ListItem::new(format!("{}{}", name, marker))
+                .style(Style::default().fg(app.theme.list_highlight_fg))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.list_highlight_bg)
+                .fg(app.theme.list_selected_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, popup_area, &mut app.move_folder_state);
+}
+
+fn draw_prune_review(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Min(8),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let popup_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .split(popup_layout[1])[1];
+
+    f.render_widget(Clear, popup_area);
+
+    let selected = app.prune_selected.iter().filter(|s| **s).count();
+    let block = Block::default()
+        .title(format!(
+            " Prune {} of {} ({}) ",
+            selected,
+            app.prune_candidates.len(),
+            app.prune_policy.describe()
+        ))
+        .title_bottom(" Space toggle | Enter confirm | Esc cancel ")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(app.theme.popup_bg));
+
+    let items: Vec<ListItem> = app
+        .prune_candidates
+        .iter()
+        .zip(&app.prune_selected)
+        .map(|(candidate, selected)| {
+            let checkbox = if candidate.is_worktree_locked {
+                "[locked]"
+            } else if *selected {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            let kind = if candidate.is_worktree {
+                " (worktree)"
+            } else {
+                ""
+            };
+            ListItem::new(format!(
+                "{} {}{}  {}d  {}MB",
+                checkbox, candidate.name, kind, candidate.age_days, candidate.size_mb
+            ))
+            .style(Style::default().fg(app.theme.list_highlight_fg))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.list_highlight_bg)
+                .fg(app.theme.list_selected_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, popup_area, &mut app.prune_list_state);
+}
+
+fn draw_worktrees(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Min(8),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let popup_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ])
+        .split(popup_layout[1])[1];
+
+    f.render_widget(Clear, popup_area);
+
+    let repo_name = app
+        .worktrees
+        .first()
+        .and_then(|main| main.path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let block = Block::default()
+        .title(format!(" Worktrees of {} ", repo_name))
+        .title_bottom(" l lock | u unlock | d remove | p prune | Enter jump | Esc back ")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(app.theme.popup_bg));
+
+    let items: Vec<ListItem> = app
+        .worktrees
+        .iter()
+        .map(|worktree| {
+            ListItem::new(format!(
+                "[{}] {}  {}  {}",
+                worktree.kind_label(),
+                worktree.path.display(),
+                worktree.branch_label(),
+                worktree.state_label()
+            ))
+            .style(Style::default().fg(app.theme.list_highlight_fg))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.list_highlight_bg)
+                .fg(app.theme.list_selected_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, popup_area, &mut app.worktree_list_state);
+}
+
+/// Most entry names listed in the batch confirmation before summarizing the rest.
+const BATCH_CONFIRM_MAX_LISTED: usize = 10;
+
+fn draw_batch_confirm(f: &mut Frame, app: &App) {
+    let Some(action) = &app.batch_action else {
+        return;
+    };
+    let area = f.area();
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Min(8),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let popup_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(popup_layout[1])[1];
+
+    f.render_widget(Clear, popup_area);
+
+    let count = app.batch_targets.len();
+    let title = match action {
+        BatchAction::Delete if app.trash.is_some() => format!(" Move {count} to trash? "),
+        BatchAction::Delete => format!(" Permanently delete {count}? "),
+        BatchAction::Move(idx) => format!(
+            " Move {count} to {}? ",
+            app.tries_dirs[*idx]
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        ),
+        BatchAction::Archive => format!(" Archive {count}? "),
+        BatchAction::Tag(tags) => format!(" Tag {count} with #{}? ", tags.join(" #")),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(format!(
+            " Total {}MB | y/Enter confirm | n/Esc cancel ",
+            app.batch_size_mb
+        ))
         .borders(Borders::ALL)
         .padding(Padding::horizontal(1))
         .style(Style::default().bg(app.theme.popup_bg));
 
-    let config_name = get_file_config_toml_name();
-    let items = vec![
-        ListItem::new(format!("System Config (~/.config/try-rs/{})", config_name))
-            .style(Style::default().fg(app.theme.list_highlight_fg)),
-        ListItem::new(format!("Home Directory (~/{})", config_name))
-            .style(Style::default().fg(app.theme.list_highlight_fg)),
-    ];
+    let mut lines: Vec<Line> = app
+        .batch_targets
+        .iter()
+        .take(BATCH_CONFIRM_MAX_LISTED)
+        .map(|name| Line::from(name.as_str()))
+        .collect();
+    if count > BATCH_CONFIRM_MAX_LISTED {
+        lines.push(Line::from(format!(
+            "... and {} more",
+            count - BATCH_CONFIRM_MAX_LISTED
+        )));
+    }
 
-    let list = List::new(items)
+    let paragraph = Paragraph::new(lines)
         .block(block)
-        .highlight_style(
-            Style::default()
-                .bg(app.theme.list_highlight_bg)
-                .fg(app.theme.list_selected_fg)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
-
-    f.render_stateful_widget(list, popup_area, &mut app.config_location_state);
+        .style(Style::default().fg(app.theme.popup_text));
+    f.render_widget(paragraph, popup_area);
 }
 
-fn draw_move_folder_select(f: &mut Frame, app: &mut App) {
+fn draw_template_select(f: &mut Frame, app: &mut App) {
     let area = f.area();
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -702,25 +1732,21 @@ fn draw_move_folder_select(f: &mut Frame, app: &mut App) {
     f.render_widget(Clear, popup_area);
 
     let block = Block::default()
-        .title(" Move Folder To ")
+        .title(" New From Template ")
         .borders(Borders::ALL)
         .padding(Padding::horizontal(1))
         .style(Style::default().bg(app.theme.popup_bg));
 
-    let items: Vec<ListItem> = app
-        .tries_dirs
-        .iter()
-        .enumerate()
-        .map(|(i, p)| {
-            let name = p
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| p.to_string_lossy().to_string());
-            let marker = if i == app.active_tab { " (current)" } else { "" };
-            ListItem::new(format!("{}{}", name, marker))
-                .style(Style::default().fg(app.theme.list_highlight_fg))
-        })
-        .collect();
+    let mut items = vec![
+        ListItem::new("(empty folder)").style(
+            Style::default()
+                .fg(app.theme.list_highlight_fg)
+                .add_modifier(Modifier::ITALIC),
+        ),
+    ];
+    items.extend(app.templates.iter().map(|name| {
+        ListItem::new(name.clone()).style(Style::default().fg(app.theme.list_highlight_fg))
+    }));
 
     let list = List::new(items)
         .block(block)
@@ -732,7 +1758,7 @@ fn draw_move_folder_select(f: &mut Frame, app: &mut App) {
         )
         .highlight_symbol(">> ");
 
-    f.render_stateful_widget(list, popup_area, &mut app.move_folder_state);
+    f.render_stateful_widget(list, popup_area, &mut app.template_list_state);
 }
 
 fn draw_about_popup(f: &mut Frame, theme: &Theme) {
@@ -868,18 +1894,169 @@ fn build_highlighted_name_spans(
     spans
 }
 
+/// Reads the folders in `base_path` with their markers, metadata and frecency.
+/// Entries come back unsorted and without git status.
+pub fn scan_entries(
+    base_path: &Path,
+    detectors: &[Detector],
+    name_template: &NameTemplate,
+    meta: &MetaStore,
+    access: &AccessLog,
+) -> Vec<TryEntry> {
+    let now = access::now();
+    let Ok(read_dir) = fs::read_dir(base_path) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_dir())?;
+            let entry_path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let git_path = entry_path.join(".git");
+
+            let created;
+            let display_name;
+            let display_offset;
+            if let Some((date_prefix, remainder, offset)) =
+                utils::extract_prefix_date(&name, name_template)
+            {
+                created = date_prefix;
+                display_name = remainder;
+                display_offset = offset;
+            } else {
+                created = metadata.created().unwrap_or(SystemTime::UNIX_EPOCH);
+                display_name = name.clone();
+                display_offset = 0;
+            }
+            let meta = meta.get(&entry_path).cloned().unwrap_or_default();
+            Some(TryEntry {
+                name,
+                display_name,
+                display_offset,
+                match_indices: Vec::new(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                created,
+                score: 0,
+                is_git: git_path.exists(),
+                is_worktree: git_path.is_file(),
+                is_worktree_locked: utils::is_git_worktree_locked(&entry_path),
+                is_gitmodules: entry_path.join(".gitmodules").exists(),
+                git_status: None,
+                project_types: detectors::detect(detectors, &entry_path),
+                tags: meta.tags,
+                note: meta.note,
+                pinned: meta.pinned,
+                frecency: access.frecency(&entry_path, now),
+                size_mb: None,
+            })
+        })
+        .collect()
+}
+
+/// Sorts entries of `base_path` by `mode`, measuring folder sizes first when sorting by size.
+pub fn sort_entries(entries: &mut [TryEntry], base_path: &Path, mode: SortMode, reversed: bool) {
+    if mode == SortMode::Size {
+        for entry in entries.iter_mut() {
+            if entry.size_mb.is_none() {
+                entry.size_mb = Some(utils::get_folder_size_mb(&base_path.join(&entry.name)));
+            }
+        }
+    }
+    order_entries(entries, mode, reversed);
+}
+
+/// Sorts entries by `mode` with what they already know, without measuring sizes.
+fn order_entries(entries: &mut [TryEntry], mode: SortMode, reversed: bool) {
+    entries.sort_by(|a, b| {
+        let order = mode.compare(a, b);
+        if reversed { order.reverse() } else { order }
+    });
+}
+
+/// Splits the query into `#tag` filters and the text to fuzzy-match.
+fn parse_query(query: &str) -> (Vec<String>, String) {
+    let mut tags = Vec::new();
+    let mut text = Vec::new();
+    for token in query.split_whitespace() {
+        match token.strip_prefix('#') {
+            Some(tag) if !tag.is_empty() => tags.push(tag.to_lowercase()),
+            _ => text.push(token),
+        }
+    }
+    (tags, text.join(" "))
+}
+
+/// Filters and ranks `entries` for `query` the way the picker does: `#tag`
+/// filters, then fuzzy matching on the folder name. Match indices are
+/// relative to `display_name`. The order of `entries` breaks score ties,
+/// after pinned entries when `pinned_first` is set.
+pub fn rank_entries(
+    entries: &[TryEntry],
+    query: &str,
+    matcher: &SkimMatcherV2,
+    pinned_first: bool,
+) -> Vec<TryEntry> {
+    let (tag_filters, text) = parse_query(query);
+    let mut ranked: Vec<TryEntry> = entries
+        .iter()
+        .filter(|entry| {
+            tag_filters.iter().all(|filter| {
+                entry
+                    .tags
+                    .iter()
+                    .any(|tag| tag.to_lowercase().starts_with(filter.as_str()))
+            })
+        })
+        .filter_map(|entry| {
+            if text.is_empty() {
+                return Some(entry.clone());
+            }
+            matcher
+                .fuzzy_indices(&entry.name, &text)
+                .map(|(score, indices)| {
+                    let mut e = entry.clone();
+                    e.score = score;
+                    if entry.display_name == entry.name {
+                        e.match_indices = indices;
+                    } else {
+                        // Drop matches in the date and the template's text
+                        let len = entry.display_name.chars().count();
+                        e.match_indices = indices
+                            .into_iter()
+                            .filter_map(|idx| idx.checked_sub(entry.display_offset))
+                            .filter(|&idx| idx < len)
+                            .collect();
+                    }
+                    e
+                })
+        })
+        .collect();
+
+    // Stable sort by score, so the incoming order breaks ties
+    if pinned_first {
+        ranked.sort_by_key(|e| (std::cmp::Reverse(e.pinned), std::cmp::Reverse(e.score)));
+    } else {
+        ranked.sort_by_key(|e| std::cmp::Reverse(e.score));
+    }
+    ranked
+}
+
 pub fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stderr>>,
     mut app: App,
-) -> Result<(SelectionResult, bool, usize)> {
+) -> Result<(SelectionResult, bool, usize, Option<String>)> {
+    app.initial_display = app.display_settings();
     while !app.should_quit {
+        app.receive_git_status();
+        app.receive_sizes();
         terminal.draw(|f| {
             // Render background if not transparent
-            if !app.transparent_background {
-                if let Some(bg_color) = app.theme.background {
-                    let background = Block::default().style(Style::default().bg(bg_color));
-                    f.render_widget(background, f.area());
-                }
+            if !app.transparent_background
+                && let Some(bg_color) = app.theme.background
+            {
+                let background = Block::default().style(Style::default().bg(bg_color));
+                f.render_widget(background, f.area());
             }
 
             let chunks = Layout::default()
@@ -909,10 +2086,7 @@ pub fn run_app(
             let content_with_tabs = if show_tabs {
                 Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Min(1),
-                        Constraint::Length(tab_height),
-                    ])
+                    .constraints([Constraint::Min(1), Constraint::Length(tab_height)])
                     .split(chunks[0])
             } else {
                 Rc::new([chunks[0], chunks[0]])
@@ -921,14 +2095,15 @@ pub fn run_app(
             let content_chunks = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints(content_constraints)
-                .split(if show_tabs { content_with_tabs[0] } else { chunks[0] });
+                .split(if show_tabs {
+                    content_with_tabs[0]
+                } else {
+                    chunks[0]
+                });
 
             let left_chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(3),
-                    Constraint::Min(1),
-                ])
+                .constraints([Constraint::Length(3), Constraint::Min(1)])
                 .split(content_chunks[0]);
 
             if show_tabs {
@@ -937,7 +2112,8 @@ pub fn run_app(
                     .iter()
                     .enumerate()
                     .map(|(i, p)| {
-                        let name = p.file_name()
+                        let name = p
+                            .file_name()
                             .map(|n| n.to_string_lossy().to_string())
                             .unwrap_or_else(|| p.to_string_lossy().to_string());
                         if i == app.active_tab {
@@ -952,7 +2128,7 @@ pub fn run_app(
                         }
                     })
                     .collect();
-                
+
                 let tab_line = Paragraph::new(Line::from(tab_names))
                     .style(Style::default().fg(app.theme.helpers_colors))
                     .alignment(Alignment::Left);
@@ -997,26 +2173,69 @@ pub fn run_app(
                     let date_width = date_str.chars().count();
 
                     // Build icon list: (flag, icon_str, color)
-                    let icons: &[(bool, &str, Color)] = &[
-                        (entry.is_cargo, " ", app.theme.icon_rust),
-                        (entry.is_maven, " ", app.theme.icon_maven),
-                        (entry.is_flutter, " ", app.theme.icon_flutter),
-                        (entry.is_go, " ", app.theme.icon_go),
-                        (entry.is_python, " ", app.theme.icon_python),
-                        (entry.is_mise, "󰬔 ", app.theme.icon_mise),
-                        (entry.is_worktree, "󰙅 ", app.theme.icon_worktree),
-                        (entry.is_worktree_locked, " ", app.theme.icon_worktree_lock),
-                        (entry.is_gitmodules, " ", app.theme.icon_gitmodules),
-                        (entry.is_git, " ", app.theme.icon_git),
-                    ];
-                    let icons_width: usize = icons.iter().filter(|(f, _, _)| *f).count() * 2;
+                    let mut icons: Vec<(bool, String, Color)> = app
+                        .detectors
+                        .iter()
+                        .filter(|d| entry.project_types.contains(&d.label))
+                        .map(|d| {
+                            (
+                                true,
+                                format!("{} ", d.icon),
+                                detectors::resolve_color(&d.color, &app.theme),
+                            )
+                        })
+                        .collect();
+                    icons.extend([
+                        (entry.is_worktree, "󰙅 ".to_string(), app.theme.icon_worktree),
+                        (
+                            entry.is_worktree_locked,
+                            " ".to_string(),
+                            app.theme.icon_worktree_lock,
+                        ),
+                        (
+                            entry.is_gitmodules,
+                            " ".to_string(),
+                            app.theme.icon_gitmodules,
+                        ),
+                        (entry.is_git, " ".to_string(), app.theme.icon_git),
+                    ]);
+                    let icons_width: usize = icons
+                        .iter()
+                        .filter(|(f, _, _)| *f)
+                        .map(|(_, icon, _)| icon.chars().count())
+                        .sum();
                     let icon_width = 3; // folder icon
 
                     let created_dt: chrono::DateTime<Local> = entry.created.into();
                     let created_text = created_dt.format("%Y-%m-%d").to_string();
                     let created_width = created_text.chars().count();
 
-                    let reserved = date_width + icons_width + icon_width + created_width + 2;
+                    let tags_text: String =
+                        entry.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+                    let tags_width = tags_text.chars().count();
+
+                    // Compact git markers: * uncommitted changes, ↑ unpushed, ↓ unpulled
+                    let git_text = entry
+                        .git_status
+                        .as_ref()
+                        .map(|status| {
+                            let mut text = String::new();
+                            if status.is_dirty() {
+                                text.push_str(" *");
+                            }
+                            if status.ahead > 0 {
+                                text.push_str(&format!(" ↑{}", status.ahead));
+                            }
+                            if status.behind > 0 {
+                                text.push_str(&format!(" ↓{}", status.behind));
+                            }
+                            text
+                        })
+                        .unwrap_or_default();
+                    let tags_width = tags_width + git_text.chars().count();
+
+                    let reserved =
+                        date_width + icons_width + icon_width + created_width + tags_width + 2;
                     let available_for_name = width.saturating_sub(reserved);
                     let name_len = entry.display_name.chars().count();
 
@@ -1046,6 +2265,7 @@ pub fn run_app(
                                     + created_width
                                     + 1
                                     + name_len
+                                    + tags_width
                                     + date_width
                                     + icons_width,
                             ),
@@ -1053,8 +2273,15 @@ pub fn run_app(
                     };
 
                     let is_current = app.current_entries.contains(&entry.name);
-                    let marker = if is_current { "* " } else { "  " };
-                    let marker_style = if is_current {
+                    let is_selected = app.selected.contains(&entry.name);
+                    let marker = if is_selected {
+                        "● "
+                    } else if is_current {
+                        "* "
+                    } else {
+                        "  "
+                    };
+                    let marker_style = if is_selected || is_current {
                         Style::default()
                             .fg(app.theme.list_match_fg)
                             .add_modifier(Modifier::BOLD)
@@ -1062,9 +2289,10 @@ pub fn run_app(
                         Style::default()
                     };
 
+                    let folder_icon = if entry.pinned { "󰐃 " } else { "󰝰 " };
                     let mut spans = vec![
                         Span::styled(marker, marker_style),
-                        Span::styled("󰝰 ", Style::default().fg(app.theme.icon_folder)),
+                        Span::styled(folder_icon, Style::default().fg(app.theme.icon_folder)),
                         Span::styled(created_text, Style::default().fg(app.theme.list_date)),
                         Span::raw(" "),
                     ];
@@ -1076,8 +2304,16 @@ pub fn run_app(
                     if is_truncated {
                         spans.push(Span::raw("..."));
                     }
+                    spans.push(Span::styled(
+                        tags_text,
+                        Style::default().fg(app.theme.list_date),
+                    ));
+                    spans.push(Span::styled(
+                        git_text,
+                        Style::default().fg(app.theme.status_message),
+                    ));
                     spans.push(Span::raw(" ".repeat(padding)));
-                    for &(flag, icon, color) in icons {
+                    for (flag, icon, color) in icons {
                         if flag {
                             spans.push(Span::styled(icon, Style::default().fg(color)));
                         }
@@ -1112,7 +2348,15 @@ pub fn run_app(
                         .borders(Borders::ALL)
                         .padding(Padding::horizontal(1))
                         .title(Span::styled(
-                            " Folders ",
+                            if app.selected.is_empty() {
+                                format!(" Folders · {} ", app.sort_mode.describe(app.sort_reversed))
+                            } else {
+                                format!(
+                                    " Folders · {} ({} selected) ",
+                                    app.sort_mode.describe(app.sort_reversed),
+                                    app.selected.len()
+                                )
+                            },
                             Style::default().fg(app.theme.folder_title),
                         ))
                         .border_style(Style::default().fg(app.theme.folder_border)),
@@ -1149,18 +2393,23 @@ pub fn run_app(
                     format!("{} MB", folder_size)
                 };
 
-                let legend_items: [(&str, Color, &str); 10] = [
-                    ("", app.theme.icon_rust, "Rust"),
-                    ("", app.theme.icon_maven, "Maven"),
-                    ("", app.theme.icon_flutter, "Flutter"),
-                    ("", app.theme.icon_go, "Go"),
-                    ("", app.theme.icon_python, "Python"),
-                    ("󰬔", app.theme.icon_mise, "Mise"),
+                let mut legend_items: Vec<(&str, Color, &str)> = app
+                    .detectors
+                    .iter()
+                    .map(|d| {
+                        (
+                            d.icon.as_str(),
+                            detectors::resolve_color(&d.color, &app.theme),
+                            d.label.as_str(),
+                        )
+                    })
+                    .collect();
+                legend_items.extend([
                     ("", app.theme.icon_worktree_lock, "Locked"),
                     ("󰙅", app.theme.icon_worktree, "Worktree"),
                     ("", app.theme.icon_gitmodules, "Submodule"),
                     ("", app.theme.icon_git, "Git"),
-                ];
+                ]);
 
                 let legend_required_lines = if show_legend_panel {
                     let legend_inner_width = content_chunks[1].width.saturating_sub(4).max(1);
@@ -1269,12 +2518,23 @@ pub fn run_app(
 
                     if is_new_selected {
                         // Show "new folder" preview
-                        let preview_lines = vec![Line::from(Span::styled(
+                        let mut preview_lines = vec![Line::from(Span::styled(
                             "(new folder)",
                             Style::default()
                                 .fg(app.theme.search_title)
                                 .add_modifier(Modifier::ITALIC),
                         ))];
+                        if let Some(template) = &app.selected_template {
+                            preview_lines.push(Line::from(Span::styled(
+                                format!("template: {}", template),
+                                Style::default().fg(app.theme.helpers_colors),
+                            )));
+                        } else if !app.templates.is_empty() {
+                            preview_lines.push(Line::from(Span::styled(
+                                format!("{} template(s) available", app.templates.len()),
+                                Style::default().fg(app.theme.helpers_colors),
+                            )));
+                        }
                         let preview = Paragraph::new(preview_lines).block(
                             Block::default()
                                 .borders(Borders::ALL)
@@ -1290,9 +2550,66 @@ pub fn run_app(
                         let preview_path = app.base_path.join(&selected.name);
                         let mut preview_lines = Vec::new();
 
+                        if let Some(note) = &selected.note {
+                            for line in note.lines() {
+                                preview_lines.push(Line::from(Span::styled(
+                                    line.to_string(),
+                                    Style::default()
+                                        .fg(app.theme.status_message)
+                                        .add_modifier(Modifier::ITALIC),
+                                )));
+                            }
+                        }
+                        if !selected.tags.is_empty() {
+                            preview_lines.push(Line::from(Span::styled(
+                                selected
+                                    .tags
+                                    .iter()
+                                    .map(|tag| format!("#{}", tag))
+                                    .collect::<Vec<_>>()
+                                    .join(" "),
+                                Style::default().fg(app.theme.list_date),
+                            )));
+                        }
+                        if let Some(status) = &selected.git_status {
+                            let branch = match (&status.branch, &status.upstream) {
+                                (Some(branch), Some(upstream)) => {
+                                    format!("{} → {}", branch, upstream)
+                                }
+                                (Some(branch), None) => format!("{} (no upstream)", branch),
+                                (None, _) => "detached HEAD".to_string(),
+                            };
+                            preview_lines.push(Line::from(vec![
+                                Span::styled(" ", Style::default().fg(app.theme.icon_git)),
+                                Span::raw(branch),
+                            ]));
+                            let mut state = if status.is_dirty() {
+                                format!("{} uncommitted change(s)", status.changed)
+                            } else {
+                                "clean".to_string()
+                            };
+                            if status.ahead > 0 {
+                                state.push_str(&format!(", {} unpushed", status.ahead));
+                            }
+                            if status.behind > 0 {
+                                state.push_str(&format!(", {} behind", status.behind));
+                            }
+                            preview_lines.push(Line::from(Span::styled(
+                                state,
+                                Style::default().fg(app.theme.status_message),
+                            )));
+                        }
+                        if !preview_lines.is_empty() {
+                            preview_lines.push(Line::from(""));
+                        }
+                        let meta_lines = preview_lines.len();
+
                         if let Ok(entries) = fs::read_dir(&preview_path) {
                             for e in entries
-                                .take(right_chunks[1].height.saturating_sub(2) as usize)
+                                .take(
+                                    (right_chunks[1].height.saturating_sub(2) as usize)
+                                        .saturating_sub(meta_lines),
+                                )
                                 .flatten()
                             {
                                 let file_name = e.file_name().to_string_lossy().to_string();
@@ -1309,7 +2626,7 @@ pub fn run_app(
                             }
                         }
 
-                        if preview_lines.is_empty() {
+                        if preview_lines.len() == meta_lines {
                             preview_lines.push(Line::from(Span::styled(
                                 " (empty) ",
                                 Style::default().fg(app.theme.helpers_colors),
@@ -1391,6 +2708,10 @@ pub fn run_app(
                     Span::raw(" Select | "),
                     Span::styled("Ctrl+D", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Del | "),
+                    Span::styled("Ctrl+Z", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Undo | "),
+                    Span::styled("Ctrl+S/O", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Sort/Reverse | "),
                     Span::styled("Ctrl+R", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Rename | "),
                     Span::styled("Ctrl+E", Style::default().add_modifier(Modifier::BOLD)),
@@ -1399,6 +2720,13 @@ pub fn run_app(
                     Span::raw(" Theme | "),
                 ];
 
+                if app.multiplexer.is_some() {
+                    help_parts.extend(vec![
+                        Span::styled("Alt+O", Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(" Multiplexer | "),
+                    ]);
+                }
+
                 if app.tries_dirs.len() > 1 {
                     help_parts.extend(vec![
                         Span::styled("←→", Style::default().add_modifier(Modifier::BOLD)),
@@ -1411,6 +2739,16 @@ pub fn run_app(
                     Span::raw(" About | "),
                     Span::styled("Alt+M", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Move | "),
+                    Span::styled("Alt+T/N/F", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Tag/Note/Pin | "),
+                    Span::styled("Tab/Alt+A", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Select/All | "),
+                    Span::styled("Alt+Z", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Archive | "),
+                    Span::styled("Alt+X", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Prune | "),
+                    Span::styled("Alt+W", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Worktrees | "),
                     Span::styled("Alt+P", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Panel | "),
                     Span::styled("Esc/Ctrl+C", Style::default().add_modifier(Modifier::BOLD)),
@@ -1429,7 +2767,11 @@ pub fn run_app(
             if app.mode == AppMode::DeleteConfirm
                 && let Some(selected) = app.filtered_entries.get(app.selected_index)
             {
-                let msg = format!("Delete '{}'?\n(y/n)", selected.name);
+                let msg = if app.trash.is_some() && !selected.is_worktree {
+                    format!("Move '{}' to trash?\n(y/n)", selected.name)
+                } else {
+                    format!("Permanently delete '{}'?\n(y/n)", selected.name)
+                };
                 draw_popup(f, " WARNING ", &msg, &app.theme);
             }
 
@@ -1438,6 +2780,16 @@ pub fn run_app(
                 draw_popup(f, " Rename ", &msg, &app.theme);
             }
 
+            if app.mode == AppMode::TagsPrompt {
+                let msg = format!("{}_", app.meta_input);
+                draw_popup(f, " Tags (comma separated) ", &msg, &app.theme);
+            }
+
+            if app.mode == AppMode::NotePrompt {
+                let msg = format!("{}_", app.meta_input);
+                draw_popup(f, " Note ", &msg, &app.theme);
+            }
+
             if app.mode == AppMode::ThemeSelect {
                 draw_theme_select(f, &mut app);
             }
@@ -1462,10 +2814,32 @@ pub fn run_app(
             if app.mode == AppMode::MoveFolder {
                 draw_move_folder_select(f, &mut app);
             }
+
+            if app.mode == AppMode::TemplateSelect {
+                draw_template_select(f, &mut app);
+            }
+
+            if app.mode == AppMode::PruneReview {
+                draw_prune_review(f, &mut app);
+            }
+
+            if app.mode == AppMode::Worktrees {
+                draw_worktrees(f, &mut app);
+            }
+
+            if app.mode == AppMode::BatchConfirm {
+                draw_batch_confirm(f, &app);
+            }
         })?;
 
         // Poll with 1-second timeout so the screen refreshes periodically
-        if !event::poll(std::time::Duration::from_secs(1))? {
+        // Poll faster while git statuses or folder sizes are still arriving
+        let poll_timeout = if app.git_status_rx.is_some() || app.size_rx.is_some() {
+            std::time::Duration::from_millis(100)
+        } else {
+            std::time::Duration::from_secs(1)
+        };
+        if !event::poll(poll_timeout)? {
             continue;
         }
         if let Event::Key(key) = event::read()? {
@@ -1482,9 +2856,17 @@ pub fn run_app(
                         } else if c == 'd' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
                             let is_new_selected = app.show_new_option
                                 && app.selected_index == app.filtered_entries.len();
-                            if !app.filtered_entries.is_empty() && !is_new_selected {
+                            if !app.selected.is_empty() {
+                                app.open_batch_confirm(BatchAction::Delete);
+                            } else if !app.filtered_entries.is_empty() && !is_new_selected {
                                 app.mode = AppMode::DeleteConfirm;
                             }
+                        } else if c == 'z' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                            app.undo_delete();
+                        } else if c == 's' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                            app.set_sort(app.sort_mode.next(), false);
+                        } else if c == 'o' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                            app.set_sort(app.sort_mode, !app.sort_reversed);
                         } else if c == 'r' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
                             let is_new_selected = app.show_new_option
                                 && app.selected_index == app.filtered_entries.len();
@@ -1498,9 +2880,7 @@ pub fn run_app(
                                 let is_new_selected = app.show_new_option
                                     && app.selected_index == app.filtered_entries.len();
                                 if is_new_selected {
-                                    app.final_selection = SelectionResult::New(app.query.clone());
-                                    app.wants_editor = true;
-                                    app.should_quit = true;
+                                    app.select_new(true);
                                 } else if !app.filtered_entries.is_empty() {
                                     app.final_selection = SelectionResult::Folder(
                                         app.filtered_entries[app.selected_index].name.clone(),
@@ -1508,9 +2888,7 @@ pub fn run_app(
                                     app.wants_editor = true;
                                     app.should_quit = true;
                                 } else if !app.query.is_empty() {
-                                    app.final_selection = SelectionResult::New(app.query.clone());
-                                    app.wants_editor = true;
-                                    app.should_quit = true;
+                                    app.select_new(true);
                                 }
                             } else {
                                 app.status_message =
@@ -1537,9 +2915,40 @@ pub fn run_app(
                                 app.move_folder_state.select(Some(0));
                                 app.mode = AppMode::MoveFolder;
                             } else {
-                                app.status_message =
-                                    Some("No folder selected to move".to_string());
+                                app.status_message = Some("No folder selected to move".to_string());
                             }
+                        } else if matches!(c, 't')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_meta_prompt(AppMode::TagsPrompt);
+                        } else if matches!(c, 'n')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_meta_prompt(AppMode::NotePrompt);
+                        } else if matches!(c, 'f')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.toggle_pin_selected();
+                        } else if matches!(c, 'x')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_prune_review();
+                        } else if matches!(c, 'w')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_worktrees();
+                        } else if matches!(c, 'a')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.toggle_select_all();
+                        } else if matches!(c, 'z')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_batch_confirm(BatchAction::Archive);
+                        } else if matches!(c, 'o')
+                            && key.modifiers.contains(event::KeyModifiers::ALT)
+                        {
+                            app.open_in_multiplexer();
                         } else if matches!(c, 'p')
                             && key.modifiers.contains(event::KeyModifiers::ALT)
                         {
@@ -1564,6 +2973,8 @@ pub fn run_app(
                         } else if c == 'u' && key.modifiers.contains(event::KeyModifiers::CONTROL) {
                             app.query.clear();
                             app.update_search();
+                        } else if c == ' ' && app.query.is_empty() && key.modifiers.is_empty() {
+                            app.toggle_selection();
                         } else if key.modifiers.is_empty()
                             || key.modifiers == event::KeyModifiers::SHIFT
                         {
@@ -1575,10 +2986,8 @@ pub fn run_app(
                         app.query.pop();
                         app.update_search();
                     }
-                    KeyCode::Up => {
-                        if app.selected_index > 0 {
-                            app.selected_index -= 1;
-                        }
+                    KeyCode::Up if app.selected_index > 0 => {
+                        app.selected_index -= 1;
                     }
                     KeyCode::Down => {
                         let max_index = if app.show_new_option {
@@ -1590,40 +2999,54 @@ pub fn run_app(
                             app.selected_index += 1;
                         }
                     }
-                    KeyCode::Left => {
-                        if app.tries_dirs.len() > 1 {
-                            let prev = if app.active_tab == 0 {
-                                app.tries_dirs.len() - 1
-                            } else {
-                                app.active_tab - 1
-                            };
-                            app.switch_tab(prev);
-                        }
+                    KeyCode::Left if app.tries_dirs.len() > 1 => {
+                        let prev = if app.active_tab == 0 {
+                            app.tries_dirs.len() - 1
+                        } else {
+                            app.active_tab - 1
+                        };
+                        app.switch_tab(prev);
                     }
-                    KeyCode::Right => {
-                        if app.tries_dirs.len() > 1 {
-                            let next = (app.active_tab + 1) % app.tries_dirs.len();
-                            app.switch_tab(next);
-                        }
+                    KeyCode::Right if app.tries_dirs.len() > 1 => {
+                        let next = (app.active_tab + 1) % app.tries_dirs.len();
+                        app.switch_tab(next);
                     }
                     KeyCode::Enter => {
                         let is_new_selected =
                             app.show_new_option && app.selected_index == app.filtered_entries.len();
-                        if is_new_selected {
-                            app.final_selection = SelectionResult::New(app.query.clone());
-                        } else if !app.filtered_entries.is_empty() {
-                            app.final_selection = SelectionResult::Folder(
-                                app.filtered_entries[app.selected_index].name.clone(),
-                            );
-                        } else if !app.query.is_empty() {
-                            app.final_selection = SelectionResult::New(app.query.clone());
+                        if is_new_selected
+                            || (app.filtered_entries.is_empty() && !app.query.is_empty())
+                        {
+                            app.select_new(false);
+                        } else {
+                            if !app.filtered_entries.is_empty() {
+                                app.final_selection = SelectionResult::Folder(
+                                    app.filtered_entries[app.selected_index].name.clone(),
+                                );
+                            }
+                            app.should_quit = true;
                         }
-                        app.should_quit = true;
                     }
+                    KeyCode::Tab => app.toggle_selection(),
+                    KeyCode::Esc if !app.selected.is_empty() => app.selected.clear(),
                     KeyCode::Esc => app.should_quit = true,
                     _ => {}
                 },
 
+                AppMode::BatchConfirm => match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        app.confirm_batch();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                        app.batch_action = None;
+                        app.mode = AppMode::Normal;
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.should_quit = true;
+                    }
+                    _ => {}
+                },
+
                 AppMode::DeleteConfirm => match key.code {
                     KeyCode::Char('y') | KeyCode::Char('Y') => {
                         app.delete_selected();
@@ -1637,6 +3060,71 @@ pub fn run_app(
                     _ => {}
                 },
 
+                AppMode::PruneReview => match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        let i = app.prune_list_state.selected().unwrap_or(0);
+                        app.prune_list_state.select(Some(i.saturating_sub(1)));
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        let i = app.prune_list_state.selected().unwrap_or(0);
+                        if i + 1 < app.prune_candidates.len() {
+                            app.prune_list_state.select(Some(i + 1));
+                        }
+                    }
+                    KeyCode::Char(' ') => {
+                        if let Some(i) = app.prune_list_state.selected()
+                            && !app.prune_candidates[i].is_worktree_locked
+                        {
+                            app.prune_selected[i] = !app.prune_selected[i];
+                        }
+                    }
+                    KeyCode::Enter | KeyCode::Char('y') => app.confirm_prune(),
+                    KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.mode = AppMode::Normal;
+                    }
+                    KeyCode::Esc | KeyCode::Char('n') => app.mode = AppMode::Normal,
+                    _ => {}
+                },
+
+                AppMode::Worktrees => match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        let i = app.worktree_list_state.selected().unwrap_or(0);
+                        app.worktree_list_state.select(Some(i.saturating_sub(1)));
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        let i = app.worktree_list_state.selected().unwrap_or(0);
+                        if i + 1 < app.worktrees.len() {
+                            app.worktree_list_state.select(Some(i + 1));
+                        }
+                    }
+                    KeyCode::Char('l') => app.run_worktree_action(WorktreeAction::Lock),
+                    KeyCode::Char('u') => app.run_worktree_action(WorktreeAction::Unlock),
+                    KeyCode::Char('d') => app.run_worktree_action(WorktreeAction::Remove),
+                    KeyCode::Char('p') => app.run_worktree_action(WorktreeAction::Prune),
+                    KeyCode::Enter => app.enter_selected_worktree(),
+                    KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.mode = AppMode::Normal;
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Normal,
+                    _ => {}
+                },
+
+                AppMode::TagsPrompt | AppMode::NotePrompt => match key.code {
+                    KeyCode::Enter => app.submit_meta_prompt(),
+                    KeyCode::Esc => app.mode = AppMode::Normal,
+                    KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.mode = AppMode::Normal;
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.meta_input.clear();
+                    }
+                    KeyCode::Backspace => {
+                        app.meta_input.pop();
+                    }
+                    KeyCode::Char(c) => app.meta_input.push(c),
+                    _ => {}
+                },
+
                 AppMode::RenamePrompt => match key.code {
                     KeyCode::Enter => {
                         app.rename_selected();
@@ -1728,20 +3216,7 @@ pub fn run_app(
                                 app.theme = theme.clone();
 
                                 if let Some(ref path) = app.config_path {
-                                    if let Err(e) = save_config(
-                                        path,
-                                        &app.theme,
-                                        &app.tries_dirs,
-                                        &app.editor_cmd,
-                                        app.apply_date_prefix,
-                                        app.date_prefix_format.clone(),
-                                        Some(app.transparent_background),
-                                        Some(app.show_disk),
-                                        Some(app.show_preview),
-                                        Some(app.show_legend),
-                                        Some(app.right_panel_visible),
-                                        Some(app.right_panel_width),
-                                    ) {
+                                    if let Err(e) = app.save_theme(path) {
                                         app.status_message = Some(format!("Error saving: {}", e));
                                     } else {
                                         app.status_message = Some("Theme saved.".to_string());
@@ -1792,7 +3267,7 @@ pub fn run_app(
                     KeyCode::Down | KeyCode::Char('j' | 'n') => {
                         let i = match app.config_location_state.selected() {
                             Some(i) => {
-                                if i < 1 {
+                                if i + 1 < app.config_locations.len() {
                                     i + 1
                                 } else {
                                     i
@@ -1803,35 +3278,13 @@ pub fn run_app(
                         app.config_location_state.select(Some(i));
                     }
                     KeyCode::Enter => {
-                        if let Some(i) = app.config_location_state.selected() {
-                            let config_name = get_file_config_toml_name();
-                            let path = if i == 0 {
-                                dirs::config_dir()
-                                    .unwrap_or_else(|| {
-                                        dirs::home_dir().expect("Folder not found").join(".config")
-                                    })
-                                    .join("try-rs")
-                                    .join(&config_name)
-                            } else {
-                                dirs::home_dir()
-                                    .expect("Folder not found")
-                                    .join(&config_name)
-                            };
-
-                            if let Err(e) = save_config(
-                                &path,
-                                &app.theme,
-                                &app.tries_dirs,
-                                &app.editor_cmd,
-                                app.apply_date_prefix,
-                                app.date_prefix_format.clone(),
-                                Some(app.transparent_background),
-                                Some(app.show_disk),
-                                Some(app.show_preview),
-                                Some(app.show_legend),
-                                Some(app.right_panel_visible),
-                                Some(app.right_panel_width),
-                            ) {
+                        if let Some(path) = app
+                            .config_location_state
+                            .selected()
+                            .and_then(|i| app.config_locations.get(i))
+                            .cloned()
+                        {
+                            if let Err(e) = app.save_theme(&path) {
                                 app.status_message = Some(format!("Error saving config: {}", e));
                             } else {
                                 app.config_path = Some(path);
@@ -1872,41 +3325,63 @@ pub fn run_app(
                         app.move_folder_state.select(Some(i));
                     }
                     KeyCode::Enter => {
+                        app.mode = AppMode::Normal;
                         if let Some(target_idx) = app.move_folder_state.selected() {
-                            if let Some(selected_entry) =
-                                app.filtered_entries.get(app.selected_index)
-                            {
-                                let name = selected_entry.name.clone();
-                                let src = app.base_path.join(&name);
-                                let dst = app.tries_dirs[target_idx].join(&name);
-                                if src.exists() && dst.exists() {
-                                    app.status_message = Some(format!(
-                                        "Folder '{}' already exists in target",
-                                        name
-                                    ));
-                                } else if let Err(e) = fs::rename(&src, &dst) {
-                                    app.status_message =
-                                        Some(format!("Error moving folder: {}", e));
-                                } else {
-                                    app.all_entries.retain(|e| e.name != name);
-                                    app.update_search();
-                                    app.status_message = Some(format!(
-                                        "Moved '{}' to {}",
-                                        name,
-                                        app.tries_dirs[target_idx].display()
-                                    ));
-                                }
+                            if app.selected.is_empty() {
+                                app.move_selected(target_idx);
+                            } else {
+                                app.open_batch_confirm(BatchAction::Move(target_idx));
                             }
                         }
+                    }
+                    _ => {}
+                },
+                AppMode::TemplateSelect => match key.code {
+                    KeyCode::Esc => {
+                        app.final_selection = SelectionResult::None;
+                        app.wants_editor = false;
+                        app.mode = AppMode::Normal;
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                        app.final_selection = SelectionResult::None;
+                        app.wants_editor = false;
                         app.mode = AppMode::Normal;
                     }
+                    KeyCode::Up | KeyCode::Char('k' | 'p') => {
+                        let i = match app.template_list_state.selected() {
+                            Some(i) => i.saturating_sub(1),
+                            None => 0,
+                        };
+                        app.template_list_state.select(Some(i));
+                    }
+                    KeyCode::Down | KeyCode::Char('j' | 'n') => {
+                        // Index 0 is the "empty folder" option
+                        let i = match app.template_list_state.selected() {
+                            Some(i) => (i + 1).min(app.templates.len()),
+                            None => 0,
+                        };
+                        app.template_list_state.select(Some(i));
+                    }
+                    KeyCode::Enter => {
+                        app.selected_template = app
+                            .template_list_state
+                            .selected()
+                            .and_then(|i| i.checked_sub(1))
+                            .and_then(|i| app.templates.get(i).cloned());
+                        app.should_quit = true;
+                    }
                     _ => {}
                 },
             }
         }
     }
 
-    Ok((app.final_selection, app.wants_editor, app.active_tab))
+    Ok((
+        app.final_selection,
+        app.wants_editor,
+        app.active_tab,
+        app.selected_template,
+    ))
 }
 
 #[cfg(test)]
@@ -4,9 +4,12 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::SystemTime;
 
-use chrono::{Local, NaiveDate, NaiveDateTime};
+use chrono::{Local, NaiveDateTime};
 
-const DATE_PREFIX_FORMAT: &str = "%Y-%m-%d";
+use crate::naming::NameTemplate;
+use crate::worktrees;
+
+pub const DATE_PREFIX_FORMAT: &str = "%Y-%m-%d";
 
 /// Checks if current directory is inside a git repository
 pub fn is_inside_git_repo<P: AsRef<Path>>(path: P) -> bool {
@@ -42,6 +45,62 @@ pub fn is_git_worktree(path: &Path) -> bool {
     dot_git.is_file()
 }
 
+/// Branch and working tree state of a git repository.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GitStatus {
+    /// Branch name, or `None` on a detached HEAD.
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    /// Number of changed or untracked files.
+    pub changed: usize,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+impl GitStatus {
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0
+    }
+}
+
+/// Parses the output of `git status --porcelain=v2 --branch`.
+pub fn parse_git_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(n) = count.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = count.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if !line.is_empty() {
+            status.changed += 1;
+        }
+    }
+    status
+}
+
+/// Runs `git status` in `path`. Returns `None` when it is not a repository or git fails.
+pub fn get_git_status(path: &Path) -> Option<GitStatus> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_git_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
 fn parse_dot_git(dot_git: &Path) -> std::io::Result<PathBuf> {
     Ok(first_line(&std::fs::read(dot_git)?).into())
 }
@@ -118,6 +177,8 @@ pub fn get_free_disk_space_mb(path: &Path) -> Option<u64> {
     unsafe {
         if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) == 0 {
             let stat = stat.assume_init();
+            // Field widths differ across platforms (e.g. u32 on macOS)
+            #[allow(clippy::unnecessary_cast)]
             let free_bytes = (stat.f_bavail as u64) * (stat.f_frsize as u64);
             return Some(free_bytes / (1024 * 1024));
         }
@@ -130,18 +191,16 @@ pub fn get_free_disk_space_mb(_path: &Path) -> Option<u64> {
     None
 }
 
-pub fn extract_prefix_date(name: &str) -> Option<(SystemTime, String)> {
-    let (lhs, rhs) = name.split_once(' ')?;
-    let naive_date = NaiveDate::parse_from_str(lhs, DATE_PREFIX_FORMAT).ok()?;
-    let dt: NaiveDateTime = naive_date.into();
+/// The date and plain name of a folder named with `template`, if it has a
+/// date, and the char offset of the plain name in `name`.
+pub fn extract_prefix_date(
+    name: &str,
+    template: &NameTemplate,
+) -> Option<(SystemTime, String, usize)> {
+    let (naive_date, rest, offset) = template.extract(name)?;
+    let dt: NaiveDateTime = naive_date?.into();
     let dt_local = dt.and_local_timezone(Local).single()?;
-    Some((dt_local.into(), rhs.into()))
-}
-
-pub fn generate_prefix_date(format: Option<&str>) -> String {
-    let now = Local::now();
-    let fmt = format.unwrap_or(DATE_PREFIX_FORMAT);
-    now.format(fmt).to_string()
+    Some((dt_local.into(), rest, offset))
 }
 
 pub fn get_folder_size_mb(path: &Path) -> u64 {
@@ -170,18 +229,154 @@ pub fn get_folder_size_mb(path: &Path) -> u64 {
     dir_size(path) / (1024 * 1024)
 }
 
-pub fn matching_folders(name: &str, path: &PathBuf) -> Vec<(PathBuf, String)> {
+/// Copies the folder `src` to `dest`, recreating symlinks instead of
+/// following them.
+pub fn copy_dir_all(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let dest_path = dest.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &dest_path)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &dest_path)?;
+        } else {
+            fs::copy(entry.path(), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Moves a directory, falling back to copy-and-delete when `rename` can't
+/// cross filesystems.
+pub fn move_dir(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if dest.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists", dest.display()),
+        ));
+    }
+    match fs::rename(src, dest) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        result => return result,
+    }
+    if let Err(e) = copy_dir_all(src, dest) {
+        let _ = fs::remove_dir_all(dest);
+        return Err(e);
+    }
+    fs::remove_dir_all(src)
+}
+
+/// Moves a try folder. Git worktrees are moved with `git worktree move`, so
+/// the main repository's `worktrees/<name>/gitdir` follows them.
+pub fn move_try(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if is_git_worktree(src) {
+        move_git_worktree(src, dest)
+    } else {
+        move_dir(src, dest)
+    }
+}
+
+/// Moves a git worktree, refusing locked ones, then checks that both the
+/// worktree and its main repository list it at `dest`.
+pub fn move_git_worktree(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if is_git_worktree_locked(src) {
+        return Err(std::io::Error::other(format!(
+            "'{}' is a locked git worktree, unlock it with `git worktree unlock` first",
+            src.display()
+        )));
+    }
+    if dest.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists", dest.display()),
+        ));
+    }
+    let output = Command::new("git")
+        .arg("worktree")
+        .arg("move")
+        .arg(src)
+        .arg(dest)
+        .current_dir(src)
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    verify_git_worktree(dest)
+}
+
+/// The worktrees `git worktree list` shows from `dir`, the main one first,
+/// each with whether git considers it prunable (its directory is gone).
+fn list_git_worktrees(dir: &Path) -> std::io::Result<Vec<(PathBuf, bool)>> {
+    let output = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(
+        worktrees::parse_worktree_list(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .map(|worktree| (worktree.path, worktree.prunable.is_some()))
+            .collect(),
+    )
+}
+
+/// Checks that `git worktree list`, run from the worktree and from its main
+/// repository, shows the worktree at `path`.
+pub fn verify_git_worktree(path: &Path) -> std::io::Result<()> {
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let path = canonical(path);
+    let registered = |worktrees: &[(PathBuf, bool)]| {
+        worktrees
+            .iter()
+            .any(|(worktree, prunable)| !prunable && canonical(worktree) == path)
+    };
+    let missing = |side: &str| {
+        std::io::Error::other(format!(
+            "moved, but `git worktree list` in the {} does not show '{}'",
+            side,
+            path.display()
+        ))
+    };
+
+    let from_worktree = list_git_worktrees(&path)?;
+    if !registered(&from_worktree) {
+        return Err(missing("worktree"));
+    }
+    let Some((main, _)) = from_worktree.first() else {
+        return Err(missing("worktree"));
+    };
+    if !registered(&list_git_worktrees(main)?) {
+        return Err(missing("main repository"));
+    }
+    Ok(())
+}
+
+/// Folders in `path` called `name`, or named with `template` for `name`.
+pub fn matching_folders(
+    name: &str,
+    path: &PathBuf,
+    template: &NameTemplate,
+) -> Vec<(PathBuf, String)> {
     let mut result = vec![];
-    if let Ok(read_dir) = fs::read_dir(&path) {
+    if let Ok(read_dir) = fs::read_dir(path) {
         for entry in read_dir.flatten() {
             if let Ok(metadata) = entry.metadata()
                 && metadata.is_dir()
             {
                 let filename = entry.file_name().to_string_lossy().to_string();
-                if filename == name {
-                    result.push((path.clone(), filename));
-                } else if let Some((_, stripped_name)) = extract_prefix_date(&filename)
-                    && name == stripped_name
+                if filename == name
+                    || template
+                        .extract(&filename)
+                        .is_some_and(|(_, stripped_name, _)| name == stripped_name)
                 {
                     result.push((path.clone(), filename));
                 }
@@ -191,12 +386,66 @@ pub fn matching_folders(name: &str, path: &PathBuf) -> Vec<(PathBuf, String)> {
     result
 }
 
+/// Reads a data file written by try-rs. A file that exists but can't be
+/// parsed is moved aside to `<file>.bak`, or `<file>.bak.1`, `.bak.2`... if
+/// that is taken, so the next save doesn't wipe what was in it. Returns the parsed value (or the default) and, if something was
+/// wrong, a warning to show plus whether the path is still safe to save to.
+pub fn load_data_file<T: serde::de::DeserializeOwned + Default>(
+    path: &Path,
+) -> (T, Option<String>, bool) {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (T::default(), None, true),
+        Err(e) => {
+            let warning = format!("could not read {}: {e}", path.display());
+            return (T::default(), Some(warning), false);
+        }
+    };
+    match toml::from_str(&contents) {
+        Ok(value) => (value, None, true),
+        Err(e) => {
+            // Never overwrite the backup of an earlier bad file
+            let backup = (0..)
+                .map(|n| {
+                    let mut backup = path.as_os_str().to_owned();
+                    backup.push(".bak");
+                    if n > 0 {
+                        backup.push(format!(".{n}"));
+                    }
+                    PathBuf::from(backup)
+                })
+                .find(|backup| backup.symlink_metadata().is_err())
+                .expect("some backup name is free");
+            let error = e.message().trim_end().to_string();
+            match fs::rename(path, &backup) {
+                Ok(()) => {
+                    let warning = format!(
+                        "could not parse {} ({error}), moved it to {}",
+                        path.display(),
+                        backup.display()
+                    );
+                    (T::default(), Some(warning), true)
+                }
+                Err(_) => {
+                    let warning = format!(
+                        "could not parse {} ({error}), changes won't be saved to it",
+                        path.display()
+                    );
+                    (T::default(), Some(warning), false)
+                }
+            }
+        }
+    }
+}
+
 // i've put this here since until now there is not really a library part
 pub enum SelectionResult {
     /// A explicit folder that is guaranteed to exist already
     Folder(String),
     /// No existing match, a new folder should be created
     New(String),
+    /// An existing folder to open in the configured terminal multiplexer
+    Multiplexer(String),
     /// Nothing was selected in the UI, quit
     None,
 }
@@ -0,0 +1,459 @@
+use crate::cli::WorktreeCommand;
+use crate::naming::NameTemplate;
+use crate::utils;
+use anyhow::{Result, bail};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// One entry of `git worktree list --porcelain`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Worktree {
+    pub path: PathBuf,
+    pub head: Option<String>,
+    /// Branch name without `refs/heads/`, or `None` when detached or bare.
+    pub branch: Option<String>,
+    pub detached: bool,
+    pub bare: bool,
+    /// The lock reason, empty when the worktree was locked without one.
+    pub locked: Option<String>,
+    /// Why git would prune the worktree, e.g. its folder was deleted.
+    pub prunable: Option<String>,
+    /// The worktree's folder does not exist.
+    pub missing: bool,
+    /// The main worktree, which git never moves, locks or removes.
+    pub is_main: bool,
+    /// The worktree is a folder of one of the tries directories.
+    pub is_try: bool,
+}
+
+impl Worktree {
+    /// Branch, `(detached)` or `(bare)`.
+    pub fn branch_label(&self) -> String {
+        match &self.branch {
+            Some(branch) => branch.clone(),
+            None if self.bare => "(bare)".to_string(),
+            None => "(detached)".to_string(),
+        }
+    }
+
+    /// Comma-separated lock and prune state, or `ok`.
+    pub fn state_label(&self) -> String {
+        let mut state = Vec::new();
+        if self.locked.is_some() {
+            state.push("locked");
+        }
+        if self.missing {
+            state.push("missing");
+        }
+        if self.prunable.is_some() {
+            state.push("prunable");
+        }
+        if state.is_empty() {
+            "ok".to_string()
+        } else {
+            state.join(",")
+        }
+    }
+
+    /// `main`, `try` or `worktree`.
+    pub fn kind_label(&self) -> &'static str {
+        if self.is_main {
+            "main"
+        } else if self.is_try {
+            "try"
+        } else {
+            "worktree"
+        }
+    }
+}
+
+/// Parses the output of `git worktree list --porcelain`. The first entry is
+/// the main worktree.
+pub fn parse_worktree_list(porcelain: &str) -> Vec<Worktree> {
+    let mut worktrees: Vec<Worktree> = Vec::new();
+    for line in porcelain.lines() {
+        let (key, value) = match line.split_once(' ') {
+            Some((key, value)) => (key, Some(value.to_string())),
+            None => (line, None),
+        };
+        if key == "worktree" {
+            worktrees.push(Worktree {
+                path: PathBuf::from(value.unwrap_or_default()),
+                is_main: worktrees.is_empty(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(worktree) = worktrees.last_mut() else {
+            continue;
+        };
+        match key {
+            "HEAD" => worktree.head = value,
+            "branch" => {
+                worktree.branch = value.map(|branch| {
+                    branch
+                        .strip_prefix("refs/heads/")
+                        .map(str::to_string)
+                        .unwrap_or(branch)
+                })
+            }
+            "detached" => worktree.detached = true,
+            "bare" => worktree.bare = true,
+            "locked" => worktree.locked = Some(value.unwrap_or_default()),
+            "prunable" => worktree.prunable = Some(value.unwrap_or_default()),
+            _ => {}
+        }
+    }
+    worktrees
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Runs `git` in `dir`, returning its stdout, or its stderr as the error.
+fn git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Lists the worktrees of the repository `repo` is in, marking the ones
+/// that are tries.
+pub fn list_worktrees(repo: &Path, tries_dirs: &[PathBuf]) -> io::Result<Vec<Worktree>> {
+    let tries_dirs: Vec<PathBuf> = tries_dirs.iter().map(|dir| canonical(dir)).collect();
+    let mut worktrees = parse_worktree_list(&git(repo, &["worktree", "list", "--porcelain"])?);
+    for worktree in &mut worktrees {
+        worktree.missing = !worktree.path.exists();
+        // A missing folder can't be canonicalized, so compare its parent.
+        worktree.is_try = !worktree.is_main
+            && worktree
+                .path
+                .parent()
+                .is_some_and(|parent| tries_dirs.contains(&canonical(parent)));
+    }
+    Ok(worktrees)
+}
+
+/// Finds the worktree `query` names: its path, its folder name (with or
+/// without the name template's date), or its branch, in that order.
+pub fn find<'a>(
+    worktrees: &'a [Worktree],
+    query: &str,
+    name_template: &NameTemplate,
+) -> Option<&'a Worktree> {
+    let path = canonical(Path::new(query));
+    let folder_name = |worktree: &Worktree| {
+        worktree
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    worktrees
+        .iter()
+        .find(|w| canonical(&w.path) == path)
+        .or_else(|| worktrees.iter().find(|w| folder_name(w) == query))
+        .or_else(|| {
+            worktrees.iter().find(|w| {
+                name_template
+                    .extract(&folder_name(w))
+                    .is_some_and(|(_, name, _)| name == query)
+            })
+        })
+        .or_else(|| {
+            worktrees
+                .iter()
+                .find(|w| w.branch.as_deref() == Some(query))
+        })
+}
+
+/// Locks `worktree` so `git worktree prune` and try-rs leave it alone.
+pub fn lock(repo: &Path, worktree: &Worktree, reason: Option<&str>) -> io::Result<()> {
+    if worktree.is_main {
+        return Err(io::Error::other("the main worktree can't be locked"));
+    }
+    let path = worktree.path.to_string_lossy();
+    let mut args = vec!["worktree", "lock"];
+    if let Some(reason) = reason {
+        args.extend(["--reason", reason]);
+    }
+    args.push(&path);
+    git(repo, &args).map(|_| ())
+}
+
+pub fn unlock(repo: &Path, worktree: &Worktree) -> io::Result<()> {
+    git(
+        repo,
+        &["worktree", "unlock", &worktree.path.to_string_lossy()],
+    )
+    .map(|_| ())
+}
+
+/// Removes `worktree` and its folder. Without `force`, git refuses
+/// worktrees with changes and locked ones.
+pub fn remove(repo: &Path, worktree: &Worktree, force: bool) -> io::Result<()> {
+    if worktree.is_main {
+        return Err(io::Error::other("the main worktree can't be removed"));
+    }
+    let path = worktree.path.to_string_lossy();
+    let mut args = vec!["worktree", "remove"];
+    if force {
+        args.push("--force");
+    }
+    args.push(&path);
+    git(repo, &args).map(|_| ())
+}
+
+/// Runs `git worktree prune`, dropping the metadata of worktrees whose
+/// folder is gone. Returns what was (or, on a dry run, would be) pruned.
+pub fn prune(repo: &Path, dry_run: bool) -> io::Result<Vec<String>> {
+    let mut args = vec!["worktree", "prune", "--verbose"];
+    if dry_run {
+        args.push("--dry-run");
+    }
+    let output = Command::new("git").args(&args).current_dir(repo).output()?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if !output.status.success() {
+        return Err(io::Error::other(text.trim().to_string()));
+    }
+    Ok(text.lines().map(str::to_string).collect())
+}
+
+/// Handles the `worktree` subcommand. Returns the folder `jump` should
+/// enter, which the caller prints as an action.
+pub fn run_worktree_command(
+    command: &WorktreeCommand,
+    repo: &Path,
+    tries_dirs: &[PathBuf],
+    name_template: &NameTemplate,
+) -> Result<Option<PathBuf>> {
+    let worktrees = match list_worktrees(repo, tries_dirs) {
+        Ok(worktrees) => worktrees,
+        Err(e) => bail!("'{}' is not inside a git repository: {}", repo.display(), e),
+    };
+    let target = |query: &str| match find(&worktrees, query, name_template) {
+        Some(worktree) => Ok(worktree),
+        None => bail!("'{}' is not a worktree of this repository", query),
+    };
+    match command {
+        WorktreeCommand::List => {
+            for worktree in &worktrees {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    worktree.path.display(),
+                    worktree.branch_label(),
+                    worktree.state_label(),
+                    worktree.kind_label()
+                );
+            }
+        }
+        WorktreeCommand::Lock {
+            target: query,
+            reason,
+        } => {
+            let worktree = target(query)?;
+            lock(repo, worktree, reason.as_deref())?;
+            eprintln!("Locked {}", worktree.path.display());
+        }
+        WorktreeCommand::Unlock { target: query } => {
+            let worktree = target(query)?;
+            unlock(repo, worktree)?;
+            eprintln!("Unlocked {}", worktree.path.display());
+        }
+        WorktreeCommand::Remove {
+            target: query,
+            force,
+        } => {
+            let worktree = target(query)?;
+            remove(repo, worktree, *force)?;
+            eprintln!("Removed {}", worktree.path.display());
+        }
+        WorktreeCommand::Prune { dry_run } => {
+            let pruned = prune(repo, *dry_run)?;
+            if pruned.is_empty() {
+                eprintln!("No stale worktree entries");
+            }
+            for line in pruned {
+                println!("{}", line);
+            }
+        }
+        WorktreeCommand::Jump { target: query } => {
+            let worktree = target(query)?;
+            if worktree.missing {
+                bail!("'{}' no longer exists", worktree.path.display());
+            }
+            return Ok(Some(worktree.path.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Turns a branch name into a folder name: `feature/x` becomes `feature-x`.
+pub fn branch_folder_name(branch: &str) -> String {
+    branch
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Where the branch of a new worktree comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BranchSource {
+    /// The local branch already exists.
+    Local,
+    /// A new local branch tracking this remote branch, e.g. `origin/feat`.
+    Remote(String),
+    /// A new branch starting at this ref, or at HEAD.
+    New(Option<String>),
+}
+
+/// Decides where `branch` comes from: the local branch if there is one,
+/// else `base` when given, else a remote branch of the same name (from
+/// `origin` when several remotes have it).
+pub fn branch_source(repo: &Path, branch: &str, base: Option<&str>) -> io::Result<BranchSource> {
+    let local = format!("refs/heads/{branch}");
+    if git(repo, &["show-ref", "--verify", "--quiet", &local]).is_ok() {
+        return Ok(BranchSource::Local);
+    }
+    if let Some(base) = base {
+        return Ok(BranchSource::New(Some(base.to_string())));
+    }
+    let remotes = git(
+        repo,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)",
+            &format!("refs/remotes/*/{branch}"),
+        ],
+    )?;
+    let remotes: Vec<&str> = remotes.lines().collect();
+    let origin = format!("origin/{branch}");
+    Ok(
+        match remotes.iter().find(|r| **r == origin).or(remotes.first()) {
+            Some(remote) => BranchSource::Remote(remote.to_string()),
+            None => BranchSource::New(None),
+        },
+    )
+}
+
+/// Arguments of the `git worktree add` that creates `path` on `branch`.
+pub fn worktree_add_args(branch: &str, path: &Path, source: &BranchSource) -> Vec<String> {
+    let path = path.to_string_lossy().to_string();
+    let mut args = vec!["worktree".to_string(), "add".to_string()];
+    match source {
+        BranchSource::Local => args.extend([path, branch.to_string()]),
+        BranchSource::Remote(remote) => args.extend([
+            "--track".to_string(),
+            "-b".to_string(),
+            branch.to_string(),
+            path,
+            remote.clone(),
+        ]),
+        BranchSource::New(base) => {
+            args.extend(["-b".to_string(), branch.to_string(), path]);
+            args.extend(base.clone());
+        }
+    }
+    args
+}
+
+/// The main working tree of the repository `dir` is in, or the current
+/// one for a bare repository.
+pub fn main_worktree(dir: &Path) -> io::Result<PathBuf> {
+    let worktrees = parse_worktree_list(&git(dir, &["worktree", "list", "--porcelain"])?);
+    match worktrees.first() {
+        Some(main) if !main.bare => Ok(main.path.clone()),
+        _ => Ok(PathBuf::from(
+            git(dir, &["rev-parse", "--show-toplevel"])?.trim(),
+        )),
+    }
+}
+
+/// True when `pattern` could match outside the working tree it is relative
+/// to: it is absolute or has a `..` component.
+pub fn copy_pattern_escapes(pattern: &str) -> bool {
+    Path::new(pattern)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Whether `dest` lands inside `worktree`, following the symlinks in the
+/// part of it that already exists.
+fn inside_worktree(worktree: &Path, dest: &Path) -> bool {
+    let Ok(root) = worktree.canonicalize() else {
+        return false;
+    };
+    dest.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+        .is_some_and(|ancestor| ancestor.starts_with(root))
+}
+
+/// Copies what matches `patterns`, globs relative to the main working tree
+/// such as `.env*`, into `worktree`. Anything the worktree already has, and
+/// `.git`, is left alone, and symlinks are recreated. Nothing is written
+/// outside `worktree`: patterns with `..` are refused, and so are matches
+/// whose destination a symlink in the worktree would send elsewhere.
+/// Returns the copied paths, relative to the working tree.
+pub fn copy_into_worktree(
+    main: &Path,
+    worktree: &Path,
+    patterns: &[String],
+) -> io::Result<Vec<PathBuf>> {
+    let root = glob::Pattern::escape(&main.to_string_lossy());
+    let mut copied = Vec::new();
+    for pattern in patterns {
+        if copy_pattern_escapes(pattern) {
+            return Err(io::Error::other(format!(
+                "pattern '{pattern}' reaches outside the working tree"
+            )));
+        }
+        let matches = glob::glob(&format!("{root}/{pattern}"))
+            .map_err(|e| io::Error::other(format!("invalid pattern '{pattern}': {e}")))?;
+        for source in matches.flatten() {
+            let Ok(relative) = source.strip_prefix(main) else {
+                continue;
+            };
+            let dest = worktree.join(relative);
+            if relative.starts_with(".git")
+                || dest.symlink_metadata().is_ok()
+                || !inside_worktree(worktree, &dest)
+            {
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let metadata = source.symlink_metadata()?;
+            if metadata.is_symlink() {
+                #[cfg(unix)]
+                std::os::unix::fs::symlink(fs::read_link(&source)?, &dest)?;
+                #[cfg(not(unix))]
+                continue;
+            } else if metadata.is_dir() {
+                utils::copy_dir_all(&source, &dest)?;
+            } else {
+                fs::copy(&source, &dest)?;
+            }
+            copied.push(relative.to_path_buf());
+        }
+    }
+    Ok(copied)
+}
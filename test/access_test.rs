@@ -0,0 +1,82 @@
+use tempdir::TempDir;
+use try_rs::access::*;
+
+const NOW: u64 = 1_700_000_000;
+
+#[test]
+fn frecency_weights_recent_visits() {
+    let tmp = TempDir::new("access-weights").unwrap();
+    let mut log = AccessLog::load(tmp.path().join("access.toml"));
+    let path = tmp.path().join("demo");
+
+    assert_eq!(log.frecency(&path, NOW), 0.0);
+    log.record(&path, NOW).unwrap();
+    log.record(&path, NOW).unwrap();
+
+    assert_eq!(log.frecency(&path, NOW + 60), 8.0);
+    assert_eq!(log.frecency(&path, NOW + 2 * 60 * 60), 4.0);
+    assert_eq!(log.frecency(&path, NOW + 2 * 24 * 60 * 60), 1.0);
+    assert_eq!(log.frecency(&path, NOW + 30 * 24 * 60 * 60), 0.5);
+}
+
+#[test]
+fn record_persists_and_rename_carries_history() {
+    let tmp = TempDir::new("access-save").unwrap();
+    let file = tmp.path().join("access.toml");
+    let old = tmp.path().join("old");
+    let new = tmp.path().join("new");
+
+    let mut log = AccessLog::load(file.clone());
+    log.record(&old, NOW).unwrap();
+    log.rename(&old, &new).unwrap();
+
+    let reloaded = AccessLog::load(file);
+    assert_eq!(reloaded.frecency(&old, NOW), 0.0);
+    assert_eq!(reloaded.frecency(&new, NOW), 4.0);
+}
+
+#[test]
+fn unparsable_log_is_moved_aside_before_saving() {
+    let tmp = TempDir::new("access-corrupt").unwrap();
+    let file = tmp.path().join("access.toml");
+    std::fs::write(&file, "tries = 3").unwrap();
+
+    let mut log = AccessLog::load(file.clone());
+    assert!(log.warning().unwrap().contains("access.toml.bak"));
+    log.record(&tmp.path().join("demo"), NOW).unwrap();
+
+    let backup = tmp.path().join("access.toml.bak");
+    assert_eq!(std::fs::read_to_string(backup).unwrap(), "tries = 3");
+    assert!(AccessLog::load(file).warning().is_none());
+}
+
+#[test]
+fn record_decays_ranks_past_the_limit() {
+    let tmp = TempDir::new("access-decay").unwrap();
+    let mut log = AccessLog::default();
+    let busy = tmp.path().join("busy");
+    let once = tmp.path().join("once");
+
+    log.record(&once, NOW).unwrap();
+    for _ in 0..10_000 {
+        log.record(&busy, NOW).unwrap();
+    }
+
+    assert_eq!(log.frecency(&once, NOW), 0.0);
+    assert!(log.frecency(&busy, NOW) < 10_000.0 * 4.0);
+}
+
+#[test]
+fn unparsable_log_keeps_earlier_backups() {
+    let tmp = TempDir::new("access-corrupt-twice").unwrap();
+    let file = tmp.path().join("access.toml");
+    std::fs::write(&file, "tries = 1").unwrap();
+    AccessLog::load(file.clone());
+    std::fs::write(&file, "tries = 2").unwrap();
+
+    let log = AccessLog::load(file);
+    assert!(log.warning().unwrap().contains("access.toml.bak.1"));
+    let backup = |name: &str| std::fs::read_to_string(tmp.path().join(name)).unwrap();
+    assert_eq!(backup("access.toml.bak"), "tries = 1");
+    assert_eq!(backup("access.toml.bak.1"), "tries = 2");
+}
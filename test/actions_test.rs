@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+use try_rs::actions::*;
+use try_rs::cli::Shell;
+
+fn enter(editor_cmd: Option<&str>, enter_command: Option<&str>) -> EnterOptions {
+    EnterOptions {
+        output: Output::Protocol,
+        editor_cmd: editor_cmd.map(String::from),
+        enter_command: enter_command.map(String::from),
+    }
+}
+
+#[test]
+fn to_line_escapes_tabs_newlines_and_backslashes() {
+    let action = Action::Edit {
+        path: PathBuf::from("/tries/a\tb\nc\\d\u{1}"),
+        cmd: "code -n".to_string(),
+    };
+    assert_eq!(
+        action.to_line(),
+        "edit\t/tries/a\\tb\\nc\\\\d\\u0001\tcode -n"
+    );
+
+    let mux = Action::Mux {
+        path: PathBuf::from("/tries/x"),
+        argv: vec!["tmux".to_string(), "new-window".to_string()],
+    };
+    assert_eq!(mux.to_line(), "mux\t/tries/x\ttmux\tnew-window");
+}
+
+#[test]
+fn to_command_quotes_for_each_shell() {
+    let run = Action::Run {
+        path: PathBuf::from("/tries/it's"),
+        cmd: "git status".to_string(),
+    };
+    assert_eq!(
+        run.to_command(&Shell::Bash),
+        r"cd '/tries/it'\''s' && git status"
+    );
+    assert_eq!(
+        run.to_command(&Shell::Fish),
+        r"cd '/tries/it\'s'; and git status"
+    );
+    assert_eq!(
+        run.to_command(&Shell::PowerShell),
+        "Set-Location -LiteralPath '/tries/it''s'; git status"
+    );
+    assert_eq!(
+        run.to_command(&Shell::Elvish),
+        "cd '/tries/it''s'; git status"
+    );
+
+    let mux = Action::Mux {
+        path: PathBuf::from("/tries/x"),
+        argv: vec!["tmux".to_string(), "new-window".to_string()],
+    };
+    assert_eq!(mux.to_command(&Shell::Zsh), "'tmux' 'new-window'");
+    assert_eq!(mux.to_command(&Shell::NuShell), r#"^"tmux" "new-window""#);
+    assert_eq!(mux.to_command(&Shell::Xonsh), "!['tmux' 'new-window']");
+}
+
+#[test]
+fn enter_action_prefers_editor_then_enter_command() {
+    let path = PathBuf::from("/tries/x");
+    assert_eq!(
+        enter(Some("code"), Some("ls")).action(&path, true),
+        Action::Edit {
+            path: path.clone(),
+            cmd: "code".to_string()
+        }
+    );
+    assert_eq!(
+        enter(Some("code"), Some("ls")).action(&path, false),
+        Action::Run {
+            path: path.clone(),
+            cmd: "ls".to_string()
+        }
+    );
+    assert_eq!(
+        enter(None, None).action(&path, true),
+        Action::Cd { path: path.clone() }
+    );
+}
+
+#[test]
+fn multiplexer_argv_depends_on_being_inside() {
+    let path = PathBuf::from("/tries/v1.2");
+    assert_eq!(Multiplexer::from_name("Tmux"), Some(Multiplexer::Tmux));
+    assert_eq!(Multiplexer::from_name("screen"), None);
+    assert_eq!(
+        Multiplexer::Tmux.argv(&path, "v1.2", true),
+        ["tmux", "new-window", "-c", "/tries/v1.2", "-n", "v1.2"]
+    );
+    assert_eq!(
+        Multiplexer::Tmux.argv(&path, "v1.2", false),
+        [
+            "tmux",
+            "new-session",
+            "-A",
+            "-s",
+            "v1_2",
+            "-c",
+            "/tries/v1.2"
+        ]
+    );
+    assert_eq!(
+        Multiplexer::Zellij.argv(&path, "v1.2", false),
+        [
+            "zellij",
+            "attach",
+            "--create",
+            "v1_2",
+            "options",
+            "--default-cwd",
+            "/tries/v1.2"
+        ]
+    );
+}
@@ -1,5 +1,5 @@
 use clap::Parser;
-use try_rs::cli::{Cli, Shell};
+use try_rs::cli::{Cli, Commands, Shell, TrashCommand};
 
 #[test]
 fn cli_default_values() {
@@ -19,6 +19,7 @@ fn cli_default_values() {
     assert!(cli.hide_legend);
     assert!(!cli.show_right_panel);
     assert!(cli.hide_right_panel);
+    assert!(cli.command.is_none());
 }
 
 #[test]
@@ -27,6 +28,16 @@ fn cli_with_name() {
     assert_eq!(cli.name_or_url, Some("my-project".to_string()));
 }
 
+#[test]
+fn cli_double_dash_takes_subcommand_names_as_names() {
+    let cli = Cli::try_parse_from(["try-rs", "list"]).unwrap();
+    assert!(cli.command.is_some());
+
+    let cli = Cli::try_parse_from(["try-rs", "--", "list"]).unwrap();
+    assert!(cli.command.is_none());
+    assert_eq!(cli.name_or_url, Some("list".to_string()));
+}
+
 #[test]
 fn cli_with_git_url() {
     let cli = Cli::try_parse_from(["try-rs", "https://github.com/user/repo.git"]).unwrap();
@@ -91,12 +102,8 @@ fn cli_completions_flag() {
 
 #[test]
 fn cli_full_clone_flag() {
-    let cli = Cli::try_parse_from([
-        "try-rs",
-        "--full-clone",
-        "https://github.com/user/repo.git",
-    ])
-    .unwrap();
+    let cli = Cli::try_parse_from(["try-rs", "--full-clone", "https://github.com/user/repo.git"])
+        .unwrap();
     assert!(cli.full_clone);
 }
 
@@ -197,7 +204,7 @@ fn shell_enum_equality() {
 #[test]
 fn shell_enum_clone() {
     let shell = Shell::Zsh;
-    let cloned = shell.clone();
+    let cloned = shell;
     assert_eq!(shell, cloned);
 }
 
@@ -240,3 +247,36 @@ fn cli_ssh_git_url() {
         Some("git@github.com:user/repo.git".to_string())
     );
 }
+
+#[test]
+fn cli_trash_restore_subcommand() {
+    let cli = Cli::try_parse_from(["try-rs", "trash", "restore", "demo"]).unwrap();
+    assert!(cli.name_or_url.is_none());
+    match cli.command {
+        Some(Commands::Trash {
+            command: TrashCommand::Restore { target },
+        }) => assert_eq!(target, "demo"),
+        _ => panic!("expected trash restore"),
+    }
+}
+
+#[test]
+fn cli_trash_empty_expired() {
+    let cli = Cli::try_parse_from(["try-rs", "trash", "empty", "--expired"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Trash {
+            command: TrashCommand::Empty {
+                expired: true,
+                yes: false
+            }
+        })
+    ));
+    let cli = Cli::try_parse_from(["try-rs", "trash", "empty", "-y"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Trash {
+            command: TrashCommand::Empty { yes: true, .. }
+        })
+    ));
+}
@@ -0,0 +1,19 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `git args` in `dir` with a throwaway identity, and fails the test if
+/// it fails.
+pub fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "user.name=try-rs",
+            "-c",
+            "user.email=try-rs@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::git;
+use std::fs;
+use tempdir::TempDir;
+use try_rs::complete::*;
+use try_rs::naming::NameTemplate;
+
+#[test]
+fn folder_candidates_include_names_without_date_prefix() {
+    let first = TempDir::new("try-complete-a").unwrap();
+    let second = TempDir::new("try-complete-b").unwrap();
+    fs::create_dir(first.path().join("2024-05-01 tokio-bench")).unwrap();
+    fs::create_dir(first.path().join("notes")).unwrap();
+    fs::write(first.path().join("README.md"), "not a try").unwrap();
+    fs::create_dir(second.path().join("notes")).unwrap();
+
+    let candidates = folder_candidates(
+        &[first.path().to_path_buf(), second.path().to_path_buf()],
+        &NameTemplate::default(),
+    );
+    assert_eq!(
+        candidates,
+        ["2024-05-01 tokio-bench", "notes", "tokio-bench"]
+    );
+}
+
+#[test]
+fn folder_candidates_follow_the_name_template() {
+    let dir = TempDir::new("try-complete-template").unwrap();
+    fs::create_dir(dir.path().join("20240501_tokio-bench")).unwrap();
+    fs::create_dir(dir.path().join("2024-05-01 notes")).unwrap();
+
+    let template = NameTemplate::parse("{date:%Y%m%d}_{name}").unwrap();
+    let candidates = folder_candidates(&[dir.path().to_path_buf()], &template);
+    assert_eq!(
+        candidates,
+        ["2024-05-01 notes", "20240501_tokio-bench", "tokio-bench"]
+    );
+}
+
+#[test]
+fn branch_candidates_list_local_branches() {
+    let repo = TempDir::new("try-complete-git").unwrap();
+    git(repo.path(), &["init", "-q", "-b", "main"]);
+    git(
+        repo.path(),
+        &["commit", "-q", "--allow-empty", "-m", "init"],
+    );
+    git(repo.path(), &["branch", "feature/x"]);
+
+    assert_eq!(branch_candidates(repo.path()), ["feature/x", "main"]);
+    assert!(branch_candidates(&std::env::temp_dir().join("missing")).is_empty());
+}
+
+#[test]
+fn theme_candidates_are_theme_names() {
+    let themes = theme_candidates();
+    assert!(themes.contains(&"Dracula".to_string()));
+}
@@ -11,10 +11,10 @@ fn save_and_reload_config() {
     let theme = Theme::default();
     let tries_path = PathBuf::from("/tmp/tries");
 
-save_config(
+    save_config(
         &config_path,
         &theme,
-        &[tries_path],
+        Some(&[tries_path]),
         &Some("code".to_string()),
         Some(true),
         None,
@@ -50,7 +50,7 @@ fn save_config_creates_parent_dirs() {
     save_config(
         &config_path,
         &theme,
-        &[PathBuf::from("/tmp/t")],
+        Some(&[PathBuf::from("/tmp/t")]),
         &None,
         None,
         None,
@@ -75,7 +75,7 @@ fn save_config_none_optionals() {
     save_config(
         &config_path,
         &theme,
-        &[PathBuf::from("/tmp/t")],
+        Some(&[PathBuf::from("/tmp/t")]),
         &None,
         None,
         None,
@@ -115,6 +115,7 @@ fn config_serialization_roundtrip() {
         show_legend: Some(false),
         show_right_panel: Some(true),
         right_panel_width: Some(25),
+        ..Default::default()
     };
 
     let toml_str = toml::to_string(&config).unwrap();
@@ -189,7 +190,7 @@ fn save_config_preserves_theme_name() {
         save_config(
             &config_path,
             theme,
-            &[PathBuf::from("/tmp/t")],
+            Some(&[PathBuf::from("/tmp/t")]),
             &None,
             None,
             None,
@@ -245,7 +246,7 @@ fn config_save_and_load_roundtrip() {
     save_config(
         &config_path,
         &theme,
-        &[PathBuf::from("~/tries")],
+        Some(&[PathBuf::from("~/tries")]),
         &Some("nvim".to_string()),
         Some(true),
         None,
@@ -314,6 +315,7 @@ fn config_preserve_booleans_exact() {
         show_legend: Some(false),
         show_right_panel: Some(true),
         right_panel_width: Some(41),
+        ..Default::default()
     };
 
     let toml = toml::to_string(&config1).unwrap();
@@ -346,6 +348,7 @@ fn config_with_empty_strings() {
         show_legend: None,
         show_right_panel: None,
         right_panel_width: None,
+        ..Default::default()
     };
 
     let toml = toml::to_string(&config).unwrap();
@@ -365,7 +368,7 @@ fn config_save_overwrites_existing() {
     save_config(
         &config_path,
         &theme1,
-        &[PathBuf::from("/path1")],
+        Some(&[PathBuf::from("/path1")]),
         &Some("editor1".to_string()),
         Some(true),
         None,
@@ -382,7 +385,7 @@ fn config_save_overwrites_existing() {
     save_config(
         &config_path,
         &theme2,
-        &[PathBuf::from("/path2")],
+        Some(&[PathBuf::from("/path2")]),
         &Some("editor2".to_string()),
         Some(false),
         None,
@@ -425,6 +428,7 @@ fn config_serialization_order() {
         show_legend: Some(false),
         show_right_panel: Some(true),
         right_panel_width: Some(37),
+        ..Default::default()
     };
 
     let toml = toml::to_string(&config).unwrap();
@@ -505,6 +509,7 @@ fn config_handles_very_long_values() {
         show_legend: None,
         show_right_panel: None,
         right_panel_width: None,
+        ..Default::default()
     };
 
     let toml = toml::to_string(&config).unwrap();
@@ -512,3 +517,328 @@ fn config_handles_very_long_values() {
 
     assert_eq!(loaded.tries_path, Some(long_path));
 }
+
+#[test]
+fn config_parses_hooks_table() {
+    let toml_str = r#"
+tries_path = "~/tries"
+
+[hooks]
+post_create = "git init"
+pre_delete = "test ! -f .keep"
+"#;
+    let config: Config = toml::from_str(toml_str).unwrap();
+    let hooks = config.hooks.unwrap();
+    assert_eq!(hooks.post_create.as_deref(), Some("git init"));
+    assert_eq!(hooks.pre_delete.as_deref(), Some("test ! -f .keep"));
+    assert!(hooks.on_enter.is_none());
+}
+
+#[test]
+fn config_parses_detectors() {
+    let toml_str = r##"
+[[detectors]]
+label = "Bazel"
+icon = "B"
+color = "#00ff00"
+markers = ["MODULE.bazel"]
+
+[[detectors]]
+label = "Mise"
+icon = ""
+"##;
+    let config: Config = toml::from_str(toml_str).unwrap();
+    let detectors = config.detectors.unwrap();
+    assert_eq!(detectors[0].markers, ["MODULE.bazel"]);
+    assert_eq!(detectors[1].color, "file");
+    assert!(detectors[1].markers.is_empty());
+}
+
+#[test]
+fn config_parses_default_sort() {
+    let config: Config =
+        toml::from_str("default_sort = \"created\"\nsort_reverse = true\n").unwrap();
+    assert_eq!(config.default_sort.as_deref(), Some("created"));
+    assert_eq!(config.sort_reverse, Some(true));
+}
+
+#[test]
+fn save_config_preserves_hooks() {
+    let tmp = TempDir::new("save-hooks").unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "[hooks]\non_enter = \"ls\"\n").unwrap();
+
+    save_config(
+        &config_path,
+        &Theme::default(),
+        Some(&[PathBuf::from("/tmp/t")]),
+        &None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    let loaded: Config = toml::from_str(&contents).unwrap();
+    assert_eq!(loaded.hooks.unwrap().on_enter.as_deref(), Some("ls"));
+}
+
+#[test]
+fn save_config_preserves_archive_path() {
+    let tmp = TempDir::new("save-archive").unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "archive_path = \"~/archive\"\n").unwrap();
+
+    save_config(
+        &config_path,
+        &Theme::default(),
+        Some(&[PathBuf::from("/tmp/t")]),
+        &None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    let loaded: Config = toml::from_str(&contents).unwrap();
+    assert_eq!(loaded.archive_path.as_deref(), Some("~/archive"));
+}
+
+#[test]
+fn parse_config_reports_the_line_of_syntax_errors() {
+    let path = PathBuf::from("/cfg/config.toml");
+    let (config, issues) = parse_config(&path, "theme = \"Nord\"\neditor = vim\n");
+    assert!(config.is_none());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(2));
+    assert!(issues[0].to_string().starts_with("/cfg/config.toml:2: "));
+}
+
+#[test]
+fn parse_config_reports_the_key_of_invalid_values() {
+    let path = PathBuf::from("config.toml");
+    let (config, issues) = parse_config(&path, "theme = \"Nord\"\n\n[prune]\nempty = 3\n");
+    assert!(config.is_none());
+    assert_eq!(issues[0].line, Some(4));
+    assert_eq!(issues[0].key.as_deref(), Some("prune.empty"));
+}
+
+#[test]
+fn parse_config_keeps_settings_next_to_unknown_keys() {
+    let path = PathBuf::from("config.toml");
+    let contents = "editr = \"vim\"\ntheme = \"Nord\"\n\n[hooks]\npost_craete = \"make\"\n";
+    let (config, issues) = parse_config(&path, contents);
+    assert_eq!(config.unwrap().theme.as_deref(), Some("Nord"));
+    let found: Vec<(Option<usize>, Option<&str>)> = issues
+        .iter()
+        .map(|issue| (issue.line, issue.key.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Some(1), Some("editr")),
+            (Some(5), Some("hooks.post_craete"))
+        ]
+    );
+    assert!(issues.iter().all(|issue| issue.message == "unknown key"));
+}
+
+#[test]
+fn parse_config_checks_theme_and_panel_width() {
+    let path = PathBuf::from("config.toml");
+    let contents = "right_panel_width = 95\ntheme = \"Nordd\"\n";
+    let (config, issues) = parse_config(&path, contents);
+    assert!(config.is_some());
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].key.as_deref(), Some("right_panel_width"));
+    assert_eq!(issues[1].key.as_deref(), Some("theme"));
+    assert!(issues[1].message.contains("Catppuccin Mocha"));
+
+    let (_, issues) = parse_config(&path, "right_panel_width = 40\ntheme = \"Nord\"\n");
+    assert!(issues.is_empty());
+}
+
+#[test]
+fn parse_config_checks_the_name_template() {
+    let path = PathBuf::from("config.toml");
+    let (config, issues) = parse_config(&path, "name_template = \"{date:%Y%m%d}_{name}\"\n");
+    assert_eq!(
+        config.unwrap().name_template.as_deref(),
+        Some("{date:%Y%m%d}_{name}")
+    );
+    assert!(issues.is_empty());
+
+    let (_, issues) = parse_config(&path, "theme = \"Nord\"\nname_template = \"{date}\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(2));
+    assert_eq!(issues[0].key.as_deref(), Some("name_template"));
+
+    let (_, issues) = parse_config(&path, "date_prefix_format = \"%Y/%m/%d\"\n");
+    assert_eq!(issues[0].key.as_deref(), Some("date_prefix_format"));
+    assert!(issues[0].message.contains("path separator"));
+}
+
+#[test]
+fn check_config_file_reports_unreadable_files() {
+    let tmp = TempDir::new("check-config-test").unwrap();
+    let issues = check_config_file(&tmp.path().join("missing.toml"));
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, None);
+}
+
+fn save_theme(config_path: &std::path::Path, theme: &Theme, tries_paths: &[PathBuf]) {
+    save_config(
+        config_path,
+        theme,
+        Some(tries_paths),
+        &None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+}
+
+#[test]
+fn save_config_keeps_comments_and_unknown_keys() {
+    let tmp = TempDir::new("save-comments").unwrap();
+    let config_path = tmp.path().join("config.toml");
+    let original = "\
+# My try-rs setup
+tries_path = \"/tmp/t\" # where experiments live
+theme = \"Default\" # picked in the TUI
+future_option = 3
+
+[hooks]
+# runs after every new try
+post_create = \"git init\"
+";
+    std::fs::write(&config_path, original).unwrap();
+
+    save_theme(
+        &config_path,
+        &Theme::tokyo_night(),
+        &[PathBuf::from("/tmp/t")],
+    );
+
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    assert_eq!(
+        contents,
+        original.replace(
+            "theme = \"Default\" # picked",
+            "theme = \"Tokyo Night\" # picked"
+        )
+    );
+}
+
+#[test]
+fn save_config_does_not_duplicate_tries_path() {
+    let tmp = TempDir::new("save-paths").unwrap();
+    let config_path = tmp.path().join("config.toml");
+
+    save_theme(&config_path, &Theme::default(), &[PathBuf::from("/a")]);
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    assert!(contents.contains("tries_path = \"/a\""));
+    assert!(!contents.contains("tries_paths"));
+
+    std::fs::write(&config_path, "tries_paths = \"/a, /b\"\n").unwrap();
+    save_theme(
+        &config_path,
+        &Theme::default(),
+        &[PathBuf::from("/a"), PathBuf::from("/c")],
+    );
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    assert!(contents.contains("tries_paths = \"/a, /c\""));
+    assert!(!contents.contains("tries_path ="));
+}
+
+#[test]
+fn save_config_keeps_unexpanded_tries_path() {
+    let tmp = TempDir::new("save-tilde").unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "tries_path = \"~/tries\"\n").unwrap();
+
+    save_theme(
+        &config_path,
+        &Theme::default(),
+        &[try_rs::utils::expand_path("~/tries")],
+    );
+
+    let contents = std::fs::read_to_string(&config_path).unwrap();
+    assert!(contents.starts_with("tries_path = \"~/tries\"\n"));
+}
+
+#[test]
+fn save_config_refuses_to_overwrite_an_invalid_file() {
+    let tmp = TempDir::new("save-invalid").unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "theme = \n").unwrap();
+
+    assert!(
+        save_config(
+            &config_path,
+            &Theme::default(),
+            Some(&[PathBuf::from("/tmp/t")]),
+            &None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .is_err()
+    );
+    assert_eq!(std::fs::read_to_string(&config_path).unwrap(), "theme = \n");
+}
+
+#[test]
+fn config_save_locations_have_no_duplicates() {
+    let locations = config_save_locations();
+    assert!(!locations.is_empty());
+    for (i, location) in locations.iter().enumerate() {
+        assert!(!locations[i + 1..].contains(location));
+        assert!(config_candidates().contains(location));
+    }
+}
+
+#[test]
+fn parse_config_checks_worktree_copy_patterns() {
+    let path = PathBuf::from("config.toml");
+    let (config, issues) = parse_config(&path, "worktree_copy = [\".env*\", \"node_modules\"]\n");
+    assert_eq!(config.unwrap().worktree_copy.unwrap().len(), 2);
+    assert!(issues.is_empty());
+
+    let (_, issues) = parse_config(&path, "worktree_copy = [\"[abc\"]\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].key.as_deref(), Some("worktree_copy"));
+    assert!(issues[0].message.contains("'[abc'"));
+
+    let (_, issues) = parse_config(
+        &path,
+        "worktree_copy = [\"../secrets/*\", \"/etc/passwd\", \"./.env\"]\n",
+    );
+    let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert!(messages[0].contains("'../secrets/*' must stay inside the repository"));
+    assert!(messages[1].contains("'/etc/passwd'"));
+}
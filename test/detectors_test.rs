@@ -0,0 +1,49 @@
+use ratatui::style::Color;
+use tempdir::TempDir;
+use try_rs::detectors::*;
+use try_rs::themes::Theme;
+
+fn custom(label: &str, markers: &[&str]) -> Detector {
+    Detector {
+        label: label.to_string(),
+        icon: "x".to_string(),
+        color: "red".to_string(),
+        markers: markers.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+#[test]
+fn detect_matches_files_and_globs() {
+    let tmp = TempDir::new("detect").unwrap();
+    std::fs::write(tmp.path().join("package.json"), "{}").unwrap();
+    std::fs::write(tmp.path().join("App.csproj"), "").unwrap();
+
+    let types = detect(&builtin_detectors(), tmp.path());
+    assert_eq!(types, ["Node", ".NET"]);
+}
+
+#[test]
+fn registry_overrides_disables_and_appends() {
+    let detectors = registry(&[
+        custom("rust", &["Cargo.toml", "rust-toolchain.toml"]),
+        custom("Mise", &[]),
+        custom("Bazel", &["MODULE.bazel"]),
+    ]);
+
+    let rust = detectors.iter().find(|d| d.label == "rust").unwrap();
+    assert_eq!(rust.markers.len(), 2);
+    assert!(
+        !detectors
+            .iter()
+            .any(|d| d.label == "Rust" || d.label == "Mise")
+    );
+    assert_eq!(detectors.last().unwrap().label, "Bazel");
+}
+
+#[test]
+fn resolve_color_uses_theme_names_and_literals() {
+    let theme = Theme::default();
+    assert_eq!(resolve_color("rust", &theme), theme.icon_rust);
+    assert_eq!(resolve_color("#ff0000", &theme), Color::Rgb(255, 0, 0));
+    assert_eq!(resolve_color("not a color", &theme), theme.icon_file);
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use tempdir::TempDir;
+use try_rs::cli::Shell;
+use try_rs::doctor::*;
+use try_rs::shell::{get_shell_integration_path, source_command};
+
+#[test]
+fn check_config_reports_parse_errors() {
+    let dir = TempDir::new("doctor-config").unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "tries_path = \"~/tries\"\n").unwrap();
+    assert_eq!(check_config(Some(&path)).status, Status::Ok);
+
+    fs::write(&path, "tries_path = ~/tries\n").unwrap();
+    let check = check_config(Some(&path));
+    assert_eq!(check.status, Status::Problem);
+    assert!(check.summary.contains("config.toml"));
+    assert!(
+        check.summary.contains("config.toml:1: "),
+        "{}",
+        check.summary
+    );
+    assert!(check.fix.is_some());
+}
+
+#[test]
+fn check_config_warns_about_unknown_keys() {
+    let dir = TempDir::new("doctor-config").unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "tries_path = \"~/tries\"\neditr = \"vim\"\n").unwrap();
+    let check = check_config(Some(&path));
+    assert_eq!(check.status, Status::Warning);
+    assert!(check.summary.ends_with("config.toml:2: editr: unknown key"));
+}
+
+#[test]
+fn check_config_without_a_file_lists_where_to_create_one() {
+    let check = check_config(None);
+    assert_eq!(check.status, Status::Warning);
+    assert!(check.fix.unwrap().contains("config.toml"));
+}
+
+#[test]
+fn check_tries_dir_needs_a_writable_directory() {
+    let dir = TempDir::new("doctor-tries").unwrap();
+    assert_eq!(check_tries_dir(dir.path()).status, Status::Ok);
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+
+    let missing = dir.path().join("missing");
+    let check = check_tries_dir(&missing);
+    assert_eq!(check.status, Status::Warning);
+    assert!(check.fix.unwrap().contains("mkdir -p"));
+
+    let file = dir.path().join("file");
+    fs::write(&file, "").unwrap();
+    assert_eq!(check_tries_dir(&file).status, Status::Problem);
+}
+
+#[test]
+fn check_rc_files_looks_for_the_source_line() {
+    let dir = TempDir::new("doctor-rc").unwrap();
+    let rc = dir.path().join(".zshrc");
+    let source = source_command(&Shell::Zsh, &get_shell_integration_path(&Shell::Zsh));
+
+    let check = check_rc_files(&Shell::Zsh, std::slice::from_ref(&rc));
+    assert_eq!(check.status, Status::Problem);
+    assert!(check.summary.contains("does not exist"));
+
+    fs::write(&rc, format!("# {source}\n")).unwrap();
+    let check = check_rc_files(&Shell::Zsh, std::slice::from_ref(&rc));
+    assert_eq!(check.status, Status::Problem);
+    let fix = check.fix.unwrap();
+    assert!(fix.contains(&source), "{}", fix);
+    assert!(fix.contains("try-rs --setup zsh"), "{}", fix);
+
+    fs::write(&rc, format!("alias ll='ls -l'\n{source}\n")).unwrap();
+    assert_eq!(
+        check_rc_files(&Shell::Zsh, std::slice::from_ref(&rc)).status,
+        Status::Ok
+    );
+}
+
+#[test]
+fn check_rc_files_passes_for_shells_without_an_rc_file() {
+    assert_eq!(check_rc_files(&Shell::Fish, &[]).status, Status::Ok);
+}
+
+#[test]
+fn check_shell_suggests_setup() {
+    assert_eq!(check_shell(None).status, Status::Warning);
+    let check = check_shell(Some(&Shell::NuShell));
+    if check.status == Status::Problem {
+        assert!(check.fix.unwrap().contains("try-rs --setup nu-shell"));
+    }
+}
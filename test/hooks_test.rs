@@ -0,0 +1,54 @@
+use std::fs;
+
+use tempdir::TempDir;
+use try_rs::hooks::*;
+
+#[test]
+fn context_name_is_folder_name() {
+    let tmp = TempDir::new("hooks-ctx").unwrap();
+    let path = tmp.path().join("2025-01-15 demo");
+    let ctx = HookContext::new(&path, None);
+    assert_eq!(ctx.name, "2025-01-15 demo");
+}
+
+#[test]
+fn hook_runs_in_target_dir_with_env() {
+    let tmp = TempDir::new("hooks-env").unwrap();
+    let target = tmp.path().join("proj");
+    fs::create_dir(&target).unwrap();
+
+    let ctx = HookContext::new(&target, Some("https://example.com/repo.git"));
+    let status = run_hook(
+        "printf '%s|%s|%s' \"$TRY_NAME\" \"$TRY_PATH\" \"$TRY_SOURCE_URL\" > env.txt",
+        &ctx,
+    )
+    .unwrap();
+
+    assert!(status.success());
+    let written = fs::read_to_string(target.join("env.txt")).unwrap();
+    assert_eq!(
+        written,
+        format!("proj|{}|https://example.com/repo.git", target.display())
+    );
+}
+
+#[test]
+fn source_url_is_empty_when_absent() {
+    let tmp = TempDir::new("hooks-no-url").unwrap();
+    let ctx = HookContext::new(tmp.path(), None);
+    let output = run_hook_captured("printf '[%s]' \"$TRY_SOURCE_URL\"", &ctx).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "[]");
+}
+
+#[test]
+fn captured_hook_reports_failure_and_stderr() {
+    let tmp = TempDir::new("hooks-fail").unwrap();
+    let ctx = HookContext::new(tmp.path(), None);
+    let output = run_hook_captured("echo 'not allowed' >&2; exit 3", &ctx).unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        "not allowed"
+    );
+}
@@ -9,6 +9,8 @@ use std::os::unix::process::ExitStatusExt;
 
 use chrono::Local;
 use tempdir::TempDir;
+use try_rs::access::{AccessLog, now};
+use try_rs::trash::Trash;
 
 #[test]
 fn shows_help() {
@@ -403,6 +405,136 @@ fn new_name_with_date_creates_dated_folder() {
     assert!(expected.exists(), "dated folder should be created");
 }
 
+#[test]
+fn name_template_names_and_finds_folders() {
+    let h = Harness::new(false);
+    let config = h.dir.path().join("config.toml");
+    let mut contents = fs::read_to_string(&config).unwrap();
+    contents.push_str("name_template = \"{date:%Y%m%d}_{name}\"\n");
+    fs::write(&config, contents).unwrap();
+
+    let p = h.run_try(&["demo"]);
+    let expected = h
+        .tries_path()
+        .join(format!("{}_demo", Local::now().format("%Y%m%d")));
+    assert!(expected.exists(), "{}", p.stderr);
+
+    // The plain name finds the templated folder instead of creating another
+    let p = h.run_try(&["demo"]);
+    assert_eq!(p.stdout.trim(), format!("cd '{}'", expected.display()));
+    assert_eq!(fs::read_dir(h.tries_path()).unwrap().count(), 1);
+}
+
+#[test]
+fn migrate_renames_to_the_configured_scheme() {
+    let h = Harness::new(false);
+    fs::create_dir_all(h.tries_path().join("2024-01-05 alpha")).unwrap();
+
+    // Without a naming scheme there is nothing to migrate to
+    let p = h.run_try(&["migrate", "--yes"]);
+    assert!(!p.status.success());
+    assert!(p.stderr.contains("No naming scheme is configured"));
+
+    let config = h.dir.path().join("config.toml");
+    let mut contents = fs::read_to_string(&config).unwrap();
+    contents.push_str("name_template = \"{date:%Y%m%d}_{name}\"\n");
+    fs::write(&config, contents).unwrap();
+
+    let p = h.run_try(&["migrate", "--dry-run"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    assert!(p.stdout.starts_with("rename\t"), "{}", p.stdout);
+    assert!(h.tries_path().join("2024-01-05 alpha").exists());
+
+    let p = h.run_try(&["migrate", "--yes"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    assert!(h.tries_path().join("20240105_alpha").exists());
+    assert!(!h.tries_path().join("2024-01-05 alpha").exists());
+}
+
+#[test]
+fn new_worktree_tracks_remote_branches_and_copies_files() {
+    let h = Harness::new(false);
+    let origin = create_git_origin(&h).expect("could not setup git origin");
+    command(&origin, "git", &["branch", "feature/login"]).unwrap();
+    let origin_arg = origin.display().to_string();
+    command(
+        &h.dir.path().to_path_buf(),
+        "git",
+        &["clone", "-q", &origin_arg, "clone"],
+    )
+    .unwrap();
+    let clone = h.dir.path().join("clone");
+    fs::write(clone.join(".env"), "TOKEN=1").unwrap();
+    let config = h.dir.path().join("config.toml");
+    let mut contents = fs::read_to_string(&config).unwrap();
+    contents.push_str("worktree_copy = [\".env\"]\n");
+    fs::write(&config, contents).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_try-rs"))
+        .current_dir(&clone)
+        .args(["--worktree", "feature/login"])
+        .env("SHELL", "")
+        .env_remove("TRY_PATH")
+        .env("TRY_CONFIG_DIR", h.dir.path())
+        .output()
+        .expect("failed to spawn process");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("Tracking 'origin/feature/login'"),
+        "{}",
+        stderr
+    );
+
+    let worktree = h.tries_path().join("feature-login");
+    assert_eq!(
+        fs::read_to_string(worktree.join(".env")).unwrap(),
+        "TOKEN=1"
+    );
+    let upstream = command(
+        &worktree,
+        "git",
+        &["rev-parse", "--abbrev-ref", "feature/login@{upstream}"],
+    )
+    .unwrap();
+    assert_eq!(upstream.stdout.trim(), "origin/feature/login");
+}
+
+#[test]
+fn worktree_command_lists_and_manages_worktrees() {
+    let h = Harness::new(false);
+    let repo = create_git_origin(&h).expect("could not setup git origin");
+    let repo_arg = repo.display().to_string();
+    fs::create_dir_all(h.tries_path()).unwrap();
+    command(&repo, "git", &["worktree", "add", "-q", "../tries/feat"]).unwrap();
+    command(&repo, "git", &["worktree", "add", "-q", "../gone"]).unwrap();
+    fs::remove_dir_all(h.dir.path().join("gone")).unwrap();
+
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "list"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    let rows: Vec<Vec<&str>> = p.stdout.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 3, "{}", p.stdout);
+    assert_eq!(rows[0][3], "main");
+    assert_eq!(rows[1][1..], ["gone", "missing,prunable", "worktree"]);
+    assert_eq!(rows[2][1..], ["feat", "ok", "try"]);
+
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "lock", "feat"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "remove", "feat"]);
+    assert!(!p.status.success());
+    assert!(h.tries_path().join("feat").exists());
+
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "jump", "feat"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    assert!(p.stdout.starts_with("cd '"), "{}", p.stdout);
+    assert!(p.stdout.contains("feat"), "{}", p.stdout);
+
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "prune"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    let p = h.run_try(&["worktree", "--repo", &repo_arg, "list"]);
+    assert_eq!(p.stdout.lines().count(), 2, "{}", p.stdout);
+}
+
 #[test]
 fn multiple_new_folders() {
     let h = Harness::new(false);
@@ -546,6 +678,350 @@ fn config_with_invalid_theme_falls_back() {
     assert!(output.contains("cd"), "should still produce cd command");
 }
 
+#[test]
+fn new_folder_from_template() {
+    let h = Harness::new(false);
+    let template = h.dir.path().join("templates").join("rust");
+    fs::create_dir_all(&template).unwrap();
+    fs::write(template.join("Cargo.toml"), "name = \"{{name}}\"\n").unwrap();
+
+    let p = h.run_try(&["--template", "rust", "templated"]);
+
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    let cargo = fs::read_to_string(h.tries_path().join("templated").join("Cargo.toml")).unwrap();
+    assert_eq!(cargo, "name = \"templated\"\n");
+}
+
+#[test]
+fn new_folder_with_unknown_template_fails() {
+    let h = Harness::new(false);
+
+    let p = h.run_try(&["--template", "missing", "no-template"]);
+
+    assert!(!p.status.success());
+    assert!(p.stderr.contains("Template 'missing' not found"));
+    assert!(!h.tries_path().join("no-template").exists());
+}
+
+#[test]
+fn post_create_and_on_enter_hooks_run() {
+    let h = Harness::new(false);
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    config.push_str(
+        "[hooks]\npost_create = \"echo created > created.txt\"\non_enter = \"echo \\\"$TRY_NAME\\\" > entered.txt\"\n",
+    );
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+
+    let p = h.run_try(&["hooked"]);
+
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    let dir = h.tries_path().join("hooked");
+    assert_eq!(
+        p.stdout.trim(),
+        format!("cd '{}'", dir.display()),
+        "hook output must not reach stdout"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("created.txt")).unwrap().trim(),
+        "created"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("entered.txt")).unwrap().trim(),
+        "hooked"
+    );
+}
+
+#[test]
+fn trash_restore_and_list() {
+    let h = Harness::new(false);
+    let trash = Trash::new(h.dir.path().join("trash"));
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    config.push_str(&format!(
+        "trash_dir = \"{}\"\n",
+        h.dir.path().join("trash").display()
+    ));
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+    h.create_try_folder("gone");
+    let entry = trash.move_to_trash(&h.tries_path().join("gone")).unwrap();
+
+    let p = h.run_try(&["trash", "list"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(p.stdout.contains(&entry.id));
+
+    let p = h.run_try(&["trash", "restore", "gone"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(p.stdout.is_empty(), "nothing for the shell to eval");
+    assert!(h.tries_path().join("gone").exists());
+
+    let p = h.run_try(&["trash", "restore", "gone"]);
+    assert!(!p.status.success());
+    assert!(p.stderr.contains("not in the trash"));
+
+    // Emptying asks first; without an answer nothing is deleted
+    trash.move_to_trash(&h.tries_path().join("gone")).unwrap();
+    let p = h.run_try(&["trash", "empty"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(p.stderr.contains("[y/N]"));
+    assert_eq!(trash.list().len(), 1);
+
+    let p = h.run_try(&["trash", "empty", "--yes"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(trash.list().is_empty());
+}
+
+#[test]
+fn prune_dry_run_then_prune() {
+    let h = Harness::new(false);
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    config.push_str(&format!(
+        "trash_dir = \"{}\"\n",
+        h.dir.path().join("trash").display()
+    ));
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+    h.create_try_folder("2020-01-01 stale");
+    h.create_try_folder("recent");
+
+    // No criterion anywhere: only a dry run of the default policy.
+    let p = h.run_try(&["prune"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(p.stderr.contains("--older-than"));
+    assert!(p.stdout.contains("2020-01-01 stale"));
+    assert!(h.tries_path().join("2020-01-01 stale").exists());
+
+    let p = h.run_try(&["prune", "--older-than", "30", "--dry-run"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(p.stdout.contains("2020-01-01 stale"));
+    assert!(!p.stdout.contains("recent"));
+    assert!(h.tries_path().join("2020-01-01 stale").exists());
+
+    let p = h.run_try(&["prune", "--older-than", "30"]);
+    assert!(p.status.success(), "stderr: {}", p.stderr);
+    assert!(!h.tries_path().join("2020-01-01 stale").exists());
+    assert!(h.tries_path().join("recent").exists());
+    assert_eq!(Trash::new(h.dir.path().join("trash")).list().len(), 1);
+}
+
+#[test]
+fn jumps_are_recorded_in_access_log() {
+    let h = Harness::new(false);
+    h.create_try_folder("favorite");
+
+    h.run_try(&["favorite"]);
+    h.run_try(&["favorite"]);
+
+    let log = AccessLog::load(h.dir.path().join("access.toml"));
+    assert_eq!(log.frecency(&h.tries_path().join("favorite"), now()), 8.0);
+}
+
+#[test]
+fn complete_lists_candidates_from_the_configured_tries_path() {
+    let h = Harness::new(false);
+    h.create_try_folder("2024-05-01 tokio-bench");
+
+    let p = h.run_try(&["complete", "names"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    assert_eq!(
+        p.stdout.lines().collect::<Vec<_>>(),
+        ["2024-05-01 tokio-bench", "tokio-bench"]
+    );
+
+    let p = h.run_try(&["complete", "themes"]);
+    assert!(p.stdout.lines().any(|line| line == "Dracula"));
+}
+
+#[test]
+fn list_prints_json_tsv_and_format() {
+    let h = Harness::new(false);
+    h.create_try_folder("alpha");
+    fs::write(h.tries_path().join("alpha").join("go.mod"), "module alpha").unwrap();
+    h.create_try_folder("beta");
+
+    let p = h.run_try(&["list", "--json"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    let json: serde_json::Value = serde_json::from_str(&p.stdout).unwrap();
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    let alpha = entries.iter().find(|e| e["name"] == "alpha").unwrap();
+    assert_eq!(alpha["project_types"][0], "Go");
+    assert_eq!(alpha["tab"], 0);
+
+    let p = h.run_try(&["list", "--tsv"]);
+    let mut lines = p.stdout.lines();
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .starts_with("name\tdisplay_name\tpath")
+    );
+    assert_eq!(lines.count(), 2);
+
+    let p = h.run_try(&["list", "--format", "{name}:{is_worktree}"]);
+    let mut names: Vec<&str> = p.stdout.lines().collect();
+    names.sort();
+    assert_eq!(names, ["alpha:false", "beta:false"]);
+
+    let p = h.run_try(&["list", "--format", "{bogus}"]);
+    assert!(!p.status.success());
+    assert!(p.stderr.contains("Unknown field"));
+}
+
+#[test]
+fn filter_prints_ranked_matches() {
+    let h = Harness::new(false);
+    h.create_try_folder("tokio-bench");
+    h.create_try_folder("notes");
+
+    let p = h.run_try(&["--filter", "tkb"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    let lines: Vec<&str> = p.stdout.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let fields: Vec<&str> = lines[0].split('\t').collect();
+    assert!(fields[0].parse::<i64>().unwrap() > 0);
+    assert_eq!(fields[1], "tokio-bench");
+    assert_eq!(fields[2], "0,2,6");
+    assert!(fields[3].ends_with("tokio-bench"));
+}
+
+#[test]
+#[cfg(unix)]
+fn hostile_names_are_quoted_for_eval() {
+    let h = Harness::new(false);
+    let name = "it's $(touch pwned) `touch pwned`";
+
+    let p = h.run_try(&["--shell", "bash", name]);
+    assert!(p.status.success(), "{}", p.stderr);
+
+    let eval = Command::new("bash")
+        .args(["-c", r#"eval "$1"; pwd"#, "bash", p.stdout.trim()])
+        .current_dir(h.dir.path())
+        .output()
+        .unwrap();
+    let pwd = String::from_utf8(eval.stdout).unwrap();
+    assert_eq!(pwd.trim(), h.tries_path().join(name).to_string_lossy());
+    assert!(!h.dir.path().join("pwned").exists());
+}
+
+#[test]
+fn actions_flag_prints_protocol_lines() {
+    let h = Harness::new(false);
+    let p = h.run_try(&["--actions", "tab\there"]);
+    assert!(p.status.success(), "{}", p.stderr);
+    assert_eq!(
+        p.stdout.trim_end(),
+        format!("cd\t{}", h.tries_path().join("tab\\there").display())
+    );
+}
+
+/// Loads the `shell` integration with `script`, enters a try whose name is
+/// hostile to shells, and checks the wrapper ran the `enter_command` there.
+#[cfg(unix)]
+fn assert_wrapper_runs_actions(shell: &str, script: &str) {
+    let h = Harness::new(false);
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    config.push_str("enter_command = \"touch entered\"\n");
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+    let bin = h.dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    std::os::unix::fs::symlink(env!("CARGO_BIN_EXE_try-rs"), bin.join("try-rs")).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    let name = "it's $(touch pwned)";
+
+    let p = Command::new(shell)
+        .args(["-c", script, shell, name])
+        .current_dir(h.dir.path())
+        .env("PATH", path)
+        .env("SHELL", "")
+        .env_remove("TRY_PATH")
+        .env("TRY_CONFIG_DIR", h.dir.path())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(p.stderr).unwrap();
+    assert!(p.status.success(), "{}", stderr);
+    let try_dir = h.tries_path().join(name);
+    assert_eq!(
+        String::from_utf8(p.stdout).unwrap().trim(),
+        try_dir.to_string_lossy()
+    );
+    assert!(try_dir.join("entered").exists());
+    assert!(!h.dir.path().join("pwned").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn bash_wrapper_runs_actions() {
+    assert_wrapper_runs_actions(
+        "bash",
+        r#"eval "$(try-rs --setup-stdout bash)"; try-rs "$1" && pwd"#,
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn sh_wrapper_runs_actions() {
+    // The alias only applies to lines read after the one defining it
+    assert_wrapper_runs_actions(
+        "sh",
+        "eval \"$(try-rs --setup-stdout sh)\"\ntry-rs \"$1\" && pwd",
+    );
+}
+
+#[test]
+fn doctor_reports_an_invalid_config() {
+    let h = Harness::new(false);
+    fs::write(h.dir.path().join("config.toml"), "tries_path = [\n").unwrap();
+
+    let p = h.run_try(&["doctor"]);
+
+    assert!(!p.status.success());
+    assert!(p.stdout.contains("[FAIL] "), "{}", p.stdout);
+    assert!(p.stdout.contains("config.toml:1: "), "{}", p.stdout);
+    assert!(p.stdout.contains("fix: "), "{}", p.stdout);
+}
+
+#[test]
+fn doctor_checks_each_tries_dir() {
+    let h = Harness::new(false);
+    fs::create_dir_all(h.tries_path()).unwrap();
+
+    let p = h.run_try(&["doctor"]);
+
+    let expected = format!("Tries directory {} is writable", h.tries_path().display());
+    assert!(p.stdout.contains(&expected), "{}", p.stdout);
+    assert!(p.stdout.contains("Config file "), "{}", p.stdout);
+}
+
+#[test]
+fn config_check_fails_on_problems() {
+    let h = Harness::new(false);
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    let p = h.run_try(&["config", "check"]);
+    assert!(p.status.success(), "{}", p.stdout);
+
+    config.push_str("theme = \"Not A Theme\"\n");
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+    let p = h.run_try(&["config", "check"]);
+    assert!(!p.status.success());
+    assert!(
+        p.stdout.contains("config.toml:2: theme: unknown theme"),
+        "{}",
+        p.stdout
+    );
+}
+
+#[test]
+fn config_problems_are_warned_about_on_stderr() {
+    let h = Harness::new(false);
+    let mut config = fs::read_to_string(h.dir.path().join("config.toml")).unwrap();
+    config.push_str("edtor = \"vim\"\n");
+    fs::write(h.dir.path().join("config.toml"), config).unwrap();
+
+    let p = h.run_try(&["list"]);
+
+    assert!(p.status.success());
+    assert!(p.stderr.contains("Warning: "), "{}", p.stderr);
+    assert!(p.stderr.contains("edtor: unknown key"), "{}", p.stderr);
+}
+
 #[test]
 fn try_path_env_overrides_config() {
     let h = Harness::new(false);
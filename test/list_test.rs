@@ -0,0 +1,104 @@
+use tempdir::TempDir;
+use try_rs::access::AccessLog;
+use try_rs::detectors::builtin_detectors;
+use try_rs::list::*;
+use try_rs::meta::MetaStore;
+use try_rs::naming::NameTemplate;
+use try_rs::tui::SortMode;
+
+#[test]
+fn collect_entries_covers_all_tabs() {
+    let tmp = TempDir::new("list-tabs").unwrap();
+    let first = tmp.path().join("first");
+    let second = tmp.path().join("second");
+    std::fs::create_dir_all(first.join("2024-01-02 rusty")).unwrap();
+    std::fs::write(first.join("2024-01-02 rusty").join("Cargo.toml"), "").unwrap();
+    std::fs::create_dir_all(second.join("plain")).unwrap();
+
+    let entries = collect_entries(
+        &[first.clone(), second],
+        &builtin_detectors(),
+        &NameTemplate::default(),
+        &MetaStore::default(),
+        &AccessLog::default(),
+        (SortMode::Name, false),
+        true,
+    );
+
+    assert_eq!(entries.len(), 2);
+    let rusty = &entries[0];
+    assert_eq!(rusty.display_name, "rusty");
+    assert_eq!(rusty.path, first.join("2024-01-02 rusty"));
+    assert_eq!(rusty.tab, 0);
+    assert!(rusty.created.starts_with("2024-01-02T00:00:00"));
+    assert_eq!(rusty.field("project_types").as_deref(), Some("Rust"));
+    assert_eq!(rusty.size_mb, Some(0));
+    assert_eq!(entries[1].field("tab").as_deref(), Some("1"));
+    assert_eq!(entries[1].field("nope"), None);
+}
+
+#[test]
+fn collect_entries_skips_size_unless_asked() {
+    let tmp = TempDir::new("list-size").unwrap();
+    std::fs::create_dir_all(tmp.path().join("demo")).unwrap();
+
+    let entries = collect_entries(
+        &[tmp.path().to_path_buf()],
+        &builtin_detectors(),
+        &NameTemplate::default(),
+        &MetaStore::default(),
+        &AccessLog::default(),
+        (SortMode::Modified, false),
+        false,
+    );
+    assert_eq!(entries[0].size_mb, None);
+    assert_eq!(entries[0].field("size_mb").as_deref(), Some(""));
+}
+
+#[test]
+fn filter_entries_ranks_across_tabs() {
+    let tmp = TempDir::new("list-filter").unwrap();
+    let first = tmp.path().join("first");
+    let second = tmp.path().join("second");
+    std::fs::create_dir_all(first.join("parser-notes")).unwrap();
+    std::fs::create_dir_all(first.join("unrelated")).unwrap();
+    std::fs::create_dir_all(second.join("2024-05-01 parser")).unwrap();
+
+    let ranked = filter_entries(
+        &[first.clone(), second.clone()],
+        &builtin_detectors(),
+        &NameTemplate::default(),
+        &MetaStore::default(),
+        &AccessLog::default(),
+        (SortMode::Name, false),
+        "parser",
+    );
+
+    assert_eq!(ranked.len(), 2);
+    assert!(ranked[0].1.score >= ranked[1].1.score);
+    let (path, dated) = ranked
+        .iter()
+        .find(|(_, e)| e.display_name == "parser")
+        .unwrap();
+    assert_eq!(path, &second.join("2024-05-01 parser"));
+    assert_eq!(dated.match_indices, [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn filter_entries_match_indices_follow_a_name_first_template() {
+    let tmp = TempDir::new("list-filter-name-first").unwrap();
+    std::fs::create_dir_all(tmp.path().join("parser_20240501")).unwrap();
+
+    let ranked = filter_entries(
+        &[tmp.path().to_path_buf()],
+        &builtin_detectors(),
+        &NameTemplate::parse("{name}_{date:%Y%m%d}").unwrap(),
+        &MetaStore::default(),
+        &AccessLog::default(),
+        (SortMode::Name, false),
+        "pars",
+    );
+
+    assert_eq!(ranked[0].1.display_name, "parser");
+    assert_eq!(ranked[0].1.match_indices, [0, 1, 2, 3]);
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use tempdir::TempDir;
+use try_rs::meta::*;
+
+#[test]
+fn parse_tags_splits_and_dedups() {
+    assert_eq!(
+        parse_tags("rust, #cli  wip,rust"),
+        vec!["rust", "cli", "wip"]
+    );
+    assert!(parse_tags("  , ").is_empty());
+}
+
+#[test]
+fn update_persists_to_file() {
+    let tmp = TempDir::new("meta-save").unwrap();
+    let file = tmp.path().join("meta.toml");
+    let try_path = tmp.path().join("tries").join("demo");
+
+    let mut store = MetaStore::load(file.clone());
+    store
+        .update(&try_path, |meta| {
+            meta.tags = vec!["rust".to_string()];
+            meta.note = Some("benchmark idea".to_string());
+            meta.pinned = true;
+        })
+        .unwrap();
+
+    let reloaded = MetaStore::load(file);
+    let meta = reloaded.get(&try_path).unwrap();
+    assert_eq!(meta.tags, vec!["rust"]);
+    assert_eq!(meta.note.as_deref(), Some("benchmark idea"));
+    assert!(meta.pinned);
+}
+
+#[test]
+fn clearing_everything_drops_the_entry() {
+    let tmp = TempDir::new("meta-clear").unwrap();
+    let file = tmp.path().join("meta.toml");
+    let try_path = tmp.path().join("demo");
+
+    let mut store = MetaStore::load(file.clone());
+    store.update(&try_path, |meta| meta.pinned = true).unwrap();
+    store.update(&try_path, |meta| meta.pinned = false).unwrap();
+
+    assert!(store.get(&try_path).is_none());
+    assert!(!std::fs::read_to_string(file).unwrap().contains("demo"));
+}
+
+#[test]
+fn rename_moves_metadata() {
+    let tmp = TempDir::new("meta-rename").unwrap();
+    let mut store = MetaStore::load(tmp.path().join("meta.toml"));
+    let old = tmp.path().join("old");
+    let new = tmp.path().join("other").join("new");
+
+    store.update(&old, |meta| meta.pinned = true).unwrap();
+    store.rename(&old, &new).unwrap();
+
+    assert!(store.get(&old).is_none());
+    assert!(store.get(&new).unwrap().pinned);
+}
+
+#[test]
+fn remove_drops_the_entry() {
+    let tmp = TempDir::new("meta-remove").unwrap();
+    let file = tmp.path().join("meta.toml");
+    let gone = tmp.path().join("gone");
+
+    let mut store = MetaStore::load(file.clone());
+    store.update(&gone, |meta| meta.pinned = true).unwrap();
+    store.remove(&gone).unwrap();
+
+    assert!(store.get(&gone).is_none());
+    assert!(MetaStore::load(file).get(&gone).is_none());
+}
+
+#[test]
+fn unparsable_file_is_moved_aside_before_saving() {
+    let tmp = TempDir::new("meta-corrupt").unwrap();
+    let file = tmp.path().join("meta.toml");
+    std::fs::write(&file, "[tries\nbroken").unwrap();
+
+    let mut store = MetaStore::load(file.clone());
+    assert!(store.warning().unwrap().contains("meta.toml.bak"));
+    store
+        .update(&tmp.path().join("demo"), |meta| meta.pinned = true)
+        .unwrap();
+
+    let backup = tmp.path().join("meta.toml.bak");
+    assert_eq!(std::fs::read_to_string(backup).unwrap(), "[tries\nbroken");
+    assert!(MetaStore::load(file).warning().is_none());
+}
+
+#[test]
+fn default_store_is_in_memory() {
+    let mut store = MetaStore::default();
+    store
+        .update(Path::new("/nowhere/demo"), |meta| meta.pinned = true)
+        .unwrap();
+    assert!(store.get(Path::new("/nowhere/demo")).unwrap().pinned);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::fs;
+use std::process::Command;
+
+use chrono::Local;
+use common::git;
+use tempdir::TempDir;
+use try_rs::migrate::*;
+use try_rs::naming::NameTemplate;
+
+fn template() -> NameTemplate {
+    NameTemplate::parse("{date:%Y%m%d}_{name}").unwrap()
+}
+
+#[test]
+fn plan_reads_dates_from_known_prefixes() {
+    let tmp = TempDir::new("migrate-prefix").unwrap();
+    for name in ["2024-01-05 alpha", "2023_12_01_beta", "05.02.2022 gamma"] {
+        fs::create_dir(tmp.path().join(name)).unwrap();
+    }
+
+    let renames = plan_migration(tmp.path(), &template(), &known_templates(Some("%d.%m.%Y")));
+    let planned: Vec<(String, String)> = renames
+        .iter()
+        .map(|r| {
+            assert_eq!(r.date_source, DateSource::Prefix);
+            assert!(r.skip.is_none());
+            (
+                r.from.file_name().unwrap().to_string_lossy().to_string(),
+                r.to.file_name().unwrap().to_string_lossy().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        planned,
+        [
+            ("05.02.2022 gamma".to_string(), "20220205_gamma".to_string()),
+            ("2023_12_01_beta".to_string(), "20231201_beta".to_string()),
+            ("2024-01-05 alpha".to_string(), "20240105_alpha".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn plan_dates_undated_folders_from_the_filesystem() {
+    let tmp = TempDir::new("migrate-undated").unwrap();
+    fs::create_dir(tmp.path().join("plain")).unwrap();
+    fs::create_dir(tmp.path().join("20240105_done")).unwrap();
+    fs::create_dir(tmp.path().join(".hidden")).unwrap();
+    fs::write(tmp.path().join("notes.txt"), "").unwrap();
+
+    let renames = plan_migration(tmp.path(), &template(), &known_templates(None));
+    assert_eq!(renames.len(), 1);
+    assert_ne!(renames[0].date_source, DateSource::Prefix);
+    assert_eq!(
+        renames[0].to,
+        tmp.path()
+            .join(format!("{}_plain", Local::now().format("%Y%m%d")))
+    );
+}
+
+#[test]
+fn plan_skips_renames_onto_existing_or_shared_names() {
+    let tmp = TempDir::new("migrate-conflict").unwrap();
+    for name in [
+        "2024-01-05 alpha",
+        "2024-01-05_alpha",
+        "2024-02-01 beta",
+        "20240201_beta",
+    ] {
+        fs::create_dir(tmp.path().join(name)).unwrap();
+    }
+
+    let renames = plan_migration(tmp.path(), &template(), &known_templates(None));
+    let skipped: Vec<&str> = renames
+        .iter()
+        .filter(|r| r.skip.is_some())
+        .map(|r| r.from.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(skipped, ["2024-01-05_alpha", "2024-02-01 beta"]);
+}
+
+#[test]
+fn apply_moves_worktrees_and_repairs_their_repository() {
+    let tmp = TempDir::new("migrate-worktree").unwrap();
+    let repo = tmp.path().join("repo");
+    fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&repo, &["worktree", "add", "-q", "../feat"]);
+
+    let renames = plan_migration(tmp.path(), &template(), &known_templates(None));
+    assert_eq!(renames.len(), 2);
+    assert!(renames[0].is_worktree);
+    assert!(!renames[1].is_worktree);
+    for rename in &renames {
+        apply_rename(rename).unwrap();
+    }
+
+    let new_repo = &renames[1].to;
+    let new_feat = &renames[0].to;
+    let pointer = fs::read_to_string(new_feat.join(".git")).unwrap();
+    assert!(
+        pointer.contains(&new_repo.display().to_string()),
+        "{pointer}"
+    );
+    let list = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(new_repo)
+        .output()
+        .unwrap();
+    let list = String::from_utf8_lossy(&list.stdout);
+    assert!(list.contains(&new_feat.display().to_string()), "{list}");
+    assert!(!list.contains("prunable"), "{list}");
+}
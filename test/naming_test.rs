@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+use try_rs::naming::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn default_template_is_the_date_prefix() {
+    let template = NameTemplate::default();
+    assert_eq!(
+        template.format("my project", date(2024, 6, 5)),
+        "2024-06-05 my project"
+    );
+    assert_eq!(
+        template.extract("2024-06-05 my project"),
+        Some((Some(date(2024, 6, 5)), "my project".to_string(), 11))
+    );
+    assert_eq!(template.extract("notes"), None);
+}
+
+#[test]
+fn custom_template_round_trips() {
+    let template = NameTemplate::parse("{date:%Y%m%d}_{name}").unwrap();
+    let folder = template.format("a_b 2024", date(2031, 12, 25));
+    assert_eq!(folder, "20311225_a_b 2024");
+    assert_eq!(
+        template.extract(&folder),
+        Some((Some(date(2031, 12, 25)), "a_b 2024".to_string(), 9))
+    );
+}
+
+#[test]
+fn name_can_come_before_the_date() {
+    let template = NameTemplate::parse("{name} ({date})").unwrap();
+    let folder = template.format("x (2020-01-01)", date(2024, 1, 5));
+    assert_eq!(folder, "x (2020-01-01) (2024-01-05)");
+    assert_eq!(
+        template.extract(&folder),
+        Some((Some(date(2024, 1, 5)), "x (2020-01-01)".to_string(), 0))
+    );
+}
+
+#[test]
+fn template_without_a_date_strips_its_literals() {
+    let template = NameTemplate::parse("try-{name}").unwrap();
+    assert!(!template.has_date());
+    assert_eq!(
+        template.extract("try-demo"),
+        Some((None, "demo".to_string(), 4))
+    );
+    assert_eq!(template.extract("demo"), None);
+}
+
+#[test]
+fn extract_rejects_dates_format_never_writes() {
+    let template = NameTemplate::default();
+    assert_eq!(template.extract("2024-6-5 demo"), None);
+    assert_eq!(template.extract("2023-02-29 demo"), None);
+    assert_eq!(template.extract("2024-06-05 "), None);
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    for (template, error) in [
+        ("{date}", "expected {name} exactly once"),
+        ("{name}-{name}", "expected {name} exactly once"),
+        ("{date}-{date}-{name}", "expected {date} at most once"),
+        (
+            "{name}{date:%Y%m%d}",
+            "{name} must be separated from {date}",
+        ),
+        ("{nme}", "unknown placeholder '{nme}'"),
+        ("{name", "unclosed '{'"),
+        ("name}", "unexpected '}'"),
+        ("{date:%Q} {name}", "invalid date format '%Q'"),
+        ("{date:%Y/%m/%d} {name}", "contains a path separator"),
+    ] {
+        let err = NameTemplate::parse(template).unwrap_err();
+        assert!(err.contains(error), "{template}: {err}");
+    }
+}
+
+#[test]
+fn templates_that_lose_the_date_are_rejected() {
+    let err = NameTemplate::parse("{date:%Y-%m} {name}").unwrap_err();
+    assert!(err.contains("can't be read back"), "{err}");
+}
+
+#[test]
+fn new_folder_name_follows_apply_date_prefix() {
+    let today = date(2024, 6, 5);
+    let plain = NameTemplate::from_config(None, None, None).unwrap();
+    assert_eq!(plain.new_folder_name("demo", today), "demo");
+
+    let dated = NameTemplate::from_config(None, Some(true), Some("%d.%m.%Y")).unwrap();
+    assert_eq!(dated.new_folder_name("demo", today), "05.06.2024 demo");
+    // A name that already has a date prefix is kept
+    assert_eq!(
+        dated.new_folder_name("01.01.2020 demo", today),
+        "01.01.2020 demo"
+    );
+
+    // `name_template` always applies
+    let custom =
+        NameTemplate::from_config(Some("{name}@{date:%Y%m%d}"), Some(false), None).unwrap();
+    assert_eq!(custom.new_folder_name("demo", today), "demo@20240605");
+}
+
+#[test]
+fn format_date_uses_the_template_date_format() {
+    let today = date(2024, 6, 5);
+    assert_eq!(NameTemplate::default().format_date(today), "2024-06-05");
+
+    let custom = NameTemplate::from_config(Some("{name}@{date:%Y%m%d}"), None, None).unwrap();
+    assert_eq!(custom.format_date(today), "20240605");
+
+    // Without a `{date}` the default format is used
+    let plain = NameTemplate::from_config(None, None, None).unwrap();
+    assert_eq!(plain.format_date(today), "2024-06-05");
+}
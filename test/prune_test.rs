@@ -0,0 +1,168 @@
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use tempdir::TempDir;
+use try_rs::config::PruneConfig;
+use try_rs::naming::NameTemplate;
+use try_rs::prune::*;
+use try_rs::trash::Trash;
+
+fn names(candidates: &[PruneCandidate]) -> Vec<&str> {
+    candidates.iter().map(|c| c.name.as_str()).collect()
+}
+
+#[test]
+fn policy_from_empty_config_uses_default_age() {
+    let policy = PrunePolicy::from_config(&PruneConfig::default());
+    assert_eq!(policy.older_than_days, Some(DEFAULT_OLDER_THAN_DAYS));
+    assert_eq!(policy.larger_than_mb, None);
+    assert!(!policy.empty);
+    assert!(PrunePolicy::configured(&PruneConfig::default()).is_empty());
+}
+
+#[test]
+fn policy_from_config_keeps_configured_criteria() {
+    let policy = PrunePolicy::from_config(&PruneConfig {
+        older_than_days: None,
+        larger_than_mb: Some(100),
+        empty: Some(true),
+    });
+    assert_eq!(policy.older_than_days, None);
+    assert_eq!(policy.describe(), "larger than 100MB, empty or untouched");
+}
+
+#[test]
+fn age_uses_date_prefix() {
+    let tmp = TempDir::new("prune-age").unwrap();
+    fs::create_dir(tmp.path().join("2020-01-01 ancient")).unwrap();
+    fs::create_dir(tmp.path().join("fresh")).unwrap();
+
+    let policy = PrunePolicy {
+        older_than_days: Some(30),
+        ..Default::default()
+    };
+    let candidates = find_candidates(tmp.path(), &policy, &NameTemplate::default());
+
+    assert_eq!(names(&candidates), vec!["2020-01-01 ancient"]);
+    assert!(candidates[0].age_days > 365);
+}
+
+#[test]
+fn empty_matches_empty_and_untouched_folders() {
+    let tmp = TempDir::new("prune-empty").unwrap();
+    fs::create_dir(tmp.path().join("empty")).unwrap();
+    fs::create_dir(tmp.path().join("untouched")).unwrap();
+    fs::write(tmp.path().join("untouched").join("README.md"), "").unwrap();
+    fs::create_dir(tmp.path().join("worked-on")).unwrap();
+    let file = fs::File::create(tmp.path().join("worked-on").join("main.rs")).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(2 * 60 * 60))
+        .unwrap();
+
+    let policy = PrunePolicy {
+        empty: true,
+        ..Default::default()
+    };
+    let mut found = names(&find_candidates(
+        tmp.path(),
+        &policy,
+        &NameTemplate::default(),
+    ))
+    .into_iter()
+    .map(str::to_string)
+    .collect::<Vec<_>>();
+    found.sort();
+
+    assert_eq!(found, vec!["empty", "untouched"]);
+}
+
+#[test]
+fn size_threshold_filters_small_folders() {
+    let tmp = TempDir::new("prune-size").unwrap();
+    fs::create_dir(tmp.path().join("big")).unwrap();
+    fs::write(
+        tmp.path().join("big").join("blob"),
+        vec![0u8; 2 * 1024 * 1024],
+    )
+    .unwrap();
+    fs::create_dir(tmp.path().join("small")).unwrap();
+
+    let policy = PrunePolicy {
+        larger_than_mb: Some(1),
+        ..Default::default()
+    };
+    let candidates = find_candidates(tmp.path(), &policy, &NameTemplate::default());
+
+    assert_eq!(names(&candidates), vec!["big"]);
+    assert_eq!(candidates[0].size_mb, 2);
+}
+
+#[test]
+fn remove_candidate_moves_to_trash() {
+    let tmp = TempDir::new("prune-remove").unwrap();
+    let tries = tmp.path().join("tries");
+    fs::create_dir_all(tries.join("2020-01-01 old")).unwrap();
+    let trash = Trash::new(tmp.path().join("trash"));
+
+    let policy = PrunePolicy {
+        older_than_days: Some(1),
+        ..Default::default()
+    };
+    let candidate = &find_candidates(&tries, &policy, &NameTemplate::default())[0];
+    let outcome = remove_candidate(candidate, Some(&trash), None);
+
+    assert!(matches!(outcome, PruneOutcome::Trashed(_)));
+    assert!(!tries.join("2020-01-01 old").exists());
+    assert_eq!(trash.list().len(), 1);
+}
+
+#[test]
+fn remove_candidate_skips_locked_worktree() {
+    let tmp = TempDir::new("prune-locked").unwrap();
+    let tries = tmp.path().join("tries");
+    let worktree = tries.join("2020-01-01 feature");
+    let admin = tmp
+        .path()
+        .join("repo")
+        .join(".git")
+        .join("worktrees")
+        .join("feature");
+    fs::create_dir_all(&worktree).unwrap();
+    fs::create_dir_all(&admin).unwrap();
+    fs::write(admin.join("locked"), "").unwrap();
+    fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", admin.display()),
+    )
+    .unwrap();
+
+    let policy = PrunePolicy {
+        older_than_days: Some(1),
+        ..Default::default()
+    };
+    let candidate = &find_candidates(&tries, &policy, &NameTemplate::default())[0];
+    assert!(candidate.is_worktree);
+    assert!(candidate.is_worktree_locked);
+
+    let outcome = remove_candidate(candidate, None, None);
+    assert!(matches!(outcome, PruneOutcome::Skipped(_)));
+    assert!(worktree.exists());
+}
+
+#[test]
+fn remove_candidate_respects_pre_delete_veto() {
+    let tmp = TempDir::new("prune-veto").unwrap();
+    fs::create_dir(tmp.path().join("2020-01-01 keep")).unwrap();
+
+    let policy = PrunePolicy {
+        older_than_days: Some(1),
+        ..Default::default()
+    };
+    let candidate = &find_candidates(tmp.path(), &policy, &NameTemplate::default())[0];
+    let outcome = remove_candidate(candidate, None, Some("echo nope >&2; exit 1"));
+
+    match outcome {
+        PruneOutcome::Skipped(reason) => assert_eq!(reason, "pre_delete hook: nope"),
+        _ => panic!("expected the hook to veto the prune"),
+    }
+    assert!(tmp.path().join("2020-01-01 keep").exists());
+}
@@ -1,5 +1,4 @@
 use std::path::PathBuf;
-use tempdir::TempDir;
 use try_rs::cli::Shell;
 use try_rs::shell::*;
 
@@ -39,6 +38,31 @@ fn get_shell_content_nushell_contains_function() {
     assert!(content.contains("^try-rs"));
 }
 
+#[test]
+fn get_shell_content_elvish_contains_function() {
+    let content = get_shell_content(&Shell::Elvish);
+    assert!(content.contains("fn try-rs {|@args|"));
+    assert!(content.contains("e:try-rs"));
+    assert!(content.contains("edit:add-var try-rs~"));
+}
+
+#[test]
+fn get_shell_content_xonsh_contains_alias() {
+    let content = get_shell_content(&Shell::Xonsh);
+    assert!(content.contains("def _try_rs(args):"));
+    assert!(content.contains("aliases['try-rs'] = _try_rs"));
+}
+
+#[test]
+fn get_shell_content_sh_contains_function() {
+    let content = get_shell_content(&Shell::Sh);
+    assert!(content.contains("try_rs()"));
+    assert!(content.contains("alias try-rs=try_rs"));
+    assert!(content.contains("command try-rs"));
+    assert!(!content.contains("local "));
+    assert!(!content.contains("[["));
+}
+
 #[test]
 fn get_shell_content_includes_completions() {
     for shell in [
@@ -47,6 +71,9 @@ fn get_shell_content_includes_completions() {
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
     ] {
         let content = get_shell_content(&shell);
         assert!(
@@ -60,22 +87,22 @@ fn get_shell_content_includes_completions() {
 #[test]
 fn get_completions_script_fish_contains_complete() {
     let script = get_completions_script(&Shell::Fish);
-    assert!(script.contains("complete"));
-    assert!(script.contains("__try_rs"));
+    assert!(script.contains("complete -f -c try-rs"));
+    assert!(script.contains("command try-rs complete names"));
 }
 
 #[test]
 fn get_completions_script_zsh_contains_function() {
     let script = get_completions_script(&Shell::Zsh);
-    assert!(script.contains("_try_rs_get_tries_path"));
     assert!(script.contains("_try_rs_complete"));
+    assert!(script.contains("compdef"));
 }
 
 #[test]
 fn get_completions_script_bash_contains_function() {
     let script = get_completions_script(&Shell::Bash);
-    assert!(script.contains("_try_rs_get_tries_path"));
     assert!(script.contains("_try_rs_complete"));
+    assert!(script.contains("complete -o default"));
 }
 
 #[test]
@@ -88,26 +115,55 @@ fn get_completions_script_powershell_contains_register() {
 #[test]
 fn get_completions_script_nushell_contains_helpers() {
     let script = get_completions_script(&Shell::NuShell);
-    assert!(script.contains("__try_rs_get_tries_path"));
     assert!(script.contains("__try_rs_complete"));
+    assert!(script.contains("__try_rs_complete_branches"));
     assert!(!script.contains("export extern try-rs"));
 }
 
 #[test]
-fn get_completions_script_all_contain_try_path() {
+fn get_completions_script_elvish_contains_arg_completer() {
+    let script = get_completions_script(&Shell::Elvish);
+    assert!(script.contains("edit:completion:arg-completer[try-rs]"));
+    assert!(script.contains("e:try-rs complete $kind"));
+}
+
+#[test]
+fn get_completions_script_xonsh_contains_completer() {
+    let script = get_completions_script(&Shell::Xonsh);
+    assert!(script.contains("contextual_command_completer_for"));
+    assert!(script.contains("['try-rs', 'complete', kind]"));
+    for kind in ["branches", "names"] {
+        assert!(script.contains(kind));
+    }
+}
+
+#[test]
+fn get_completions_script_sh_is_a_comment() {
+    let script = get_completions_script(&Shell::Sh);
+    assert!(script.lines().all(|line| line.starts_with('#')));
+}
+
+#[test]
+fn get_completions_script_all_ask_the_binary() {
     for shell in [
         Shell::Fish,
         Shell::Zsh,
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
     ] {
         let script = get_completions_script(&shell);
-        assert!(
-            script.contains("TRY_PATH") || script.contains("tries_path"),
-            "Shell {:?} completions should reference TRY_PATH or tries_path",
-            shell
-        );
+        for kind in ["names", "branches"] {
+            assert!(
+                script.contains("try-rs") && script.contains(kind),
+                "Shell {:?} completions should list {} with `try-rs complete`",
+                shell,
+                kind
+            );
+        }
+        assert!(script.contains(" complete "), "{:?}", shell);
+        assert!(!script.contains("config.toml"), "{:?}", shell);
     }
 }
 
@@ -149,6 +205,15 @@ fn get_completion_script_only_nushell_is_different() {
     );
 }
 
+#[test]
+fn get_completion_script_only_new_shells_are_same() {
+    for shell in [Shell::Elvish, Shell::Xonsh, Shell::Sh] {
+        let only = get_completion_script_only(&shell);
+        let full = get_completions_script(&shell);
+        assert_eq!(only, full, "{:?}", shell);
+    }
+}
+
 #[test]
 fn get_shell_integration_path_fish() {
     let path = get_shell_integration_path(&Shell::Fish);
@@ -180,6 +245,24 @@ fn get_shell_integration_path_nushell() {
     assert!(path.to_string_lossy().contains("try-rs.nu"));
 }
 
+#[test]
+fn get_shell_integration_path_elvish_is_a_module() {
+    let path = get_shell_integration_path(&Shell::Elvish);
+    assert!(path.ends_with("elvish/lib/try-rs.elv"));
+}
+
+#[test]
+fn get_shell_integration_path_xonsh() {
+    let path = get_shell_integration_path(&Shell::Xonsh);
+    assert!(path.to_string_lossy().contains("try-rs.xsh"));
+}
+
+#[test]
+fn get_shell_integration_path_sh() {
+    let path = get_shell_integration_path(&Shell::Sh);
+    assert!(path.to_string_lossy().contains("try-rs.sh"));
+}
+
 #[test]
 fn generate_completions_outputs_script() {
     for shell in [
@@ -188,6 +271,9 @@ fn generate_completions_outputs_script() {
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
     ] {
         let result = generate_completions(&shell);
         assert!(
@@ -200,7 +286,13 @@ fn generate_completions_outputs_script() {
 
 #[test]
 fn shell_content_contains_eval_or_cd() {
-    for shell in [Shell::Fish, Shell::Zsh, Shell::Bash] {
+    for shell in [
+        Shell::Fish,
+        Shell::Zsh,
+        Shell::Bash,
+        Shell::Elvish,
+        Shell::Sh,
+    ] {
         let content = get_shell_content(&shell);
         assert!(
             content.contains("eval") || content.contains("cd "),
@@ -218,6 +310,9 @@ fn shell_content_handles_flags() {
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
     ] {
         let content = get_shell_content(&shell);
         assert!(
@@ -230,10 +325,10 @@ fn shell_content_handles_flags() {
 
 #[test]
 fn completions_contain_directory_listing() {
-    for shell in [Shell::Fish, Shell::Zsh, Shell::Bash] {
+    for shell in [Shell::Fish, Shell::Zsh, Shell::Bash, Shell::Elvish] {
         let script = get_completions_script(&shell);
         assert!(
-            script.contains("ls") || script.contains("dir") || script.contains("Directory"),
+            script.contains("try-rs complete"),
             "Shell {:?} completions should list directories",
             shell
         );
@@ -248,9 +343,12 @@ fn all_shells_have_unique_extensions() {
         Shell::Bash,
         Shell::PowerShell,
         Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
     ]
     .iter()
-    .map(|s| get_shell_integration_path(s))
+    .map(get_shell_integration_path)
     .collect();
 
     let extensions: Vec<String> = paths
@@ -266,3 +364,174 @@ fn all_shells_have_unique_extensions() {
         "All shells should have unique extensions"
     );
 }
+
+#[test]
+fn get_shell_content_passes_subcommands_through() {
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Sh] {
+        let content = get_shell_content(&shell);
+        assert!(content.contains("trash|"));
+        assert!(content.contains("|help) command try-rs \"$@\"; return ;;"));
+        assert!(content.contains("--) break ;;"));
+    }
+    assert!(get_shell_content(&Shell::Fish).contains("if test \"$arg\" = --"));
+    assert!(get_shell_content(&Shell::Fish).contains(" trash "));
+    assert!(get_shell_content(&Shell::PowerShell).contains("'trash', "));
+    assert!(get_shell_content(&Shell::NuShell).contains("\"trash\", "));
+    assert!(get_shell_content(&Shell::Elvish).contains(" trash "));
+    assert!(get_shell_content(&Shell::Xonsh).contains("'trash', "));
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_posix_shells() {
+    let path = PathBuf::from("/tries/it's $(rm -rf x) `id`");
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Sh] {
+        assert_eq!(
+            quote_path(&shell, &path),
+            r#"'/tries/it'\''s $(rm -rf x) `id`'"#
+        );
+    }
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_fish() {
+    let path = PathBuf::from(r"/tries/it's \$(rm)");
+    assert_eq!(quote_path(&Shell::Fish, &path), r"'/tries/it\'s \\$(rm)'");
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_powershell() {
+    let path = PathBuf::from("/tries/it's $(rm) \u{2019}x");
+    assert_eq!(
+        quote_path(&Shell::PowerShell, &path),
+        "'/tries/it''s $(rm) \u{2019}\u{2019}x'"
+    );
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_nushell() {
+    let path = PathBuf::from("/tries/say \"hi\" \\ $(rm)\n");
+    assert_eq!(
+        quote_path(&Shell::NuShell, &path),
+        r#""/tries/say \"hi\" \\ $(rm)\n""#
+    );
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_elvish() {
+    let path = PathBuf::from("/tries/it's $(rm) \\n");
+    assert_eq!(
+        quote_path(&Shell::Elvish, &path),
+        r"'/tries/it''s $(rm) \n'"
+    );
+}
+
+#[test]
+fn quote_path_escapes_hostile_names_for_xonsh() {
+    let path = PathBuf::from("/tries/it's @(rm) \\ $HOME\n\u{7}");
+    assert_eq!(
+        quote_path(&Shell::Xonsh, &path),
+        r"'/tries/it\'s @(rm) \\ $HOME\n\x07'"
+    );
+}
+
+#[test]
+fn cd_command_uses_each_shell_syntax() {
+    let path = PathBuf::from("/tries/a[1]");
+    assert_eq!(cd_command(&Shell::Bash, &path, None), "cd '/tries/a[1]'");
+    assert_eq!(
+        cd_command(&Shell::PowerShell, &path, None),
+        "Set-Location -LiteralPath '/tries/a[1]'"
+    );
+    assert_eq!(
+        cd_command(&Shell::NuShell, &path, Some("code")),
+        "code \"/tries/a[1]\""
+    );
+}
+
+#[test]
+fn wrappers_read_actions_from_the_binary() {
+    // How each wrapper spells the branch handling an action
+    let branches = [
+        (Shell::Fish, "case ACTION\n"),
+        (Shell::Zsh, "ACTION)"),
+        (Shell::Bash, "ACTION)"),
+        (Shell::PowerShell, "'ACTION' {"),
+        (Shell::NuShell, "\"ACTION\" =>"),
+        (Shell::Elvish, "(eq $fields[0] ACTION)"),
+        (Shell::Xonsh, "fields[0] == 'ACTION'"),
+        (Shell::Sh, "ACTION)"),
+    ];
+    for (shell, branch) in branches {
+        let content = get_shell_content(&shell);
+        assert!(content.contains("--actions"), "{:?}", shell);
+        for action in ["cd", "edit", "run", "mux"] {
+            assert!(
+                content.contains(&branch.replace("ACTION", action)),
+                "{:?}: {}",
+                shell,
+                action
+            );
+        }
+    }
+    let nushell = get_shell_content(&Shell::NuShell);
+    assert!(!nushell.contains(r#"str replace --all "'""#));
+    assert!(!nushell.contains(r#"str starts-with "cd ""#));
+}
+
+#[test]
+fn shell_content_includes_inline_picker_bindings() {
+    for (shell, binding) in [
+        (Shell::Zsh, "bindkey '^T' try-rs-picker"),
+        (Shell::Bash, r#"bind -x '"\C-t": try-rs-picker'"#),
+        (Shell::NuShell, r#"cmd: "try-rs-picker""#),
+    ] {
+        let content = get_shell_content(&shell);
+        // Only printed by --setup, Ctrl+T may already be taken (fzf)
+        assert!(!content.contains(binding), "{:?}", shell);
+        assert!(picker_binding(&shell).unwrap().contains(binding));
+        assert!(content.contains("TRY_RS_PICKER_HEIGHT"), "{:?}", shell);
+        assert!(content.contains("--inline-height"), "{:?}", shell);
+        assert_eq!(
+            content.matches("__try_rs_run_actions() {").count()
+                + content.matches("def --env __try_rs_run_actions").count(),
+            1,
+            "{:?}",
+            shell
+        );
+        assert!(content.contains("--inline-picker"), "{:?}", shell);
+    }
+    assert!(get_shell_content(&Shell::Zsh).contains("zle reset-prompt"));
+    assert!(
+        picker_binding(&Shell::Fish)
+            .unwrap()
+            .contains("bind \\ct try-rs-picker")
+    );
+    assert!(picker_binding(&Shell::PowerShell).is_none());
+}
+
+#[test]
+fn setup_clear_reports_the_files_setup_writes() {
+    let tmp = tempdir::TempDir::new("shell-paths").unwrap();
+    unsafe {
+        std::env::set_var("XDG_CONFIG_HOME", tmp.path().join("config"));
+        std::env::set_var("TRY_CONFIG_DIR", tmp.path().join("config").join("try-rs"));
+    }
+    // Fish is left out: its functions directory comes from fish itself
+    for shell in [
+        Shell::Zsh,
+        Shell::Bash,
+        Shell::PowerShell,
+        Shell::NuShell,
+        Shell::Elvish,
+        Shell::Xonsh,
+        Shell::Sh,
+    ] {
+        let written = write_shell_integration(&shell).unwrap();
+        assert!(written.starts_with(tmp.path()), "{}", written.display());
+        assert_eq!(get_shell_config_paths(&shell), [written]);
+    }
+    unsafe {
+        std::env::remove_var("XDG_CONFIG_HOME");
+        std::env::remove_var("TRY_CONFIG_DIR");
+    }
+}
@@ -0,0 +1,139 @@
+use std::fs;
+
+use tempdir::TempDir;
+use try_rs::templates::*;
+
+fn vars() -> TemplateVars {
+    TemplateVars {
+        name: "demo".to_string(),
+        date: "2025-01-15".to_string(),
+        author: "Ada".to_string(),
+    }
+}
+
+#[test]
+fn render_replaces_all_placeholders() {
+    let text = "name={{name}} date={{date}} author={{author}} again={{name}}";
+    assert_eq!(
+        render(text, &vars()),
+        "name=demo date=2025-01-15 author=Ada again=demo"
+    );
+}
+
+#[test]
+fn render_leaves_unknown_placeholders() {
+    assert_eq!(render("{{unknown}} {name}", &vars()), "{{unknown}} {name}");
+}
+
+#[test]
+fn list_templates_returns_sorted_directories() {
+    let tmp = TempDir::new("templates-list").unwrap();
+    fs::create_dir(tmp.path().join("rust")).unwrap();
+    fs::create_dir(tmp.path().join("python")).unwrap();
+    fs::write(tmp.path().join("not-a-template.txt"), "").unwrap();
+
+    assert_eq!(list_templates(tmp.path()), vec!["python", "rust"]);
+}
+
+#[test]
+fn list_templates_missing_dir_is_empty() {
+    let tmp = TempDir::new("templates-missing").unwrap();
+    assert!(list_templates(&tmp.path().join("nope")).is_empty());
+}
+
+#[test]
+fn apply_template_renders_names_and_contents() {
+    let tmp = TempDir::new("templates-apply").unwrap();
+    let template = tmp.path().join("templates").join("rust");
+    fs::create_dir_all(template.join("src")).unwrap();
+    fs::write(
+        template.join("Cargo.toml"),
+        "[package]\nname = \"{{name}}\"\nauthors = [\"{{author}}\"]\n",
+    )
+    .unwrap();
+    fs::write(template.join("src").join("{{name}}.rs"), "// {{date}}\n").unwrap();
+
+    let dest = tmp.path().join("out");
+    fs::create_dir(&dest).unwrap();
+    apply_template(&tmp.path().join("templates"), "rust", &dest, &vars()).unwrap();
+
+    let cargo = fs::read_to_string(dest.join("Cargo.toml")).unwrap();
+    assert!(cargo.contains("name = \"demo\""));
+    assert!(cargo.contains("authors = [\"Ada\"]"));
+    let module = fs::read_to_string(dest.join("src").join("demo.rs")).unwrap();
+    assert_eq!(module, "// 2025-01-15\n");
+}
+
+#[test]
+fn apply_template_copies_binary_files_verbatim() {
+    let tmp = TempDir::new("templates-binary").unwrap();
+    let template = tmp.path().join("bin");
+    fs::create_dir_all(&template).unwrap();
+    let bytes = vec![0xff, 0xfe, b'{', b'{', 0x00];
+    fs::write(template.join("blob.bin"), &bytes).unwrap();
+
+    let dest = tmp.path().join("out");
+    apply_template(tmp.path(), "bin", &dest, &vars()).unwrap();
+
+    assert_eq!(fs::read(dest.join("blob.bin")).unwrap(), bytes);
+}
+
+#[test]
+fn apply_template_unknown_name_lists_available() {
+    let tmp = TempDir::new("templates-unknown").unwrap();
+    fs::create_dir(tmp.path().join("go")).unwrap();
+
+    let err = apply_template(tmp.path(), "rust", &tmp.path().join("out"), &vars())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("'rust' not found"));
+    assert!(err.contains("go"));
+}
+
+#[test]
+fn apply_template_rejects_path_traversal() {
+    let tmp = TempDir::new("templates-traversal").unwrap();
+    fs::create_dir_all(tmp.path().join("templates")).unwrap();
+    fs::create_dir_all(tmp.path().join("secret")).unwrap();
+
+    let result = apply_template(
+        &tmp.path().join("templates"),
+        "../secret",
+        &tmp.path().join("out"),
+        &vars(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn apply_template_rejects_dot_names() {
+    let tmp = TempDir::new("templates-dots").unwrap();
+    fs::create_dir_all(tmp.path().join("templates").join("rust")).unwrap();
+    fs::write(tmp.path().join("config.toml"), "theme = \"Nord\"").unwrap();
+
+    for name in [".", "..", ""] {
+        let dest = tmp.path().join("out");
+        let result = apply_template(&tmp.path().join("templates"), name, &dest, &vars());
+        assert!(result.is_err(), "{name:?} was accepted");
+        assert!(!dest.exists());
+    }
+}
+
+#[test]
+fn apply_template_keeps_existing_files() {
+    let tmp = TempDir::new("templates-existing").unwrap();
+    let template = tmp.path().join("templates").join("rust");
+    fs::create_dir_all(template.join("src")).unwrap();
+    fs::write(template.join("Cargo.toml"), "name = \"{{name}}\"\n").unwrap();
+    fs::write(template.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+
+    let dest = tmp.path().join("out");
+    fs::create_dir_all(dest.join("src")).unwrap();
+    fs::write(dest.join("src").join("main.rs"), "// my work\n").unwrap();
+    apply_template(&tmp.path().join("templates"), "rust", &dest, &vars()).unwrap();
+
+    let main = fs::read_to_string(dest.join("src").join("main.rs")).unwrap();
+    assert_eq!(main, "// my work\n");
+    let cargo = fs::read_to_string(dest.join("Cargo.toml")).unwrap();
+    assert_eq!(cargo, "name = \"demo\"\n");
+}
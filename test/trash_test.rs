@@ -0,0 +1,121 @@
+use std::fs;
+
+use tempdir::TempDir;
+use try_rs::trash::*;
+
+fn setup(prefix: &str) -> (TempDir, Trash) {
+    let tmp = TempDir::new(prefix).unwrap();
+    let trash = Trash::new(tmp.path().join("trash"));
+    (tmp, trash)
+}
+
+#[test]
+fn move_to_trash_records_origin() {
+    let (tmp, trash) = setup("trash-move");
+    let folder = tmp.path().join("tries").join("demo");
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("notes.txt"), "keep me").unwrap();
+
+    let entry = trash.move_to_trash(&folder).unwrap();
+
+    assert!(!folder.exists());
+    assert_eq!(entry.name, "demo");
+    assert_eq!(entry.original_path, folder);
+    assert_eq!(trash.list(), vec![entry]);
+}
+
+#[test]
+fn restore_puts_folder_back() {
+    let (tmp, trash) = setup("trash-restore");
+    let folder = tmp.path().join("demo");
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("notes.txt"), "keep me").unwrap();
+
+    let entry = trash.move_to_trash(&folder).unwrap();
+    let restored = trash.restore(&entry).unwrap();
+
+    assert_eq!(restored, folder);
+    assert_eq!(
+        fs::read_to_string(folder.join("notes.txt")).unwrap(),
+        "keep me"
+    );
+    assert!(trash.list().is_empty());
+}
+
+#[test]
+fn restore_refuses_to_overwrite() {
+    let (tmp, trash) = setup("trash-overwrite");
+    let folder = tmp.path().join("demo");
+    fs::create_dir_all(&folder).unwrap();
+
+    let entry = trash.move_to_trash(&folder).unwrap();
+    fs::create_dir_all(&folder).unwrap();
+
+    assert!(trash.restore(&entry).is_err());
+    assert_eq!(trash.list().len(), 1);
+}
+
+#[test]
+fn same_name_gets_distinct_ids() {
+    let (tmp, trash) = setup("trash-ids");
+    let folder = tmp.path().join("demo");
+
+    fs::create_dir_all(&folder).unwrap();
+    let first = trash.move_to_trash(&folder).unwrap();
+    fs::create_dir_all(&folder).unwrap();
+    let second = trash.move_to_trash(&folder).unwrap();
+
+    assert_ne!(first.id, second.id);
+    assert_eq!(trash.list().len(), 2);
+}
+
+#[test]
+fn find_matches_id_or_name() {
+    let (tmp, trash) = setup("trash-find");
+    let folder = tmp.path().join("demo");
+    fs::create_dir_all(&folder).unwrap();
+    let entry = trash.move_to_trash(&folder).unwrap();
+
+    assert_eq!(trash.find(&entry.id), Some(entry.clone()));
+    assert_eq!(trash.find("demo"), Some(entry));
+    assert_eq!(trash.find("other"), None);
+}
+
+#[test]
+fn empty_removes_everything() {
+    let (tmp, trash) = setup("trash-empty");
+    for name in ["a", "b"] {
+        let folder = tmp.path().join(name);
+        fs::create_dir_all(&folder).unwrap();
+        trash.move_to_trash(&folder).unwrap();
+    }
+
+    assert_eq!(trash.empty().unwrap().len(), 2);
+    assert!(trash.list().is_empty());
+}
+
+#[test]
+fn purge_expired_respects_retention() {
+    let (tmp, trash) = setup("trash-purge");
+    let folder = tmp.path().join("old");
+    fs::create_dir_all(&folder).unwrap();
+    let entry = trash.move_to_trash(&folder).unwrap();
+
+    // Nothing is older than a day yet
+    assert!(trash.purge_expired(1).unwrap().is_empty());
+
+    // Backdate the entry by rewriting its metadata
+    let info = trash.dir().join("info").join(format!("{}.toml", entry.id));
+    let old = TrashEntry {
+        deleted_at: entry.deleted_at - 3 * 24 * 60 * 60,
+        ..entry
+    };
+    fs::write(&info, toml::to_string(&old).unwrap()).unwrap();
+
+    assert!(
+        trash.purge_expired(0).unwrap().is_empty(),
+        "0 keeps everything"
+    );
+    assert_eq!(trash.purge_expired(2).unwrap(), [old]);
+    assert!(trash.list().is_empty());
+}
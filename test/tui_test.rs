@@ -1,7 +1,14 @@
+mod common;
+
+use common::git;
 use std::time::SystemTime;
 use tempdir::TempDir;
+use try_rs::access::AccessLog;
+use try_rs::meta::MetaStore;
 use try_rs::themes::Theme;
-use try_rs::tui::{App, TryEntry};
+use try_rs::trash::Trash;
+use try_rs::tui::{App, AppMode, BatchAction, SortMode, TryEntry, WorktreeAction};
+use try_rs::utils::SelectionResult;
 
 #[test]
 fn try_entry_default_values() {
@@ -17,12 +24,13 @@ fn try_entry_default_values() {
         is_worktree: false,
         is_worktree_locked: false,
         is_gitmodules: false,
-        is_mise: false,
-        is_cargo: false,
-        is_maven: false,
-        is_flutter: false,
-        is_go: false,
-        is_python: false,
+        git_status: None,
+        project_types: vec![],
+        tags: vec![],
+        note: None,
+        pinned: false,
+        frecency: 0.0,
+        size_mb: None,
     };
 
     assert_eq!(entry.name, "test");
@@ -46,19 +54,20 @@ fn try_entry_clone() {
         is_worktree: false,
         is_worktree_locked: false,
         is_gitmodules: true,
-        is_mise: false,
-        is_cargo: true,
-        is_maven: false,
-        is_flutter: false,
-        is_go: false,
-        is_python: false,
+        git_status: None,
+        project_types: vec!["Rust".to_string()],
+        tags: vec![],
+        note: None,
+        pinned: false,
+        frecency: 0.0,
+        size_mb: None,
     };
 
     let cloned = entry.clone();
     assert_eq!(cloned.name, entry.name);
     assert_eq!(cloned.score, entry.score);
     assert_eq!(cloned.is_git, entry.is_git);
-    assert_eq!(cloned.is_cargo, entry.is_cargo);
+    assert_eq!(cloned.project_types, entry.project_types);
 }
 
 #[test]
@@ -75,18 +84,19 @@ fn try_entry_with_flags() {
         is_worktree: false,
         is_worktree_locked: false,
         is_gitmodules: false,
-        is_mise: true,
-        is_cargo: true,
-        is_maven: false,
-        is_flutter: false,
-        is_go: false,
-        is_python: false,
+        git_status: None,
+        project_types: vec!["Rust".to_string(), "Mise".to_string()],
+        tags: vec![],
+        note: None,
+        pinned: false,
+        frecency: 0.0,
+        size_mb: None,
     };
 
     assert!(entry.is_git);
-    assert!(entry.is_cargo);
-    assert!(entry.is_mise);
-    assert!(!entry.is_maven);
+    assert!(entry.project_types.contains(&"Rust".to_string()));
+    assert!(entry.project_types.contains(&"Mise".to_string()));
+    assert!(!entry.project_types.contains(&"Maven".to_string()));
 }
 
 #[test]
@@ -187,7 +197,7 @@ fn app_new_detects_cargo() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_cargo);
+    assert_eq!(app.all_entries[0].project_types, ["Rust"]);
 }
 
 #[test]
@@ -213,7 +223,7 @@ fn app_new_detects_python() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_python);
+    assert_eq!(app.all_entries[0].project_types, ["Python"]);
 }
 
 #[test]
@@ -248,7 +258,7 @@ fn app_new_with_query() {
     std::fs::create_dir(tmp.path().join("beta")).unwrap();
 
     let theme = Theme::default();
-let app = App::new(
+    let app = App::new(
         tmp.path().to_path_buf(),
         theme,
         None,
@@ -485,6 +495,440 @@ fn app_delete_selected_sets_status() {
     assert!(app.status_message.as_ref().unwrap().contains("Deleted"));
 }
 
+#[test]
+fn app_delete_vetoed_by_pre_delete_hook() {
+    let tmp = TempDir::new("app-delete-veto").unwrap();
+    std::fs::create_dir(tmp.path().join("keep-me")).unwrap();
+
+    let theme = Theme::default();
+    let mut app = App::new(
+        tmp.path().to_path_buf(),
+        theme,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tmp.path().to_path_buf()],
+        0,
+        true,
+    );
+    app.hooks.pre_delete = Some("echo \"$TRY_NAME is protected\" >&2; exit 1".to_string());
+
+    app.delete_selected();
+
+    assert!(tmp.path().join("keep-me").exists());
+    assert_eq!(app.all_entries.len(), 1);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Delete blocked by pre_delete hook: keep-me is protected")
+    );
+}
+
+#[test]
+fn app_delete_moves_to_trash_and_undo_restores() {
+    let tmp = TempDir::new("app-delete-trash").unwrap();
+    let tries = tmp.path().join("tries");
+    std::fs::create_dir_all(tries.join("precious")).unwrap();
+    std::fs::write(tries.join("precious").join("work.txt"), "a day of work").unwrap();
+
+    let theme = Theme::default();
+    let mut app = App::new(
+        tries.clone(),
+        theme,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tries.clone()],
+        0,
+        true,
+    );
+    app.trash = Some(Trash::new(tmp.path().join("trash")));
+
+    app.delete_selected();
+    assert!(!tries.join("precious").exists());
+    assert!(app.all_entries.is_empty());
+    assert_eq!(app.deleted.len(), 1);
+
+    app.undo_delete();
+    assert_eq!(
+        std::fs::read_to_string(tries.join("precious").join("work.txt")).unwrap(),
+        "a day of work"
+    );
+    assert_eq!(app.all_entries.len(), 1);
+    assert!(app.deleted.is_empty());
+}
+
+#[test]
+fn app_undo_without_deletes_reports_nothing() {
+    let tmp = TempDir::new("app-undo-empty").unwrap();
+    let theme = Theme::default();
+    let mut app = App::new(
+        tmp.path().to_path_buf(),
+        theme,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tmp.path().to_path_buf()],
+        0,
+        true,
+    );
+
+    app.undo_delete();
+
+    assert_eq!(app.status_message.as_deref(), Some("Nothing to undo"));
+}
+
+#[test]
+fn app_prune_review_prunes_only_selected() {
+    let tmp = TempDir::new("app-prune").unwrap();
+    let tries = tmp.path().join("tries");
+    std::fs::create_dir_all(tries.join("2020-01-01 old-a")).unwrap();
+    std::fs::create_dir_all(tries.join("2020-02-01 old-b")).unwrap();
+    std::fs::create_dir_all(tries.join("fresh")).unwrap();
+
+    let theme = Theme::default();
+    let mut app = App::new(
+        tries.clone(),
+        theme,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tries.clone()],
+        0,
+        true,
+    );
+    app.trash = Some(Trash::new(tmp.path().join("trash")));
+
+    app.open_prune_review();
+    assert_eq!(app.mode, AppMode::PruneReview);
+    assert_eq!(app.prune_candidates.len(), 2);
+    assert_eq!(app.prune_candidates[0].name, "2020-01-01 old-a");
+
+    // Keep the second candidate
+    app.prune_selected[1] = false;
+    app.confirm_prune();
+
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(!tries.join("2020-01-01 old-a").exists());
+    assert!(tries.join("2020-02-01 old-b").exists());
+    assert!(tries.join("fresh").exists());
+    assert_eq!(app.all_entries.len(), 2);
+    assert_eq!(app.deleted.len(), 1);
+    assert_eq!(app.status_message.as_deref(), Some("Pruned 1 tries"));
+}
+
+#[test]
+fn app_prune_review_with_nothing_to_prune() {
+    let tmp = TempDir::new("app-prune-none").unwrap();
+    std::fs::create_dir(tmp.path().join("fresh")).unwrap();
+
+    let theme = Theme::default();
+    let mut app = App::new(
+        tmp.path().to_path_buf(),
+        theme,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tmp.path().to_path_buf()],
+        0,
+        true,
+    );
+
+    app.open_prune_review();
+
+    assert_eq!(app.mode, AppMode::Normal);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Nothing to prune (older than 30d)")
+    );
+}
+
+fn app_with_meta(tmp: &TempDir, folders: &[&str]) -> App {
+    for folder in folders {
+        std::fs::create_dir_all(tmp.path().join("tries").join(folder)).unwrap();
+    }
+    let tries = tmp.path().join("tries");
+    let mut app = App::new(
+        tries.clone(),
+        Theme::default(),
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        vec![tries, tmp.path().join("elsewhere")],
+        0,
+        true,
+    );
+    app.set_meta(MetaStore::load(tmp.path().join("meta.toml")));
+    app
+}
+
+fn select(app: &mut App, name: &str) {
+    app.selected_index = app
+        .filtered_entries
+        .iter()
+        .position(|e| e.name == name)
+        .unwrap();
+}
+
+#[test]
+fn app_tags_are_searchable() {
+    let tmp = TempDir::new("app-tags").unwrap();
+    let mut app = app_with_meta(&tmp, &["alpha", "beta"]);
+
+    select(&mut app, "beta");
+    app.open_meta_prompt(AppMode::TagsPrompt);
+    app.meta_input = "rust, cli".to_string();
+    app.submit_meta_prompt();
+
+    app.query = "#ru".to_string();
+    app.update_search();
+    assert_eq!(app.filtered_entries.len(), 1);
+    assert_eq!(app.filtered_entries[0].name, "beta");
+    assert!(
+        !app.show_new_option,
+        "a tag filter is not a new folder name"
+    );
+
+    app.query = "#rust al".to_string();
+    app.update_search();
+    assert!(app.filtered_entries.is_empty());
+}
+
+#[test]
+fn app_pinned_entries_sort_first() {
+    let tmp = TempDir::new("app-pin").unwrap();
+    let mut app = app_with_meta(&tmp, &["alpha", "beta", "gamma"]);
+
+    select(&mut app, "gamma");
+    app.toggle_pin_selected();
+    assert_eq!(app.filtered_entries[0].name, "gamma");
+    assert_eq!(app.selected_index, 0);
+
+    app.query = "a".to_string();
+    app.update_search();
+    assert_eq!(app.filtered_entries[0].name, "gamma");
+}
+
+#[test]
+fn app_note_survives_rename_and_move() {
+    let tmp = TempDir::new("app-note").unwrap();
+    std::fs::create_dir_all(tmp.path().join("elsewhere")).unwrap();
+    let mut app = app_with_meta(&tmp, &["draft"]);
+
+    app.open_meta_prompt(AppMode::NotePrompt);
+    app.meta_input = "try the new allocator".to_string();
+    app.submit_meta_prompt();
+    assert_eq!(
+        app.filtered_entries[0].note.as_deref(),
+        Some("try the new allocator")
+    );
+
+    app.rename_input = "final".to_string();
+    app.rename_selected();
+    app.move_selected(1);
+
+    app.switch_tab(1);
+    assert_eq!(app.filtered_entries[0].name, "final");
+    assert_eq!(
+        app.filtered_entries[0].note.as_deref(),
+        Some("try the new allocator")
+    );
+}
+
+#[test]
+fn app_select_all_covers_filtered_entries_only() {
+    let tmp = TempDir::new("app-select-all").unwrap();
+    let mut app = app_with_meta(&tmp, &["api-one", "api-two", "web"]);
+
+    app.query = "api".to_string();
+    app.update_search();
+    app.toggle_select_all();
+    assert_eq!(app.selected.len(), 2);
+    assert!(!app.selected.contains("web"));
+
+    app.toggle_select_all();
+    assert!(app.selected.is_empty());
+}
+
+#[test]
+fn app_batch_delete_trashes_every_selected_entry() {
+    let tmp = TempDir::new("app-batch-delete").unwrap();
+    let mut app = app_with_meta(&tmp, &["one", "two", "keep"]);
+    app.trash = Some(Trash::new(tmp.path().join("trash")));
+
+    select(&mut app, "one");
+    app.toggle_selection();
+    select(&mut app, "two");
+    app.toggle_selection();
+    app.open_batch_confirm(BatchAction::Delete);
+    assert_eq!(app.mode, AppMode::BatchConfirm);
+    assert_eq!(app.batch_targets.len(), 2);
+
+    app.confirm_batch();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.selected.is_empty());
+    assert_eq!(app.all_entries.len(), 1);
+    assert_eq!(app.all_entries[0].name, "keep");
+    assert_eq!(app.deleted.len(), 2);
+    assert_eq!(app.status_message.as_deref(), Some("Trashed 2 folder(s)"));
+}
+
+#[test]
+fn app_batch_move_archive_and_tag() {
+    let tmp = TempDir::new("app-batch-move").unwrap();
+    let mut app = app_with_meta(&tmp, &["one", "two", "three"]);
+    app.archive_dir = Some(tmp.path().join("archive"));
+
+    app.toggle_select_all();
+    app.meta_input = "old".to_string();
+    app.open_meta_prompt(AppMode::TagsPrompt);
+    assert!(app.meta_input.is_empty());
+    app.meta_input = "old, batch".to_string();
+    app.submit_meta_prompt();
+    assert_eq!(
+        app.batch_action,
+        Some(BatchAction::Tag(vec![
+            "old".to_string(),
+            "batch".to_string()
+        ]))
+    );
+    app.confirm_batch();
+    assert!(app.all_entries.iter().all(|e| e.tags == ["old", "batch"]));
+
+    select(&mut app, "one");
+    app.toggle_selection();
+    app.open_batch_confirm(BatchAction::Archive);
+    app.confirm_batch();
+    assert!(tmp.path().join("archive").join("one").is_dir());
+
+    app.toggle_select_all();
+    app.open_batch_confirm(BatchAction::Move(1));
+    app.confirm_batch();
+    assert!(app.all_entries.is_empty());
+
+    app.switch_tab(1);
+    assert_eq!(app.all_entries.len(), 2);
+    assert!(app.all_entries.iter().all(|e| e.tags == ["old", "batch"]));
+}
+
+#[test]
+fn app_git_status_arrives_in_background() {
+    let tmp = TempDir::new("app-git-status").unwrap();
+    let repo = tmp.path().join("tries").join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    std::process::Command::new("git")
+        .args(["init", "-q", "-b", "main"])
+        .current_dir(&repo)
+        .status()
+        .unwrap();
+    std::fs::write(repo.join("wip.txt"), "unsaved").unwrap();
+
+    let mut app = app_with_meta(&tmp, &[]);
+    let start = std::time::Instant::now();
+    while !app.receive_git_status() && start.elapsed().as_secs() < 10 {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    let status = app.filtered_entries[0].git_status.clone().unwrap();
+    assert_eq!(status.branch.as_deref(), Some("main"));
+    assert_eq!(status.changed, 1);
+    assert!(status.upstream.is_none());
+}
+
+#[test]
+fn app_frecency_orders_entries_and_breaks_score_ties() {
+    let tmp = TempDir::new("app-frecency").unwrap();
+    let tries = tmp.path().join("tries");
+    let mut access = AccessLog::load(tmp.path().join("access.toml"));
+    let now = try_rs::access::now();
+    for _ in 0..3 {
+        access.record(&tries.join("api-old"), now).unwrap();
+    }
+    access.record(&tries.join("api-new"), now).unwrap();
+
+    let mut app = app_with_meta(&tmp, &["api-new", "api-old", "untouched"]);
+    app.access = access;
+    app.set_meta(MetaStore::default());
+    let names: Vec<_> = app
+        .filtered_entries
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(names, ["api-old", "api-new", "untouched"]);
+
+    app.query = "api".to_string();
+    app.update_search();
+    assert_eq!(app.filtered_entries[0].score, app.filtered_entries[1].score);
+    assert_eq!(app.filtered_entries[0].name, "api-old");
+}
+
+#[test]
+fn app_sort_modes_order_the_list() {
+    let tmp = TempDir::new("app-sort").unwrap();
+    let mut app = app_with_meta(
+        &tmp,
+        &["2024-03-01 beta", "2023-01-15 alpha", "2025-06-30 gamma"],
+    );
+    std::fs::write(
+        tmp.path()
+            .join("tries")
+            .join("2024-03-01 beta")
+            .join("big.bin"),
+        vec![0u8; 2 * 1024 * 1024],
+    )
+    .unwrap();
+    let names = |app: &App| -> Vec<String> {
+        app.filtered_entries
+            .iter()
+            .map(|e| e.display_name.clone())
+            .collect()
+    };
+
+    app.set_sort(SortMode::Created, true);
+    assert_eq!(names(&app), ["alpha", "beta", "gamma"]);
+    assert_eq!(app.sort_mode.describe(app.sort_reversed), "oldest first");
+
+    app.set_sort(SortMode::Name, true);
+    assert_eq!(names(&app), ["gamma", "beta", "alpha"]);
+
+    // Sizes arrive from a background worker, the list re-sorts with them
+    app.set_sort(SortMode::Size, false);
+    let start = std::time::Instant::now();
+    while !app.receive_sizes() && start.elapsed().as_secs() < 10 {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(names(&app)[0], "beta");
+    assert_eq!(app.filtered_entries[0].size_mb, Some(2));
+}
+
+#[test]
+fn sort_mode_names_and_cycle() {
+    assert_eq!(SortMode::from_name("Created"), Some(SortMode::Created));
+    assert_eq!(SortMode::from_name("shuffle"), None);
+    let mut mode = SortMode::default();
+    for _ in 0..SortMode::ALL.len() {
+        mode = mode.next();
+    }
+    assert_eq!(mode, SortMode::default());
+}
+
 #[test]
 fn app_has_all_themes() {
     let tmp = TempDir::new("app-themes").unwrap();
@@ -530,7 +974,7 @@ fn app_detects_mise() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_mise);
+    assert_eq!(app.all_entries[0].project_types, ["Mise"]);
 }
 
 #[test]
@@ -556,7 +1000,7 @@ fn app_detects_maven() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_maven);
+    assert_eq!(app.all_entries[0].project_types, ["Maven"]);
 }
 
 #[test]
@@ -582,7 +1026,7 @@ fn app_detects_go() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_go);
+    assert_eq!(app.all_entries[0].project_types, ["Go"]);
 }
 
 #[test]
@@ -608,7 +1052,7 @@ fn app_detects_flutter() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_flutter);
+    assert_eq!(app.all_entries[0].project_types, ["Flutter"]);
 }
 
 #[test]
@@ -634,7 +1078,7 @@ fn app_detects_python_requirements() {
     );
 
     assert_eq!(app.all_entries.len(), 1);
-    assert!(app.all_entries[0].is_python);
+    assert_eq!(app.all_entries[0].project_types, ["Python"]);
 }
 
 #[test]
@@ -798,3 +1242,160 @@ fn app_hide_new_option_on_empty_query() {
 
     assert!(!app.show_new_option);
 }
+
+#[test]
+fn app_rename_and_move_keep_worktrees_registered() {
+    let tmp = TempDir::new("app-worktree").unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&repo, &["worktree", "add", "-q", "../tries/feat"]);
+    let mut app = app_with_meta(&tmp, &[]);
+    assert!(app.filtered_entries[0].is_worktree);
+
+    app.rename_input = "feature".to_string();
+    app.rename_selected();
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Renamed 'feat' → 'feature'")
+    );
+    try_rs::utils::verify_git_worktree(&tmp.path().join("tries").join("feature")).unwrap();
+
+    app.move_selected(1);
+    let moved = tmp.path().join("elsewhere").join("feature");
+    assert!(moved.join(".git").is_file());
+    try_rs::utils::verify_git_worktree(&moved).unwrap();
+
+    app.switch_tab(1);
+    git(&repo, &["worktree", "lock", moved.to_str().unwrap()]);
+    app.rename_input = "locked".to_string();
+    app.rename_selected();
+    assert!(
+        app.status_message
+            .as_deref()
+            .is_some_and(|message| message.contains("locked git worktree")),
+        "{:?}",
+        app.status_message
+    );
+    assert!(moved.exists());
+}
+
+#[test]
+fn app_worktree_screen_locks_prunes_and_jumps() {
+    let tmp = TempDir::new("app-worktrees").unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&repo, &["worktree", "add", "-q", "../tries/feat"]);
+    git(&repo, &["worktree", "add", "-q", "../tries/gone"]);
+    std::fs::remove_dir_all(tmp.path().join("tries").join("gone")).unwrap();
+    let mut app = app_with_meta(&tmp, &[]);
+    app.worktrees = try_rs::worktrees::list_worktrees(&repo, &app.tries_dirs).unwrap();
+    app.worktree_repo = Some(repo);
+    app.worktree_list_state.select(Some(1));
+    app.mode = AppMode::Worktrees;
+
+    app.run_worktree_action(WorktreeAction::Lock);
+    assert_eq!(app.worktrees.len(), 3);
+    assert!(app.worktrees[1].is_try);
+    assert!(app.worktrees[1].locked.is_some());
+
+    app.run_worktree_action(WorktreeAction::Prune);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Pruned 1 stale worktree entries")
+    );
+    assert_eq!(app.worktrees.len(), 2);
+
+    app.enter_selected_worktree();
+    assert!(app.should_quit);
+    let SelectionResult::Folder(path) = &app.final_selection else {
+        panic!("expected a folder selection");
+    };
+    assert_eq!(
+        std::path::Path::new(path),
+        tmp.path().join("tries").join("feat")
+    );
+}
+
+#[test]
+fn app_save_theme_only_writes_changed_display_settings() {
+    let tmp = TempDir::new("app-save-theme").unwrap();
+    let mut app = app_with_meta(&tmp, &[]);
+    let config = tmp.path().join("config.toml");
+    std::fs::write(&config, "show_disk = true\n").unwrap();
+
+    // Started with the disk panel hidden by a flag, then toggled the legend
+    app.show_disk = false;
+    app.initial_display = app.display_settings();
+    app.show_legend = !app.show_legend;
+    app.save_theme(&config).unwrap();
+
+    let saved = std::fs::read_to_string(&config).unwrap();
+    assert!(saved.contains("show_disk = true"), "{saved}");
+    assert!(saved.contains(&format!("show_legend = {}", app.show_legend)));
+    assert!(saved.contains("theme = "));
+    for key in [
+        "transparent_background",
+        "show_preview",
+        "show_right_panel",
+        "right_panel_width",
+    ] {
+        assert!(!saved.contains(key), "{key} in {saved}");
+    }
+}
+
+#[test]
+fn app_save_theme_leaves_the_editor_and_tries_paths_alone() {
+    let tmp = TempDir::new("app-save-env").unwrap();
+    let config = tmp.path().join("config.toml");
+    std::fs::write(&config, "").unwrap();
+    unsafe {
+        std::env::set_var("TRY_CONFIG_DIR", tmp.path());
+        std::env::set_var("EDITOR", "vim");
+        std::env::remove_var("VISUAL");
+    }
+    let loaded = try_rs::config::load_configuration();
+    unsafe {
+        std::env::remove_var("TRY_CONFIG_DIR");
+        std::env::remove_var("EDITOR");
+    }
+    assert_eq!(loaded.editor_cmd.as_deref(), Some("vim"));
+
+    let mut app = App::new(
+        loaded.tries_dirs[0].clone(),
+        Theme::default(),
+        loaded.editor_cmd,
+        loaded.config_path,
+        loaded.apply_date_prefix,
+        loaded.date_prefix_format,
+        false,
+        None,
+        loaded.tries_dirs,
+        0,
+        false,
+    );
+    app.initial_display = app.display_settings();
+    app.save_theme(&config).unwrap();
+
+    let saved = std::fs::read_to_string(&config).unwrap();
+    assert_eq!(saved, "theme = \"Default\"\n");
+}
+
+#[test]
+fn app_match_indices_follow_a_name_first_template() {
+    let tmp = TempDir::new("app-name-first").unwrap();
+    let mut app = app_with_meta(&tmp, &["demo_20240605"]);
+    app.name_template = try_rs::naming::NameTemplate::parse("{name}_{date:%Y%m%d}").unwrap();
+    app.switch_tab(0);
+    assert_eq!(app.filtered_entries[0].display_name, "demo");
+
+    app.rename_input = "parser_20240605".to_string();
+    app.rename_selected();
+    app.query = "pars".to_string();
+    app.update_search();
+    assert_eq!(app.filtered_entries[0].display_name, "parser");
+    assert_eq!(app.filtered_entries[0].match_indices, [0, 1, 2, 3]);
+}
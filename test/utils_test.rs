@@ -1,7 +1,11 @@
+mod common;
+
 use std::path::{Path, PathBuf};
 
 use chrono::Local;
+use common::git;
 use tempdir::TempDir;
+use try_rs::naming::NameTemplate;
 use try_rs::utils::*;
 
 #[test]
@@ -69,50 +73,35 @@ fn extract_repo_name_fallback() {
 
 #[test]
 fn extract_prefix_date_valid() {
-    let result = extract_prefix_date("2024-06-15 my-project");
+    let result = extract_prefix_date("2024-06-15 my-project", &NameTemplate::default());
     assert!(result.is_some());
-    let (_, name) = result.unwrap();
+    let (_, name, _) = result.unwrap();
     assert_eq!(name, "my-project");
 }
 
 #[test]
 fn extract_prefix_date_invalid() {
-    assert!(extract_prefix_date("not-a-date project").is_none());
-    assert!(extract_prefix_date("nodate").is_none());
+    assert!(extract_prefix_date("not-a-date project", &NameTemplate::default()).is_none());
+    assert!(extract_prefix_date("nodate", &NameTemplate::default()).is_none());
 }
 
 #[test]
 fn extract_prefix_date_only_date_no_name() {
-    assert!(extract_prefix_date("2024-06-15").is_none());
+    assert!(extract_prefix_date("2024-06-15", &NameTemplate::default()).is_none());
 }
 
 #[test]
 fn extract_prefix_date_with_spaces_in_name() {
-    let result = extract_prefix_date("2024-01-01 my cool project");
+    let result = extract_prefix_date("2024-01-01 my cool project", &NameTemplate::default());
     assert!(result.is_some());
-    let (_, name) = result.unwrap();
+    let (_, name, _) = result.unwrap();
     assert_eq!(name, "my cool project");
 }
 
 #[test]
 fn extract_prefix_date_wrong_format() {
-    assert!(extract_prefix_date("01-01-2024 project").is_none());
-    assert!(extract_prefix_date("2024/01/01 project").is_none());
-}
-
-#[test]
-fn generate_prefix_date_format() {
-    let date = generate_prefix_date(None);
-    assert_eq!(date.len(), 10);
-    assert_eq!(&date[4..5], "-");
-    assert_eq!(&date[7..8], "-");
-}
-
-#[test]
-fn generate_prefix_date_matches_today() {
-    let date = generate_prefix_date(None);
-    let today = Local::now().format("%Y-%m-%d").to_string();
-    assert_eq!(date, today);
+    assert!(extract_prefix_date("01-01-2024 project", &NameTemplate::default()).is_none());
+    assert!(extract_prefix_date("2024/01/01 project", &NameTemplate::default()).is_none());
 }
 
 #[test]
@@ -148,7 +137,7 @@ fn matching_folders_exact_and_dated() {
     std::fs::create_dir(base.join("2024-01-15 foo")).unwrap();
     std::fs::create_dir(base.join("bar")).unwrap();
 
-    let matches = matching_folders("foo", &base.to_path_buf());
+    let matches = matching_folders("foo", &base.to_path_buf(), &NameTemplate::default());
     assert!(matches.iter().any(|m| m.1 == "foo"));
     assert!(matches.iter().any(|m| m.1 == "2024-01-15 foo"));
     assert!(!matches.iter().any(|m| m.1 == "bar"));
@@ -157,7 +146,11 @@ fn matching_folders_exact_and_dated() {
 #[test]
 fn matching_folders_empty_dir() {
     let tmp = TempDir::new("match-empty").unwrap();
-    let matches = matching_folders("anything", &tmp.path().to_path_buf());
+    let matches = matching_folders(
+        "anything",
+        &tmp.path().to_path_buf(),
+        &NameTemplate::default(),
+    );
     assert!(matches.is_empty());
 }
 
@@ -166,7 +159,7 @@ fn matching_folders_no_match() {
     let tmp = TempDir::new("match-none").unwrap();
     std::fs::create_dir(tmp.path().join("alpha")).unwrap();
     std::fs::create_dir(tmp.path().join("beta")).unwrap();
-    let matches = matching_folders("gamma", &tmp.path().to_path_buf());
+    let matches = matching_folders("gamma", &tmp.path().to_path_buf(), &NameTemplate::default());
     assert!(matches.is_empty());
 }
 
@@ -174,7 +167,7 @@ fn matching_folders_no_match() {
 fn matching_folders_ignores_files() {
     let tmp = TempDir::new("match-files").unwrap();
     std::fs::write(tmp.path().join("foo"), "not a dir").unwrap();
-    let matches = matching_folders("foo", &tmp.path().to_path_buf());
+    let matches = matching_folders("foo", &tmp.path().to_path_buf(), &NameTemplate::default());
     assert!(matches.is_empty());
 }
 
@@ -184,14 +177,18 @@ fn matching_folders_multiple_dated() {
     std::fs::create_dir(tmp.path().join("2024-01-01 proj")).unwrap();
     std::fs::create_dir(tmp.path().join("2024-06-15 proj")).unwrap();
     std::fs::create_dir(tmp.path().join("other")).unwrap();
-    let matches = matching_folders("proj", &tmp.path().to_path_buf());
+    let matches = matching_folders("proj", &tmp.path().to_path_buf(), &NameTemplate::default());
     assert_eq!(matches.len(), 2);
     assert!(matches.iter().all(|m| m.1.contains("proj")));
 }
 
 #[test]
 fn matching_folders_nonexistent_path() {
-    let matches = matching_folders("foo", &PathBuf::from("/nonexistent/dir"));
+    let matches = matching_folders(
+        "foo",
+        &PathBuf::from("/nonexistent/dir"),
+        &NameTemplate::default(),
+    );
     assert!(matches.is_empty());
 }
 
@@ -360,26 +357,26 @@ fn expand_path_empty_string() {
 
 #[test]
 fn extract_prefix_date_with_different_dates() {
-    let result = extract_prefix_date("2023-12-25 christmas-project");
+    let result = extract_prefix_date("2023-12-25 christmas-project", &NameTemplate::default());
     assert!(result.is_some());
-    let (_, name) = result.unwrap();
+    let (_, name, _) = result.unwrap();
     assert_eq!(name, "christmas-project");
 }
 
 #[test]
 fn extract_prefix_date_invalid_formats() {
-    assert!(extract_prefix_date("2023-13-01 invalid-month").is_none());
-    assert!(extract_prefix_date("2023-00-15 invalid-day").is_none());
+    assert!(extract_prefix_date("2023-13-01 invalid-month", &NameTemplate::default()).is_none());
+    assert!(extract_prefix_date("2023-00-15 invalid-day", &NameTemplate::default()).is_none());
     // Note: "23-01-01" might be parsed as year 23 (0023), which is technically valid
     // We focus on clearly invalid dates
-    assert!(extract_prefix_date("not-a-date project").is_none());
-    assert!(extract_prefix_date("01-01-2023 wrong-format").is_none());
+    assert!(extract_prefix_date("not-a-date project", &NameTemplate::default()).is_none());
+    assert!(extract_prefix_date("01-01-2023 wrong-format", &NameTemplate::default()).is_none());
 }
 
 #[test]
 fn extract_prefix_date_edge_cases() {
-    assert!(extract_prefix_date("2024-02-29 leap-year").is_some());
-    assert!(extract_prefix_date("2023-02-29 not-leap-year").is_none());
+    assert!(extract_prefix_date("2024-02-29 leap-year", &NameTemplate::default()).is_some());
+    assert!(extract_prefix_date("2023-02-29 not-leap-year", &NameTemplate::default()).is_none());
 }
 
 #[test]
@@ -388,7 +385,11 @@ fn matching_folders_with_special_chars() {
     std::fs::create_dir(tmp.path().join("my-project_v2")).unwrap();
     std::fs::create_dir(tmp.path().join("2024-01-15 my-project_v2")).unwrap();
 
-    let matches = matching_folders("my-project_v2", &tmp.path().to_path_buf());
+    let matches = matching_folders(
+        "my-project_v2",
+        &tmp.path().to_path_buf(),
+        &NameTemplate::default(),
+    );
     assert_eq!(matches.len(), 2);
 }
 
@@ -399,7 +400,7 @@ fn matching_folders_nested_search() {
     std::fs::create_dir_all(&nested).unwrap();
 
     // matching_folders only searches immediate children
-    let matches = matching_folders("child", &tmp.path().to_path_buf());
+    let matches = matching_folders("child", &tmp.path().to_path_buf(), &NameTemplate::default());
     assert!(matches.is_empty());
 }
 
@@ -467,14 +468,71 @@ fn selection_result_variants() {
 }
 
 #[test]
-fn generate_prefix_date_consistency() {
-    let date1 = generate_prefix_date(None);
-    let date2 = generate_prefix_date(None);
-    // Same call should produce same result
-    assert_eq!(date1, date2);
+fn parse_git_status_reads_branch_and_counts() {
+    let output = "# branch.oid 1234abcd\n\
+# branch.head feature\n\
+# branch.upstream origin/feature\n\
+# branch.ab +2 -1\n\
+1 .M N... 100644 100644 100644 aaaa bbbb src/main.rs\n\
+? notes.txt\n";
+    let status = parse_git_status(output);
+    assert_eq!(status.branch.as_deref(), Some("feature"));
+    assert_eq!(status.upstream.as_deref(), Some("origin/feature"));
+    assert_eq!((status.ahead, status.behind), (2, 1));
+    assert_eq!(status.changed, 2);
+    assert!(status.is_dirty());
+}
+
+#[test]
+fn parse_git_status_detached_and_clean() {
+    let status = parse_git_status("# branch.oid 1234abcd\n# branch.head (detached)\n");
+    assert_eq!(status, GitStatus::default());
+}
+
+#[test]
+fn matching_folders_follow_the_name_template() {
+    let tmp = TempDir::new("match-template").unwrap();
+    std::fs::create_dir(tmp.path().join("20240115_foo")).unwrap();
+    std::fs::create_dir(tmp.path().join("2024-01-15 foo")).unwrap();
+
+    let template = NameTemplate::parse("{date:%Y%m%d}_{name}").unwrap();
+    let matches = matching_folders("foo", &tmp.path().to_path_buf(), &template);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].1, "20240115_foo");
+}
+
+#[test]
+fn extract_prefix_date_uses_the_template_format() {
+    let template = NameTemplate::from_config(None, Some(true), Some("%d.%m.%Y")).unwrap();
+    let (date, name, offset) = extract_prefix_date("15.06.2024 my project", &template).unwrap();
+    let date: chrono::DateTime<Local> = date.into();
+    assert_eq!(date.format("%Y-%m-%d").to_string(), "2024-06-15");
+    assert_eq!(name, "my project");
+    assert_eq!(offset, 11);
+    assert!(extract_prefix_date("2024-06-15 my project", &template).is_none());
+}
+
+#[test]
+fn verify_git_worktree_catches_a_plain_rename() {
+    let tmp = TempDir::new("worktree-verify").unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&repo, &["worktree", "add", "-q", "../feat"]);
+
+    let feat = tmp.path().join("feat");
+    verify_git_worktree(&feat).unwrap();
+    let renamed = tmp.path().join("renamed");
+    std::fs::rename(&feat, &renamed).unwrap();
+    assert!(verify_git_worktree(&renamed).is_err());
+    std::fs::rename(&renamed, &feat).unwrap();
+
+    move_try(&feat, &renamed).unwrap();
+    verify_git_worktree(&renamed).unwrap();
 
-    // Check format is YYYY-MM-DD
-    assert_eq!(date1.len(), 10);
-    assert!(date1.chars().nth(4).unwrap() == '-');
-    assert!(date1.chars().nth(7).unwrap() == '-');
+    git(&repo, &["worktree", "lock", "../renamed"]);
+    let err = move_try(&renamed, &feat).unwrap_err();
+    assert!(err.to_string().contains("locked git worktree"), "{err}");
+    assert!(renamed.exists());
 }
@@ -0,0 +1,240 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::git;
+use tempdir::TempDir;
+use try_rs::naming::NameTemplate;
+use try_rs::worktrees::*;
+
+/// A repository with the worktree `tries/feat` on branch `feat`.
+fn repo_with_worktree(tmp: &TempDir) -> (PathBuf, PathBuf) {
+    let repo = tmp.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&repo, &["worktree", "add", "-q", "../tries/feat"]);
+    (repo, tmp.path().join("tries"))
+}
+
+#[test]
+fn parse_worktree_list_reads_every_field() {
+    let porcelain = "worktree /src/repo\nHEAD 1111\nbranch refs/heads/main\n\n\
+        worktree /tries/feat\nHEAD 2222\nbranch refs/heads/feat/login\nlocked on a usb drive\n\n\
+        worktree /tries/gone\nHEAD 3333\ndetached\nlocked\nprunable gitdir file points to non-existent location\n";
+    let worktrees = parse_worktree_list(porcelain);
+
+    assert_eq!(worktrees.len(), 3);
+    assert!(worktrees[0].is_main);
+    assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+    assert_eq!(worktrees[0].state_label(), "ok");
+
+    assert!(!worktrees[1].is_main);
+    assert_eq!(worktrees[1].path, PathBuf::from("/tries/feat"));
+    assert_eq!(worktrees[1].head.as_deref(), Some("2222"));
+    assert_eq!(worktrees[1].branch_label(), "feat/login");
+    assert_eq!(worktrees[1].locked.as_deref(), Some("on a usb drive"));
+
+    assert!(worktrees[2].detached);
+    assert_eq!(worktrees[2].branch_label(), "(detached)");
+    assert_eq!(worktrees[2].locked.as_deref(), Some(""));
+    assert_eq!(worktrees[2].state_label(), "locked,prunable");
+}
+
+#[test]
+fn list_worktrees_marks_tries_and_missing_folders() {
+    let tmp = TempDir::new("worktrees-list").unwrap();
+    let (repo, tries) = repo_with_worktree(&tmp);
+    git(&repo, &["worktree", "add", "-q", "../outside"]);
+    fs::remove_dir_all(tmp.path().join("outside")).unwrap();
+
+    // Listed the same from the worktree as from the main repository.
+    let worktrees = list_worktrees(&tries.join("feat"), std::slice::from_ref(&tries)).unwrap();
+    let kinds: Vec<(&str, String)> = worktrees
+        .iter()
+        .map(|w| (w.kind_label(), w.state_label()))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("main", "ok".to_string()),
+            ("worktree", "missing,prunable".to_string()),
+            ("try", "ok".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn find_matches_paths_names_and_branches() {
+    let tmp = TempDir::new("worktrees-find").unwrap();
+    let (repo, tries) = repo_with_worktree(&tmp);
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "fix",
+            "../tries/2024-06-15 fix",
+        ],
+    );
+    let worktrees = list_worktrees(&repo, std::slice::from_ref(&tries)).unwrap();
+    let template = NameTemplate::default();
+    let found = |query: &str| {
+        find(&worktrees, query, &template).map(|w| w.path.file_name().unwrap().to_owned())
+    };
+
+    assert_eq!(found("feat"), Some("feat".into()));
+    assert_eq!(found("fix"), Some("2024-06-15 fix".into()));
+    assert_eq!(
+        found(tries.join("feat").to_str().unwrap()),
+        Some("feat".into())
+    );
+    assert_eq!(found("nope"), None);
+}
+
+#[test]
+fn lock_unlock_remove_and_prune_go_through_git() {
+    let tmp = TempDir::new("worktrees-actions").unwrap();
+    let (repo, tries) = repo_with_worktree(&tmp);
+    let dirs = std::slice::from_ref(&tries);
+    let worktree = |repo: &Path| list_worktrees(repo, dirs).unwrap()[1].clone();
+
+    lock(&repo, &worktree(&repo), Some("keep")).unwrap();
+    assert_eq!(worktree(&repo).locked.as_deref(), Some("keep"));
+    assert!(remove(&repo, &worktree(&repo), false).is_err());
+    unlock(&repo, &worktree(&repo)).unwrap();
+    assert_eq!(worktree(&repo).locked, None);
+
+    let main = list_worktrees(&repo, dirs).unwrap()[0].clone();
+    assert!(remove(&repo, &main, true).is_err());
+
+    // Deleted outside of git: only the metadata is left, until pruned.
+    fs::remove_dir_all(tries.join("feat")).unwrap();
+    assert!(worktree(&repo).missing);
+    assert_eq!(prune(&repo, true).unwrap().len(), 1);
+    assert_eq!(list_worktrees(&repo, dirs).unwrap().len(), 2);
+    assert_eq!(prune(&repo, false).unwrap().len(), 1);
+    assert_eq!(list_worktrees(&repo, dirs).unwrap().len(), 1);
+    assert!(prune(&repo, false).unwrap().is_empty());
+}
+
+#[test]
+fn branch_folder_name_replaces_path_separators() {
+    assert_eq!(branch_folder_name("feature/x"), "feature-x");
+    assert_eq!(branch_folder_name("fix\\windows:path"), "fix-windows-path");
+    assert_eq!(branch_folder_name("plain"), "plain");
+}
+
+#[test]
+fn branch_source_prefers_local_then_base_then_remote() {
+    let tmp = TempDir::new("worktrees-source").unwrap();
+    let (origin, _) = repo_with_worktree(&tmp);
+    git(&origin, &["branch", "feature/x"]);
+    git(tmp.path(), &["clone", "-q", "repo", "clone"]);
+    let clone = tmp.path().join("clone");
+
+    assert_eq!(
+        branch_source(&clone, "feature/x", None).unwrap(),
+        BranchSource::Remote("origin/feature/x".to_string())
+    );
+    assert_eq!(
+        branch_source(&clone, "feature/x", Some("HEAD~0")).unwrap(),
+        BranchSource::New(Some("HEAD~0".to_string()))
+    );
+    assert_eq!(
+        branch_source(&clone, "brand-new", None).unwrap(),
+        BranchSource::New(None)
+    );
+    git(&clone, &["branch", "local"]);
+    assert_eq!(
+        branch_source(&clone, "local", Some("main")).unwrap(),
+        BranchSource::Local
+    );
+
+    let path = Path::new("/tries/feature-x");
+    assert_eq!(
+        worktree_add_args(
+            "feature/x",
+            path,
+            &BranchSource::Remote("origin/feature/x".to_string())
+        ),
+        [
+            "worktree",
+            "add",
+            "--track",
+            "-b",
+            "feature/x",
+            "/tries/feature-x",
+            "origin/feature/x"
+        ]
+    );
+    assert_eq!(
+        worktree_add_args("x", path, &BranchSource::New(Some("v1".to_string()))),
+        ["worktree", "add", "-b", "x", "/tries/feature-x", "v1"]
+    );
+    assert_eq!(
+        worktree_add_args("x", path, &BranchSource::Local),
+        ["worktree", "add", "/tries/feature-x", "x"]
+    );
+}
+
+#[test]
+fn copy_into_worktree_copies_matches_the_worktree_lacks() {
+    let tmp = TempDir::new("worktrees-copy").unwrap();
+    let (repo, tries) = repo_with_worktree(&tmp);
+    let worktree = tries.join("feat");
+    fs::write(repo.join(".env"), "A=1").unwrap();
+    fs::write(repo.join(".envrc"), "use flake").unwrap();
+    fs::create_dir_all(repo.join("config")).unwrap();
+    fs::write(repo.join("config").join("local.toml"), "x = 1").unwrap();
+    fs::write(worktree.join(".envrc"), "kept").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("/nonexistent/node_modules", repo.join("node_modules")).unwrap();
+
+    assert_eq!(main_worktree(&worktree).unwrap(), repo);
+    let patterns = [".env*", "config/*.toml", "node_modules", "*"].map(String::from);
+    let mut copied = copy_into_worktree(&repo, &worktree, &patterns).unwrap();
+    copied.sort();
+
+    // `*` comes last: `config` and `.env` are already there, and `.git` is skipped.
+    let mut expected = vec![PathBuf::from(".env"), PathBuf::from("config/local.toml")];
+    if cfg!(unix) {
+        expected.push(PathBuf::from("node_modules"));
+    }
+    assert_eq!(copied, expected);
+    assert_eq!(fs::read_to_string(worktree.join(".envrc")).unwrap(), "kept");
+    assert_eq!(fs::read_to_string(worktree.join(".env")).unwrap(), "A=1");
+    #[cfg(unix)]
+    assert!(worktree.join("node_modules").is_symlink());
+}
+
+#[test]
+fn copy_into_worktree_stays_inside_the_worktree() {
+    let tmp = TempDir::new("worktrees-escape").unwrap();
+    let (repo, tries) = repo_with_worktree(&tmp);
+    let worktree = tries.join("feat");
+    fs::write(tmp.path().join("outside.txt"), "secret").unwrap();
+
+    assert!(copy_pattern_escapes("../*.txt"));
+    assert!(copy_pattern_escapes("/etc/passwd"));
+    assert!(!copy_pattern_escapes("./config/*.toml"));
+    assert!(copy_into_worktree(&repo, &worktree, &["../*.txt".to_string()]).is_err());
+    assert!(!tries.join("outside.txt").exists());
+
+    // A folder of the worktree that links elsewhere is not written through
+    #[cfg(unix)]
+    {
+        let elsewhere = tmp.path().join("elsewhere");
+        fs::create_dir_all(&elsewhere).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, worktree.join("config")).unwrap();
+        fs::create_dir_all(repo.join("config")).unwrap();
+        fs::write(repo.join("config").join("local.toml"), "x = 1").unwrap();
+
+        let copied = copy_into_worktree(&repo, &worktree, &["config/*".to_string()]).unwrap();
+        assert!(copied.is_empty());
+        assert!(!elsewhere.join("local.toml").exists());
+    }
+}